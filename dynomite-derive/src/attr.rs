@@ -1,8 +1,10 @@
 //! dynomite field attributes
 
+use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use syn::{
-    parse::{Parse, ParseStream},
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
     Ident, LitStr, Path, Token,
 };
 
@@ -17,6 +19,8 @@ pub(crate) struct Attr<Kind> {
 
 /// Attribute that appears on record fields (struct fields and enum record variant fields)
 pub(crate) type FieldAttr = Attr<FieldAttrKind>;
+/// Attribute that appears on the top level of a struct
+pub(crate) type StructAttr = Attr<StructAttrKind>;
 /// Attribute that appears on the top level of an enum
 pub(crate) type EnumAttr = Attr<EnumAttrKind>;
 /// Attribute that appears on enum varinats
@@ -27,6 +31,13 @@ pub(crate) enum FieldAttrKind {
     /// Denotes field should be replaced with Default impl when absent in ddb
     Default,
 
+    /// Denotes field should be replaced with the result of calling the function at
+    /// the given path when absent in ddb
+    DefaultWith(Path),
+
+    /// Denotes an `Option` field which should be `None` when absent in ddb
+    Optional,
+
     /// Denotes field should be renamed to value of ListStr
     Rename(LitStr),
 
@@ -42,23 +53,213 @@ pub(crate) enum FieldAttrKind {
     /// Denotes a field that should not be present in the resulting `Attributes` map
     /// if the given function returns `true` for its value
     SkipSerializingIf(Path),
+
+    /// Denotes a field that is part of a global secondary index key
+    Gsi(Gsi),
+
+    /// Denotes a binary field that should be stored as a base64 encoded string
+    Base64,
+
+    /// Denotes a field that may be read from either of the `S` or `N` types
+    Lenient,
+
+    /// Denotes a field holding the version of an item, used for optimistic locking
+    Version,
+
+    /// Denotes a flattened array field whose elements' keys should be prefixed
+    /// with the value of LitStr followed by the element's index
+    Prefix(LitStr),
 }
 
 impl DynomiteAttr for FieldAttrKind {
     const KVS: Kvs<Self> = &[
         ("rename", |lit| Ok(FieldAttrKind::Rename(lit))),
+        ("prefix", |lit| Ok(FieldAttrKind::Prefix(lit))),
+        ("default", |lit| lit.parse().map(FieldAttrKind::DefaultWith)),
         ("skip_serializing_if", |lit| {
             lit.parse().map(FieldAttrKind::SkipSerializingIf)
         }),
     ];
     const KEYS: Keys<Self> = &[
         ("default", FieldAttrKind::Default),
+        ("optional", FieldAttrKind::Optional),
         ("partition_key", FieldAttrKind::PartitionKey),
         ("sort_key", FieldAttrKind::SortKey),
         ("flatten", FieldAttrKind::Flatten),
+        ("base64", FieldAttrKind::Base64),
+        ("lenient", FieldAttrKind::Lenient),
+        ("version", FieldAttrKind::Version),
+    ];
+    const LISTS: Lists<Self> = &[("gsi", |tokens| Gsi::parse(tokens).map(FieldAttrKind::Gsi))];
+}
+
+/// The role a field plays in a global secondary index key
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum KeyKind {
+    Partition,
+    Sort,
+}
+
+/// A field's membership in a global secondary index, declared
+/// as `#[dynomite(gsi(name = "IndexName", partition_key))]`
+#[derive(Clone)]
+pub(crate) struct Gsi {
+    /// The name of the index
+    pub(crate) name: LitStr,
+    /// Whether the field is the partition or sort key of the index
+    pub(crate) key: KeyKind,
+}
+
+#[derive(Clone)]
+enum GsiAttrKind {
+    Name(LitStr),
+    PartitionKey,
+    SortKey,
+}
+
+impl DynomiteAttr for GsiAttrKind {
+    const KVS: Kvs<Self> = &[("name", |lit| Ok(GsiAttrKind::Name(lit)))];
+    const KEYS: Keys<Self> = &[
+        ("partition_key", GsiAttrKind::PartitionKey),
+        ("sort_key", GsiAttrKind::SortKey),
     ];
 }
 
+impl Gsi {
+    fn parse(tokens: TokenStream) -> syn::Result<Self> {
+        let span = tokens.clone();
+        let attrs = Punctuated::<Attr<GsiAttrKind>, Token![,]>::parse_terminated.parse2(tokens)?;
+        let mut name = None;
+        let mut key = None;
+        for attr in attrs {
+            match attr.kind {
+                GsiAttrKind::Name(lit) => name = Some(lit),
+                GsiAttrKind::PartitionKey | GsiAttrKind::SortKey if key.is_some() => abort!(
+                    attr.ident,
+                    "a field may only be one of `partition_key` or `sort_key` of an index"
+                ),
+                GsiAttrKind::PartitionKey => key = Some(KeyKind::Partition),
+                GsiAttrKind::SortKey => key = Some(KeyKind::Sort),
+            }
+        }
+        match (name, key) {
+            (Some(name), Some(key)) => Ok(Gsi { name, key }),
+            (None, _) => Err(syn::Error::new_spanned(
+                span,
+                "expected an index name: `gsi(name = \"IndexName\", ...)`",
+            )),
+            (_, None) => Err(syn::Error::new_spanned(
+                span,
+                "expected one of `partition_key` or `sort_key`: `gsi(name = \"IndexName\", partition_key)`",
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum StructAttrKind {
+    /// Denotes a function to call with `&mut Self` once all fields have been read from ddb
+    Finalize(Path),
+
+    /// Denotes a function to call with `&mut Self` before any fields are written to ddb
+    Prepare(Path),
+
+    /// Denotes a single field struct represented in the same way as its field
+    Transparent,
+
+    /// Denotes a struct whose fields should take their values from its Default impl when absent in ddb
+    Default,
+
+    /// Denotes a struct which should fail to be read from attributes which remain once all of its fields are read
+    DenyUnknownFields,
+
+    /// Denotes a struct which should also be resolvable along with a record of which of its
+    /// optional fields were present in ddb
+    Presence,
+
+    /// Denotes a struct represented by a single string composed of its fields according
+    /// to the pattern in LitStr, e.g. `ORDER#{year}#{seq}`
+    Compose(LitStr),
+
+    /// Additional traits to derive for an Item's generated key struct, e.g. `Hash` and `Eq`
+    KeyDerive(Vec<Path>),
+
+    /// Denotes an Item sort key which is computed from the struct's fields rather than stored in one
+    ComputedSortKey(ComputedSortKey),
+}
+
+impl DynomiteAttr for StructAttrKind {
+    const KVS: Kvs<Self> = &[
+        ("finalize", |lit| lit.parse().map(StructAttrKind::Finalize)),
+        ("prepare", |lit| lit.parse().map(StructAttrKind::Prepare)),
+        ("compose", |lit| Ok(StructAttrKind::Compose(lit))),
+    ];
+    const KEYS: Keys<Self> = &[
+        ("transparent", StructAttrKind::Transparent),
+        ("default", StructAttrKind::Default),
+        ("presence", StructAttrKind::Presence),
+        ("deny_unknown_fields", StructAttrKind::DenyUnknownFields),
+    ];
+    const LISTS: Lists<Self> = &[
+        ("key_derive", |tokens| {
+            Punctuated::<Path, Token![,]>::parse_terminated
+                .parse2(tokens)
+                .map(|paths| StructAttrKind::KeyDerive(paths.into_iter().collect()))
+        }),
+        ("sort_key", |tokens| {
+            ComputedSortKey::parse(tokens).map(StructAttrKind::ComputedSortKey)
+        }),
+    ];
+}
+
+/// An Item sort key computed by a function, declared on the struct
+/// as `#[dynomite(sort_key(name = "sk", compute = "path"))]`
+#[derive(Clone)]
+pub(crate) struct ComputedSortKey {
+    /// The name of the sort key attribute
+    pub(crate) name: LitStr,
+    /// A function with the signature `fn(&Self) -> String` which computes the sort key
+    pub(crate) compute: Path,
+}
+
+#[derive(Clone)]
+enum ComputedSortKeyAttrKind {
+    Name(LitStr),
+    Compute(Path),
+}
+
+impl DynomiteAttr for ComputedSortKeyAttrKind {
+    const KVS: Kvs<Self> = &[
+        ("name", |lit| Ok(ComputedSortKeyAttrKind::Name(lit))),
+        ("compute", |lit| {
+            lit.parse().map(ComputedSortKeyAttrKind::Compute)
+        }),
+    ];
+}
+
+impl ComputedSortKey {
+    fn parse(tokens: TokenStream) -> syn::Result<Self> {
+        let span = tokens.clone();
+        let attrs = Punctuated::<Attr<ComputedSortKeyAttrKind>, Token![,]>::parse_terminated
+            .parse2(tokens)?;
+        let mut name = None;
+        let mut compute = None;
+        for attr in attrs {
+            match attr.kind {
+                ComputedSortKeyAttrKind::Name(lit) => name = Some(lit),
+                ComputedSortKeyAttrKind::Compute(path) => compute = Some(path),
+            }
+        }
+        match (name, compute) {
+            (Some(name), Some(compute)) => Ok(ComputedSortKey { name, compute }),
+            _ => Err(syn::Error::new_spanned(
+                span,
+                "expected a sort key name and function: `sort_key(name = \"sk\", compute = \"path\")`",
+            )),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) enum EnumAttrKind {
     // FIXME: implement content attribute to support non-map values in enum variants
@@ -66,24 +267,49 @@ pub(crate) enum EnumAttrKind {
     // Content(LitStr),
     /// The name of the tag field for an internally-tagged enum
     Tag(LitStr),
+
+    /// Denotes an enum whose variants, and their scalar payloads, are represented by a single string
+    CompactString,
+
+    /// The separator between a `compact_string` enum's variant names and payloads
+    Separator(LitStr),
+
+    /// Denotes a plain enum which should also implement `FromStr` and `Display` so it may key maps
+    MapKey,
+
+    /// Denotes a plain enum represented by the numeric value of its discriminants
+    Numeric,
 }
 
 impl DynomiteAttr for EnumAttrKind {
-    const KVS: Kvs<Self> = &[("tag", |lit| Ok(EnumAttrKind::Tag(lit)))];
+    const KVS: Kvs<Self> = &[
+        ("tag", |lit| Ok(EnumAttrKind::Tag(lit))),
+        ("separator", |lit| Ok(EnumAttrKind::Separator(lit))),
+    ];
+    const KEYS: Keys<Self> = &[
+        ("compact_string", EnumAttrKind::CompactString),
+        ("map_key", EnumAttrKind::MapKey),
+        ("numeric", EnumAttrKind::Numeric),
+    ];
 }
 
 #[derive(Clone)]
 pub(crate) enum VariantAttrKind {
     // TODO: add default for enum variants?
     Rename(LitStr),
+
+    /// Denotes the unit variant of a plain enum which unrecognized values are read as
+    Other,
 }
 
 impl DynomiteAttr for VariantAttrKind {
     const KVS: Kvs<Self> = &[("rename", |lit| Ok(VariantAttrKind::Rename(lit)))];
+    const KEYS: Keys<Self> = &[("other", VariantAttrKind::Other)];
 }
 
 type Kvs<T> = &'static [(&'static str, fn(syn::LitStr) -> syn::Result<T>)];
 type Keys<T> = &'static [(&'static str, T)];
+type Lists<T> = &'static [(&'static str, fn(TokenStream) -> syn::Result<T>)];
 
 /// Helper to ease defining `#[dynomite(key)` and `#[dynomite(key = "val")` attributes
 pub(crate) trait DynomiteAttr: Clone + Sized + 'static {
@@ -93,15 +319,26 @@ pub(crate) trait DynomiteAttr: Clone + Sized + 'static {
     /// List of `("attr_name", enum_variant_value)` entires to define attributes
     /// that should not have any value (e.g. `default` or `flatten`)
     const KEYS: Keys<Self> = &[];
+    /// List of `("attr_name", enum_variant_parser)` entries to define attributes
+    /// that accept a nested list of values (e.g. `gsi(name = "foo", partition_key)`)
+    const LISTS: Lists<Self> = &[];
 }
 
 impl<A: DynomiteAttr> Parse for Attr<A> {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let entry: MetadataEntry = input.parse()?;
-        let kind = entry
-            .try_attr_with_val(A::KVS)?
-            .or_else(|| entry.try_attr_without_val(A::KEYS))
-            .unwrap_or_else(|| abort!(entry.key, "unexpected dynomite attribute: {}", entry.key));
+        let kind = match entry.try_attr_with_list(A::LISTS)? {
+            Some(kind) => Some(kind),
+            // attributes may be declared both with and without a value, e.g. `default` and `default = "..."`
+            None if entry.val.is_none() => match entry.try_attr_without_val(A::KEYS) {
+                Some(kind) => Some(kind),
+                None => entry.try_attr_with_val(A::KVS)?,
+            },
+            None => entry
+                .try_attr_with_val(A::KVS)?
+                .or_else(|| entry.try_attr_without_val(A::KEYS)),
+        }
+        .unwrap_or_else(|| abort!(entry.key, "unexpected dynomite attribute: {}", entry.key));
         Ok(Attr {
             ident: entry.key,
             kind,
@@ -112,6 +349,7 @@ impl<A: DynomiteAttr> Parse for Attr<A> {
 struct MetadataEntry {
     key: Ident,
     val: Option<LitStr>,
+    list: Option<TokenStream>,
 }
 
 impl MetadataEntry {
@@ -120,14 +358,14 @@ impl MetadataEntry {
         &self,
         mappings: Keys<T>,
     ) -> Option<T> {
-        let Self { key, val } = self;
+        let Self { key, val, list } = self;
         let key_str = key.to_string();
         mappings
             .iter()
             .find(|(key_pat, _)| *key_pat == key_str)
-            .map(|(_, enum_val)| match val {
-                Some(_) => abort!(key, "expected no value for dynomite attribute `{}`", key),
-                None => enum_val.clone(),
+            .map(|(_, enum_val)| match (val, list) {
+                (None, None) => enum_val.clone(),
+                _ => abort!(key, "expected no value for dynomite attribute `{}`", key),
             })
     }
 
@@ -136,14 +374,14 @@ impl MetadataEntry {
         &self,
         mappings: Kvs<T>,
     ) -> syn::Result<Option<T>> {
-        let Self { key, val } = self;
+        let Self { key, val, list } = self;
         let key_str = key.to_string();
         mappings
             .iter()
             .find(|(key_pat, _)| *key_pat == key_str)
-            .map(|(_, to_enum)| match val {
-                Some(it) => to_enum(it.clone()),
-                None => abort!(
+            .map(|(_, to_enum)| match (val, list) {
+                (Some(it), None) => to_enum(it.clone()),
+                _ => abort!(
                     key,
                     "expected a value for dynomite attribute: `{} = \"foo\"`",
                     key
@@ -151,6 +389,27 @@ impl MetadataEntry {
             })
             .transpose()
     }
+
+    /// Attempt to map the parsed entry to a nested list attribute from the list
+    fn try_attr_with_list<T>(
+        &self,
+        mappings: Lists<T>,
+    ) -> syn::Result<Option<T>> {
+        let Self { key, val, list } = self;
+        let key_str = key.to_string();
+        mappings
+            .iter()
+            .find(|(key_pat, _)| *key_pat == key_str)
+            .map(|(_, to_enum)| match (val, list) {
+                (None, Some(it)) => to_enum(it.clone()),
+                _ => abort!(
+                    key,
+                    "expected a list of values for dynomite attribute: `{}(...)`",
+                    key
+                ),
+            })
+            .transpose()
+    }
 }
 
 impl Parse for MetadataEntry {
@@ -158,7 +417,13 @@ impl Parse for MetadataEntry {
         let key: Ident = input.parse()?;
         if input.peek(syn::token::Paren) {
             // `name(...)` attributes.
-            abort!(key, "unexpected paren in dynomite attribute: {}", key);
+            let content;
+            syn::parenthesized!(content in input);
+            return Ok(Self {
+                key,
+                val: None,
+                list: Some(content.parse()?),
+            });
         }
         Ok(Self {
             key,
@@ -167,6 +432,7 @@ impl Parse for MetadataEntry {
                 .ok()
                 .map(|_| input.parse())
                 .transpose()?,
+            list: None,
         })
     }
 }
@@ -29,17 +29,20 @@
 //! ```
 
 mod attr;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
-use attr::{EnumAttr, EnumAttrKind, FieldAttr, FieldAttrKind, VariantAttr};
+use attr::{
+    ComputedSortKey, EnumAttr, EnumAttrKind, FieldAttr, FieldAttrKind, Gsi, KeyKind, StructAttr,
+    StructAttrKind, VariantAttr,
+};
 
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use proc_macro_error::{abort, ResultExt};
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    parse::Parse, punctuated::Punctuated, Attribute, DataStruct, DeriveInput, Field, Fields, Ident,
-    Path, Token, Visibility,
+    parse::Parse, punctuated::Punctuated, spanned::Spanned, Attribute, DataStruct, DeriveInput,
+    Field, Fields, Ident, LitStr, Path, Token, Visibility,
 };
 
 struct Variant {
@@ -53,9 +56,18 @@ impl Variant {
             .iter()
             .find_map(|it| match &it.kind {
                 attr::VariantAttrKind::Rename(it) => Some(it.value()),
+                _ => None,
             })
             .unwrap_or_else(|| self.inner.ident.to_string())
     }
+
+    /// The `#[dynomite(other)]` attribute of this variant, if declared
+    fn other_attr(&self) -> Option<&Ident> {
+        self.attrs
+            .iter()
+            .find(|it| matches!(it.kind, attr::VariantAttrKind::Other))
+            .map(|it| &it.ident)
+    }
 }
 
 struct DataEnum {
@@ -83,6 +95,12 @@ impl DataEnum {
                 .collect(),
         };
 
+        // Validate that at most one variant is the fallback for unknown values
+        let mut others = me.variants.iter().filter_map(Variant::other_attr);
+        if let (Some(_), Some(extra)) = (others.next(), others.next()) {
+            abort!(extra, "only one variant may be declared #[dynomite(other)]");
+        }
+
         // Validate that all enum tag values are unique
         let mut unique_names = HashSet::new();
         for variant in &me.variants {
@@ -103,6 +121,7 @@ impl DataEnum {
             .iter()
             .find_map(|attr| match &attr.kind {
                 EnumAttrKind::Tag(lit) => Some(lit.value()),
+                _ => None,
             })
             .unwrap_or_else(|| {
                 abort!(
@@ -114,6 +133,241 @@ impl DataEnum {
             })
     }
 
+    /// Aborts should any variant be declared `#[dynomite(other)]`, which only plain enums support
+    fn reject_other(
+        &self,
+        kind: &str,
+    ) {
+        if let Some(other) = self.variants.iter().find_map(Variant::other_attr) {
+            abort!(
+                other,
+                "#[dynomite(other)] is not supported for {}", kind;
+                help = "#[dynomite(other)] may only be declared on a unit variant of an enum whose variants are all units"
+            );
+        }
+    }
+
+    /// The `#[dynomite(map_key)]` attribute of this enum, if declared
+    fn map_key_attr(&self) -> Option<&Ident> {
+        self.attrs
+            .iter()
+            .find(|attr| matches!(attr.kind, EnumAttrKind::MapKey))
+            .map(|attr| &attr.ident)
+    }
+
+    /// Aborts should the enum be declared `#[dynomite(map_key)]`, which only plain enums support
+    fn reject_map_key(
+        &self,
+        kind: &str,
+    ) {
+        if let Some(map_key) = self.map_key_attr() {
+            abort!(
+                map_key,
+                "#[dynomite(map_key)] is not supported for {}", kind;
+                help = "#[dynomite(map_key)] may only be declared on an enum whose variants are all units"
+            );
+        }
+    }
+
+    /// The separator of a `#[dynomite(compact_string)]` enum, which defaults to `:`
+    fn compact_separator(&self) -> Option<String> {
+        let compact = self
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr.kind, EnumAttrKind::CompactString));
+        let separator = self.attrs.iter().find_map(|attr| match &attr.kind {
+            EnumAttrKind::Separator(lit) => Some(lit),
+            _ => None,
+        });
+        let separator = match (compact, separator) {
+            (false, None) => return None,
+            (false, Some(lit)) => abort!(
+                lit,
+                "#[dynomite(separator = \"...\")] requires a sibling #[dynomite(compact_string)] attribute"
+            ),
+            (true, Some(lit)) if lit.value().is_empty() => {
+                abort!(lit, "compact_string separators must not be empty")
+            }
+            (true, separator) => separator.map_or_else(|| ":".to_string(), LitStr::value),
+        };
+        self.reject_other("compact_string enums");
+        self.reject_map_key("compact_string enums");
+        for variant in &self.variants {
+            if variant.deser_name().contains(&separator) {
+                abort!(
+                    variant.inner.ident,
+                    "compact_string variant names must not contain the separator `{}`", separator;
+                    help = "Use #[dynomite(rename = \"...\")] or a different #[dynomite(separator = \"...\")]"
+                );
+            }
+        }
+        Some(separator)
+    }
+
+    /// Whether the enum is declared `#[dynomite(numeric)]`, aborting should it not be a plain enum
+    fn is_numeric(&self) -> bool {
+        let numeric = match self
+            .attrs
+            .iter()
+            .find(|attr| matches!(attr.kind, EnumAttrKind::Numeric))
+        {
+            Some(numeric) => &numeric.ident,
+            None => return false,
+        };
+        if self
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr.kind, EnumAttrKind::CompactString))
+        {
+            abort!(
+                numeric,
+                "#[dynomite(numeric)] may not be used along with #[dynomite(compact_string)]"
+            );
+        }
+        self.reject_map_key("numeric enums");
+        for variant in &self.variants {
+            if !matches!(variant.inner.fields, Fields::Unit) {
+                abort!(
+                    variant.inner.ident,
+                    "#[dynomite(numeric)] enums may only have unit variants"
+                );
+            }
+            if let Some(rename) = variant
+                .attrs
+                .iter()
+                .find(|attr| matches!(attr.kind, attr::VariantAttrKind::Rename(_)))
+            {
+                abort!(
+                    rename.ident,
+                    "variants of #[dynomite(numeric)] enums are represented by their discriminants so may not be renamed";
+                    help = "Declare an explicit discriminant, e.g. `Admin = 10`"
+                );
+            }
+        }
+        true
+    }
+
+    /// ```rust,ignore
+    /// impl ::dynomite::Attribute for Name {
+    ///   const ATTRIBUTE_TYPE: Option<&'static str> = Some("N");
+    ///   fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+    ///     ::dynomite::Attribute::into_attr(self as i64)
+    ///   }
+    ///   fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> Result<Self, ::dynomite::AttributeError> {
+    ///     match <i64 as ::dynomite::Attribute>::from_attr(value)? {
+    ///       value if value == Name::Variant as i64 => Ok(Name::Variant),
+    ///       _ => Err(::dynomite::AttributeError::InvalidFormat)
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    fn impl_numeric_attribute(&self) -> impl ToTokens {
+        let name = &self.ident;
+        let attr = quote!(::dynomite::Attribute);
+        let err = quote!(::dynomite::AttributeError);
+        let from_match_arms = self.variants.iter().map(|variant| {
+            let variant_ident = &variant.inner.ident;
+            quote! {
+                value if value == #name::#variant_ident as i64 => ::std::result::Result::Ok(#name::#variant_ident),
+            }
+        });
+        // values of unknown variants are read as the #[dynomite(other)] variant when declared
+        let fallback_arm = match self.variants.iter().find(|var| var.other_attr().is_some()) {
+            Some(other) => {
+                let variant_ident = &other.inner.ident;
+                quote!(_ => ::std::result::Result::Ok(#name::#variant_ident))
+            }
+            None => quote!(_ => ::std::result::Result::Err(#err::InvalidFormat)),
+        };
+
+        quote! {
+            impl #attr for #name {
+                const ATTRIBUTE_TYPE: ::std::option::Option<&'static str> = ::std::option::Option::Some("N");
+
+                fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+                    #attr::into_attr(self as i64)
+                }
+                fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> ::std::result::Result<Self, #err> {
+                    match <i64 as #attr>::from_attr(value)? {
+                        #(#from_match_arms)*
+                        #fallback_arm
+                    }
+                }
+            }
+
+            impl ::dynomite::encoding::Scalar for #name {}
+        }
+    }
+
+    fn impl_compact_attribute(
+        &self,
+        separator: &str,
+    ) -> impl ToTokens {
+        let compact = quote!(::dynomite::encoding::compact);
+        let into_match_arms = self.variants.iter().map(|variant| {
+            let variant_ident = &variant.inner.ident;
+            let variant_deser_name = variant.deser_name();
+            match &variant.inner.fields {
+                Fields::Named(_record) => Self::unimplemented_record_variants(variant),
+                Fields::Unnamed(tuple) => {
+                    Self::expect_single_item_tuple(tuple, variant_ident);
+                    // payloads are spanned by their type so that non-scalar types are reported there
+                    let payload = quote_spanned! {tuple.unnamed.span()=>
+                        ::dynomite::encoding::Scalar::into_scalar_string(payload)
+                    };
+                    quote! {
+                        Self::#variant_ident(payload) => #compact::into_attr(
+                            #variant_deser_name,
+                            #separator,
+                            ::std::option::Option::Some(#payload),
+                        ),
+                    }
+                }
+                Fields::Unit => quote! {
+                    Self::#variant_ident => #compact::into_attr(
+                        #variant_deser_name,
+                        #separator,
+                        ::std::option::Option::None,
+                    ),
+                },
+            }
+        });
+        let from_match_arms = self.variants.iter().map(|variant| {
+            let variant_ident = &variant.inner.ident;
+            let variant_deser_name = variant.deser_name();
+            match &variant.inner.fields {
+                Fields::Unnamed(_) => quote! {
+                    (#variant_deser_name, payload) => Self::#variant_ident(#compact::payload(payload)?),
+                },
+                _ => quote! {
+                    (#variant_deser_name, ::std::option::Option::None) => Self::#variant_ident,
+                },
+            }
+        });
+
+        let enum_ident = &self.ident;
+        quote! {
+            impl ::dynomite::Attribute for #enum_ident {
+                const ATTRIBUTE_TYPE: ::std::option::Option<&'static str> = ::std::option::Option::Some("S");
+
+                fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+                    match self {
+                        #(#into_match_arms)*
+                    }
+                }
+                fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> ::std::result::Result<Self, ::dynomite::AttributeError> {
+                    let (variant, payload) = #compact::from_attr(value, #separator)?;
+                    ::std::result::Result::Ok(match (variant.as_str(), payload) {
+                        #(#from_match_arms)*
+                        _ => return ::std::result::Result::Err(::dynomite::AttributeError::InvalidFormat)
+                    })
+                }
+            }
+
+            impl ::dynomite::encoding::Scalar for #enum_ident {}
+        }
+    }
+
     fn impl_from_attributes(&self) -> impl ToTokens {
         let match_arms = self.variants.iter().map(|variant| {
             let variant_ident = &variant.inner.ident;
@@ -227,7 +481,7 @@ impl<'a> ItemField<'a> {
             if let Some(it) = me
                 .attrs
                 .iter()
-                .find(|it| !matches!(it.kind, FieldAttrKind::Flatten))
+                .find(|it| !matches!(it.kind, FieldAttrKind::Flatten | FieldAttrKind::Prefix(_)))
             {
                 abort!(
                     it.ident,
@@ -235,6 +489,54 @@ impl<'a> ItemField<'a> {
                 );
             }
         }
+        if option_inner(&me.field.ty).and_then(option_inner).is_some() {
+            abort!(
+                me.field.ty,
+                "Nested `Option` fields are not supported as `Some(None)` and `None` are both represented as `NULL`";
+                help = "Use a single `Option<T>` or an enum which distinguishes the cases"
+            );
+        }
+        if let Some(it) = me
+            .attrs
+            .iter()
+            .find(|it| matches!(it.kind, FieldAttrKind::Prefix(_)))
+        {
+            if me.is_base64() || me.is_lenient() {
+                abort!(
+                    it.ident,
+                    "#[dynomite(prefix = \"...\")] may not be used along with #[dynomite(base64)] or #[dynomite(lenient)]"
+                );
+            }
+            if me.is_flatten() && !matches!(me.field.ty, syn::Type::Array(_)) {
+                abort!(
+                    me.field.ty,
+                    "#[dynomite(flatten, prefix = \"...\")] may only be used on array fields";
+                    help = "Use an array type `[T; N]` where `T` implements `Attributes`"
+                );
+            }
+            if let Some(repr) = Some(&me.field.ty)
+                .filter(|_| !me.is_flatten())
+                .and_then(non_scalar_representation)
+            {
+                abort!(
+                    me.field.ty,
+                    "#[dynomite(prefix = \"...\")] fields must be represented as a string (S) or number (N) value but this type is represented as {}",
+                    repr
+                );
+            }
+        }
+        if me.is_base64() && me.is_lenient() {
+            if let Some(it) = me
+                .attrs
+                .iter()
+                .find(|it| matches!(it.kind, FieldAttrKind::Lenient))
+            {
+                abort!(
+                    it.ident,
+                    "#[dynomite(lenient)] may not be used along with #[dynomite(base64)]"
+                );
+            }
+        }
         me
     }
 
@@ -250,10 +552,58 @@ impl<'a> ItemField<'a> {
             .any(|attr| matches!(attr.kind, FieldAttrKind::SortKey))
     }
 
+    fn is_version(&self) -> bool {
+        self.attrs
+            .iter()
+            .any(|attr| matches!(attr.kind, FieldAttrKind::Version))
+    }
+
     fn is_default_when_absent(&self) -> bool {
+        self.attrs.iter().any(|attr| {
+            matches!(
+                attr.kind,
+                FieldAttrKind::Default | FieldAttrKind::DefaultWith(_)
+            )
+        })
+    }
+
+    /// Whether this is a `PhantomData` marker field, which isn't stored at all
+    fn is_phantom(&self) -> bool {
+        match &self.field.ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "PhantomData"),
+            _ => false,
+        }
+    }
+
+    fn default_fn(&self) -> Option<&Path> {
+        self.attrs.iter().find_map(|attr| match &attr.kind {
+            FieldAttrKind::DefaultWith(path) => Some(path),
+            _ => None,
+        })
+    }
+
+    /// Whether this field takes its value from the struct's `Default` impl when absent,
+    /// given whether the struct is declared `#[dynomite(default)]`
+    fn is_container_default(
+        &self,
+        container_default: bool,
+    ) -> bool {
+        container_default
+            && !self.is_phantom()
+            && !self.is_optional()
+            && !self.is_default_when_absent()
+            && !self.is_flatten()
+            && !self.is_partition_key()
+            && !self.is_sort_key()
+    }
+
+    fn is_optional(&self) -> bool {
         self.attrs
             .iter()
-            .any(|attr| matches!(attr.kind, FieldAttrKind::Default))
+            .any(|attr| matches!(attr.kind, FieldAttrKind::Optional))
     }
 
     fn skip_serializing_if(&self) -> Option<&Path> {
@@ -269,6 +619,80 @@ impl<'a> ItemField<'a> {
             .any(|attr| matches!(attr.kind, FieldAttrKind::Flatten))
     }
 
+    fn prefix(&self) -> Option<&LitStr> {
+        self.attrs.iter().find_map(|attr| match &attr.kind {
+            FieldAttrKind::Prefix(prefix) => Some(prefix),
+            _ => None,
+        })
+    }
+
+    /// The prefix of the keys of a flattened array field's elements
+    fn flatten_prefix(&self) -> Option<&LitStr> {
+        self.prefix().filter(|_| self.is_flatten())
+    }
+
+    /// The prefix of a field's stored value
+    fn value_prefix(&self) -> Option<&LitStr> {
+        self.prefix().filter(|_| !self.is_flatten())
+    }
+
+    fn is_base64(&self) -> bool {
+        self.attrs
+            .iter()
+            .any(|attr| matches!(attr.kind, FieldAttrKind::Base64))
+    }
+
+    fn is_lenient(&self) -> bool {
+        self.attrs
+            .iter()
+            .any(|attr| matches!(attr.kind, FieldAttrKind::Lenient))
+    }
+
+    /// Converts `value`, an expression of this field's type, into an `AttributeValue`
+    ///
+    /// The conversion is spanned by the field's type, and is passed `value` by a binding of
+    /// the same span, so that types which don't implement `Attribute` are reported at the
+    /// field rather than at the derive
+    fn to_attr(
+        &self,
+        value: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let span = self.field.ty.span();
+        let convert = if let Some(prefix) = self.value_prefix() {
+            quote_spanned!(span=> ::dynomite::encoding::prefixed::into_attr(#prefix, value))
+        } else if self.is_base64() {
+            quote_spanned!(span=> ::dynomite::encoding::base64::into_attr(value))
+        } else {
+            quote_spanned!(span=> ::dynomite::Attribute::into_attr(value))
+        };
+        quote_spanned!(span=> { let value = #value; #convert })
+    }
+
+    /// Converts `value`, an `AttributeValue` expression, into this field's value, spanned like `to_attr`
+    fn parse_attr(
+        &self,
+        value: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let span = self.field.ty.span();
+        let convert = if let Some(prefix) = self.value_prefix() {
+            quote_spanned!(span=> ::dynomite::encoding::prefixed::from_attr(#prefix, value))
+        } else if self.is_base64() {
+            quote_spanned!(span=> ::dynomite::encoding::base64::from_attr(value))
+        } else if self.is_lenient() {
+            quote_spanned!(span=> ::dynomite::encoding::lenient::from_attr(value))
+        } else {
+            quote_spanned!(span=> ::dynomite::Attribute::from_attr(value))
+        };
+        quote_spanned!(span=> { let value = #value; #convert })
+    }
+
+    fn gsis(&self) -> impl Iterator<Item = &Gsi> {
+        self.attrs.iter().filter_map(|attr| match &attr.kind {
+            FieldAttrKind::Gsi(gsi) => Some(gsi),
+            _ => None,
+        })
+    }
+
     fn deser_name(&self) -> String {
         let ItemField { field, attrs } = self;
         attrs
@@ -287,6 +711,69 @@ impl<'a> ItemField<'a> {
     }
 }
 
+/// Returns the DynamoDB representation of types which are known not to be valid key attributes.
+/// Other types may implement `Attribute` in any way so are assumed to be valid
+fn non_key_representation(ty: &syn::Type) -> Option<&'static str> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    match segment.ident.to_string().as_str() {
+        "bool" => Some("a boolean (BOOL)"),
+        "Option" => Some("null (NULL) when `None`"),
+        "HashMap" | "BTreeMap" => Some("a map (M)"),
+        "HashSet" | "BTreeSet" => Some("a set (SS, NS or BS)"),
+        // Vec<u8> is binary
+        "Vec" => match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(syn::GenericArgument::Type(syn::Type::Path(inner)))
+                    if inner.path.is_ident("u8") =>
+                {
+                    None
+                }
+                _ => Some("a list (L)"),
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the DynamoDB representation of types which are known not to be strings or numbers,
+/// which can't be embedded in a string following a prefix
+fn non_scalar_representation(ty: &syn::Type) -> Option<&'static str> {
+    if let Some(repr) = non_key_representation(ty) {
+        return Some(repr);
+    }
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    match segment.ident.to_string().as_str() {
+        // Vec<u8> is binary
+        "Vec" | "Bytes" | "UuidBin" | "Be32" | "Be64" | "CompositeBinary" => Some("binary (B)"),
+        _ => None,
+    }
+}
+
+/// Returns `T` when `ty` is an `Option<T>`
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
 fn parse_attrs<A: Parse>(all_attrs: &[Attribute]) -> Vec<A> {
     all_attrs
         .iter()
@@ -331,13 +818,17 @@ pub fn derive_attributes(input: TokenStream) -> TokenStream {
     expand_attributes(ast).unwrap_or_else(|e| e.to_compile_error().into())
 }
 
-/// Derives `dynomite::Attribute` for enum types
+/// Derives `dynomite::Attribute` for enum types and single field tuple structs
+///
+/// Enums declared with `#[dynomite(compact_string)]` may also have single field tuple variants,
+/// and structs with named fields may be derived when declared with `#[dynomite(compose = "...")]`,
+/// or with `#[dynomite(transparent)]` when they have a single field
 ///
 /// # Panics
 ///
 /// This proc macro will panic when applied to other types
 #[proc_macro_error::proc_macro_error]
-#[proc_macro_derive(Attribute)]
+#[proc_macro_derive(Attribute, attributes(dynomite))]
 pub fn derive_attribute(input: TokenStream) -> TokenStream {
     let ast = syn::parse_macro_input!(input);
     let gen = expand_attribute(ast);
@@ -347,71 +838,366 @@ pub fn derive_attribute(input: TokenStream) -> TokenStream {
 fn expand_attribute(ast: DeriveInput) -> impl ToTokens {
     let name = &ast.ident;
     match ast.data {
-        syn::Data::Enum(variants) => {
-            make_dynomite_attr(name, &variants.variants.into_iter().collect::<Vec<_>>())
+        syn::Data::Enum(data_enum) => {
+            let enum_item = DataEnum::new(name.clone(), data_enum, &ast.attrs);
+            if enum_item.is_numeric() {
+                return enum_item.impl_numeric_attribute().into_token_stream();
+            }
+            match enum_item.compact_separator() {
+                Some(separator) => enum_item
+                    .impl_compact_attribute(&separator)
+                    .into_token_stream(),
+                None => make_dynomite_attr(
+                    name,
+                    &enum_item.variants,
+                    enum_item.map_key_attr().is_some(),
+                )
+                .into_token_stream(),
+            }
+        }
+        syn::Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) if fields.unnamed.len() == 1 => {
+            make_dynomite_newtype_attr(name, &quote!(0), &fields.unnamed[0].ty).into_token_stream()
+        }
+        syn::Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => {
+            let attrs = parse_attrs::<StructAttr>(&ast.attrs);
+            if let Some(transparent) = attrs
+                .iter()
+                .find(|attr| matches!(attr.kind, StructAttrKind::Transparent))
+            {
+                return match fields.named.iter().collect::<Vec<_>>().as_slice() {
+                    [field] => make_dynomite_newtype_attr(name, &field.ident, &field.ty)
+                        .into_token_stream(),
+                    _ => abort!(
+                        transparent.ident,
+                        "#[dynomite(transparent)] requires a struct with exactly one field"
+                    ),
+                };
+            }
+            let pattern = attrs
+                .into_iter()
+                .find_map(|attr| match attr.kind {
+                    StructAttrKind::Compose(pattern) => Some(pattern),
+                    _ => None,
+                })
+                .unwrap_or_else(|| {
+                    abort!(
+                        name,
+                        "#[derive(Attribute)] for structs with named fields requires a sibling \
+                        #[dynomite(compose = \"...\")] or #[dynomite(transparent)] attribute";
+                        help = "Use #[derive(Attributes)] to represent the struct as a map"
+                    )
+                });
+            make_dynomite_composed_attr(
+                name,
+                &fields.named.into_iter().collect::<Vec<_>>(),
+                &pattern,
+            )
+            .into_token_stream()
         }
-        _ => panic!("Dynomite Attributes can only be generated for enum types"),
+        _ => panic!(
+            "Dynomite Attributes can only be generated for enum types, single field tuple structs \
+            and structs with a #[dynomite(compose = \"...\")] attribute"
+        ),
     }
 }
 
+/// Splits a `compose` pattern such as `ORDER#{year}#{seq}` into the literal text
+/// surrounding each field, `["ORDER#", "#", ""]`, and the field names, `["year", "seq"]`
+fn parse_compose_pattern(pattern: &LitStr) -> (Vec<String>, Vec<String>) {
+    let value = pattern.value();
+    let mut literals = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find(&['{', '}'][..]) {
+        let (literal, placeholder) = rest.split_at(start);
+        let end = match placeholder.find('}') {
+            Some(end) if placeholder.starts_with('{') => end,
+            _ => abort!(pattern, "unmatched brace in compose pattern"),
+        };
+        let name = &placeholder[1..end];
+        if name.is_empty() || name.contains('{') {
+            abort!(
+                pattern,
+                "compose placeholders must name a field, e.g. `{{field}}`"
+            );
+        }
+        if let (Some(previous), true) = (names.last(), literal.is_empty()) {
+            abort!(
+                pattern,
+                "compose placeholders `{{{}}}` and `{{{}}}` must be separated by literal text",
+                previous,
+                name
+            );
+        }
+        literals.push(literal.to_string());
+        names.push(name.to_string());
+        rest = &placeholder[end + 1..];
+    }
+    literals.push(rest.to_string());
+    (literals, names)
+}
+
 /// ```rust,ignore
 /// impl ::dynomite::Attribute for Name {
+///   const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
 ///   fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
-///     let arm = match self {
-///        Name::Variant => "Variant".to_string()
-///     };
-///     ::dynomite::dynamodb::AttributeValue {
-///        s: Some(arm),
-///        ..Default::default()
-///     }
+///     ::dynomite::encoding::compose::into_attr(&["ORDER#", "#", ""], vec![
+///       ::dynomite::encoding::Scalar::into_scalar_string(self.year),
+///       ::dynomite::encoding::Scalar::into_scalar_string(self.seq),
+///     ])
 ///   }
 ///   fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> Result<Self, ::dynomite::AttributeError> {
-///     value.s.ok_or(::dynomite::AttributeError::InvalidType)
-///       .and_then(|value| match &value[..] {
-///          "Variant" => Ok(Name::Variant),
-///          _ => Err(::dynomite::AttributeError::InvalidFormat)
-///       })
+///     let mut values = ::dynomite::encoding::compose::from_attr(&["ORDER#", "#", ""], value)?.into_iter();
+///     let year = ::dynomite::encoding::compose::value(values.next())?;
+///     let seq = ::dynomite::encoding::compose::value(values.next())?;
+///     Ok(Self { year, seq })
 ///   }
 /// }
 /// ```
-fn make_dynomite_attr(
+fn make_dynomite_composed_attr(
     name: &Ident,
-    variants: &[syn::Variant],
+    fields: &[Field],
+    pattern: &LitStr,
 ) -> impl ToTokens {
-    let attr = quote!(::dynomite::Attribute);
-    let err = quote!(::dynomite::AttributeError);
-    let into_match_arms = variants.iter().map(|var| {
-        let vname = &var.ident;
-        quote! {
-            #name::#vname => stringify!(#vname).to_string(),
+    let (literals, names) = parse_compose_pattern(pattern);
+    let field_idents = names
+        .iter()
+        .map(|name| {
+            fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+                .find(|ident| *ident == name)
+                .unwrap_or_else(|| {
+                    abort!(
+                        pattern,
+                        "compose placeholder `{{{}}}` does not name a field",
+                        name
+                    )
+                })
+        })
+        .collect::<Vec<_>>();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields");
+        match field_idents.iter().filter(|it| *it == &ident).count() {
+            1 => {}
+            0 => abort!(
+                ident,
+                "field `{}` is missing from the compose pattern",
+                ident
+            ),
+            _ => abort!(
+                pattern,
+                "field `{}` may only appear once in the compose pattern",
+                ident
+            ),
         }
-    });
-    let from_match_arms = variants.iter().map(|var| {
-        let vname = &var.ident;
-        quote! {
-            stringify!(#vname) => ::std::result::Result::Ok(#name::#vname),
+    }
+    let compose = quote!(::dynomite::encoding::compose);
+    let err = quote!(::dynomite::AttributeError);
+    // values are spanned by their field's type so that non-scalar types are reported there
+    let values = field_idents.iter().map(|ident| {
+        let field = fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(*ident))
+            .expect("named field");
+        quote_spanned! {field.ty.span()=>
+            ::dynomite::encoding::Scalar::into_scalar_string(self.#ident)
         }
     });
 
     quote! {
-        impl #attr for #name {
+        impl ::dynomite::Attribute for #name {
+            const ATTRIBUTE_TYPE: ::std::option::Option<&'static str> = ::std::option::Option::Some("S");
+
             fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
-                let arm = match self {
-                    #(#into_match_arms)*
-                };
-                ::dynomite::dynamodb::AttributeValue {
-                    s: ::std::option::Option::Some(arm),
-                    ..::std::default::Default::default()
-                }
+                #compose::into_attr(&[#(#literals),*], ::std::vec![
+                    #(#values),*
+                ])
             }
             fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> ::std::result::Result<Self, #err> {
-                value.s.ok_or(::dynomite::AttributeError::InvalidType)
-                    .and_then(|value| match &value[..] {
+                let mut values = #compose::from_attr(&[#(#literals),*], value)?.into_iter();
+                #(
+                    let #field_idents = #compose::value(values.next())
+                        .map_err(|err| #err::in_field(err, stringify!(#field_idents)))?;
+                )*
+                ::std::result::Result::Ok(Self {
+                    #(#field_idents),*
+                })
+            }
+        }
+
+        impl ::dynomite::encoding::Scalar for #name {}
+    }
+}
+
+/// ```rust,ignore
+/// impl ::dynomite::Attribute for Name {
+///   const ATTRIBUTE_TYPE: Option<&'static str> = <Type as ::dynomite::Attribute>::ATTRIBUTE_TYPE;
+///   fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+///     ::dynomite::Attribute::into_attr(self.member)
+///   }
+///   fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> Result<Self, ::dynomite::AttributeError> {
+///     ::dynomite::Attribute::from_attr(value).map(|member| Name { member })
+///   }
+/// }
+/// ```
+///
+/// The single `member` is `0` for tuple structs, or the name of the field of a named struct
+/// of type `ty`. Newtypes of `Scalar` types are `Scalar` themselves
+fn make_dynomite_newtype_attr(
+    name: &Ident,
+    member: &impl ToTokens,
+    ty: &syn::Type,
+) -> impl ToTokens {
+    let attr = quote!(::dynomite::Attribute);
+    let err = quote!(::dynomite::AttributeError);
+
+    // the higher-ranked bound defers checking it to each use, as an impl with a bound on
+    // a concrete type that doesn't hold, such as `Vec<String>: Scalar`, fails to compile
+    quote! {
+        impl ::dynomite::encoding::Scalar for #name where for<'__dynomite> #ty: ::dynomite::encoding::Scalar {}
+
+        impl #attr for #name {
+            const ATTRIBUTE_TYPE: ::std::option::Option<&'static str> = <#ty as #attr>::ATTRIBUTE_TYPE;
+
+            fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+                #attr::into_attr(self.#member)
+            }
+            fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> ::std::result::Result<Self, #err> {
+                #attr::from_attr(value).map(|value| #name { #member: value })
+            }
+        }
+    }
+}
+
+/// ```rust,ignore
+/// impl ::dynomite::Attribute for Name {
+///   const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+///   fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+///     let arm = match self {
+///        Name::Variant => "Variant".to_string()
+///     };
+///     ::dynomite::dynamodb::AttributeValue {
+///        s: Some(arm),
+///        ..Default::default()
+///     }
+///   }
+///   fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> Result<Self, ::dynomite::AttributeError> {
+///     let err = ::dynomite::AttributeError::wrong_type("S", &value);
+///     value.s.ok_or(err)
+///       .and_then(|value| match &value[..] {
+///          "Variant" => Ok(Name::Variant),
+///          _ => Err(::dynomite::AttributeError::InvalidFormat)
+///       })
+///   }
+/// }
+/// ```
+fn make_dynomite_attr(
+    name: &Ident,
+    variants: &[Variant],
+    map_key: bool,
+) -> impl ToTokens {
+    let attr = quote!(::dynomite::Attribute);
+    let err = quote!(::dynomite::AttributeError);
+    // variants might have #[dynomite(rename = "...")] attributes
+    let into_match_arms = variants.iter().map(|var| {
+        let vname = &var.inner.ident;
+        let deser_name = var.deser_name();
+        quote! {
+            #name::#vname => #deser_name.to_string(),
+        }
+    });
+    let from_match_arms = variants
+        .iter()
+        .map(|var| {
+            let vname = &var.inner.ident;
+            let deser_name = var.deser_name();
+            quote! {
+                #deser_name => ::std::result::Result::Ok(#name::#vname),
+            }
+        })
+        .collect::<Vec<_>>();
+    // values of unknown variants are read as the #[dynomite(other)] variant when declared
+    let fallback_arm = match variants.iter().find(|var| var.other_attr().is_some()) {
+        Some(other) => {
+            if !matches!(other.inner.fields, Fields::Unit) {
+                abort!(
+                    other.inner.ident,
+                    "#[dynomite(other)] may only be declared on a unit variant"
+                );
+            }
+            let vname = &other.inner.ident;
+            quote!(_ => ::std::result::Result::Ok(#name::#vname))
+        }
+        None => quote!(_ => ::std::result::Result::Err(::dynomite::AttributeError::InvalidFormat)),
+    };
+
+    // #[dynomite(map_key)] enums are also parsed from and displayed as their strings,
+    // which map keys are converted with
+    let map_key_impls = if map_key {
+        let display_match_arms = variants.iter().map(|var| {
+            let vname = &var.inner.ident;
+            let deser_name = var.deser_name();
+            quote! {
+                #name::#vname => #deser_name,
+            }
+        });
+        quote! {
+            impl ::std::str::FromStr for #name {
+                type Err = #err;
+
+                fn from_str(value: &str) -> ::std::result::Result<Self, #err> {
+                    match value {
                         #(#from_match_arms)*
-                        _ => ::std::result::Result::Err(::dynomite::AttributeError::InvalidFormat)
+                        #fallback_arm
+                    }
+                }
+            }
+
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(match self {
+                        #(#display_match_arms)*
                     })
+                }
             }
         }
+    } else {
+        quote!()
+    };
+
+    quote! {
+        #map_key_impls
+
+        impl #attr for #name {
+            const ATTRIBUTE_TYPE: ::std::option::Option<&'static str> = ::std::option::Option::Some("S");
+
+            fn into_attr(self) -> ::dynomite::dynamodb::AttributeValue {
+                let arm = match self {
+                    #(#into_match_arms)*
+                };
+                ::dynomite::dynamodb::AttributeValue {
+                    s: ::std::option::Option::Some(arm),
+                    ..::std::default::Default::default()
+                }
+            }
+            fn from_attr(value: ::dynomite::dynamodb::AttributeValue) -> ::std::result::Result<Self, #err> {
+                let err = #err::wrong_type("S", &value);
+                value.s.ok_or(err)
+                    .and_then(|value| match &value[..] {
+                        #(#from_match_arms)*
+                        #fallback_arm
+                    })
+            }
+        }
+
+        impl ::dynomite::encoding::Scalar for #name {}
     }
 }
 
@@ -419,18 +1205,40 @@ fn expand_attributes(ast: DeriveInput) -> syn::Result<TokenStream> {
     use syn::spanned::Spanned as _;
     let name = ast.ident;
     let tokens = match ast.data {
-        syn::Data::Struct(DataStruct { fields, .. }) => match fields {
-            Fields::Named(named) => {
-                make_dynomite_attrs_for_struct(&name, &named.named.into_iter().collect::<Vec<_>>())
-                    .into_token_stream()
-            }
-            fields => {
-                return Err(syn::Error::new(
-                    fields.span(),
-                    "Dynomite Attributes require named fields",
-                ))
+        syn::Data::Struct(DataStruct { fields, .. }) => {
+            let attrs = parse_attrs::<StructAttr>(&ast.attrs);
+            let transparent = attrs
+                .iter()
+                .any(|attr| matches!(attr.kind, StructAttrKind::Transparent));
+            match fields {
+                fields if transparent => match fields.iter().collect::<Vec<_>>().as_slice() {
+                    [field] => make_dynomite_transparent_attrs(&name, field).into_token_stream(),
+                    _ => {
+                        return Err(syn::Error::new(
+                            fields.span(),
+                            "#[dynomite(transparent)] requires a struct with exactly one field",
+                        ))
+                    }
+                },
+                Fields::Named(named) => make_dynomite_attrs_for_struct(
+                    &ast.vis,
+                    &name,
+                    &named.named.into_iter().collect::<Vec<_>>(),
+                    &attrs,
+                )
+                .into_token_stream(),
+                // unit structs are represented by an empty map
+                Fields::Unit => {
+                    make_dynomite_attrs_for_struct(&ast.vis, &name, &[], &attrs).into_token_stream()
+                }
+                fields => {
+                    return Err(syn::Error::new(
+                        fields.span(),
+                        "Dynomite Attributes require named fields",
+                    ))
+                }
             }
-        },
+        }
         syn::Data::Enum(data_enum) => {
             make_dynomite_attrs_for_enum(&DataEnum::new(name, data_enum, &ast.attrs))
                 .into_token_stream()
@@ -446,9 +1254,12 @@ fn expand_item(ast: DeriveInput) -> syn::Result<impl ToTokens> {
     let vis = &ast.vis;
     match ast.data {
         syn::Data::Struct(DataStruct { fields, .. }) => match fields {
-            Fields::Named(named) => {
-                make_dynomite_item(vis, name, &named.named.into_iter().collect::<Vec<_>>())
-            }
+            Fields::Named(named) => make_dynomite_item(
+                vis,
+                name,
+                &named.named.into_iter().collect::<Vec<_>>(),
+                &parse_attrs(&ast.attrs),
+            ),
             fields => Err(syn::Error::new(
                 fields.span(),
                 "Dynomite Items require named fields",
@@ -459,6 +1270,8 @@ fn expand_item(ast: DeriveInput) -> syn::Result<impl ToTokens> {
 }
 
 fn make_dynomite_attrs_for_enum(enum_item: &DataEnum) -> impl ToTokens {
+    enum_item.reject_other("#[derive(Attributes)] enums");
+    enum_item.reject_map_key("#[derive(Attributes)] enums");
     let from_attributes = enum_item.impl_from_attributes();
     let into_attributes = enum_item.impl_into_attributes();
     let std_into_attrs = get_std_convert_traits(&enum_item.ident);
@@ -471,23 +1284,84 @@ fn make_dynomite_attrs_for_enum(enum_item: &DataEnum) -> impl ToTokens {
 }
 
 fn make_dynomite_attrs_for_struct(
+    vis: &Visibility,
     name: &Ident,
     fields: &[Field],
+    attrs: &[StructAttr],
 ) -> impl ToTokens {
     let item_fields = fields.iter().map(ItemField::new).collect::<Vec<_>>();
+    validate_unique_names(&item_fields);
     // impl ::dynomite::FromAttributes for Name
-    let from_attribute_map = get_from_attributes_trait(name, &item_fields);
+    let from_attribute_map = get_from_attributes_trait(name, &item_fields, attrs);
     // impl ::dynomite::IntoAttributes for Name
     // impl From<Name> for ::dynomite::Attributes
-    let to_attribute_map = get_into_attribute_map_trait(name, &item_fields);
+    let to_attribute_map = get_into_attribute_map_trait(name, &item_fields, attrs, false);
     // impl TryFrom<::dynomite::Attributes> for Name
     // impl From<Name> for ::dynomite::Attributes
     let std_into_attrs = get_std_convert_traits(name);
+    // pub struct NamePresence ...
+    let presence = get_presence(vis, name, &item_fields, attrs);
 
     quote! {
         #from_attribute_map
         #to_attribute_map
         #std_into_attrs
+        #presence
+    }
+}
+
+/// Validates that no two fields are stored under the same attribute name,
+/// as one would otherwise silently overwrite the other
+fn validate_unique_names(fields: &[ItemField]) {
+    let mut unique_names = HashSet::new();
+    for field in fields
+        .iter()
+        .filter(|field| !field.is_flatten() && !field.is_phantom())
+    {
+        if let Some(existing) = unique_names.replace(field.deser_name()) {
+            abort!(
+                field.field.ident,
+                "Duplicate attribute name detected: `{}`", existing;
+                help = "Please ensure that no `rename = \"attribute_name\"` \
+                clauses conflict with each other and remaining fields' names"
+            );
+        }
+    }
+}
+
+/// ```rust,ignore
+/// impl ::dynomite::FromAttributes for Name {
+///     fn from_attrs(attrs: &mut ::dynomite::Attributes) -> Result<Self, ::dynomite::AttributeError> {
+///         Ok(Self { field: ::dynomite::FromAttributes::from_attrs(attrs)? })
+///     }
+/// }
+/// ```
+fn make_dynomite_transparent_attrs(
+    name: &Ident,
+    field: &Field,
+) -> impl ToTokens {
+    let member = match &field.ident {
+        Some(ident) => quote!(#ident),
+        None => quote!(0),
+    };
+    let std_into_attrs = get_std_convert_traits(name);
+
+    quote! {
+        impl ::dynomite::FromAttributes for #name {
+            fn from_attrs(attrs: &mut ::dynomite::Attributes) -> ::std::result::Result<Self, ::dynomite::AttributeError> {
+                ::std::result::Result::Ok(Self {
+                    #member: ::dynomite::FromAttributes::from_attrs(attrs)?
+                })
+            }
+        }
+
+        impl ::dynomite::IntoAttributes for #name {
+            fn into_attrs(self, attrs: &mut ::dynomite::Attributes) {
+                ::dynomite::IntoAttributes::into_attrs(self.#member, attrs)
+            }
+        }
+
+        #std_into_attrs
     }
 }
 
@@ -495,8 +1369,19 @@ fn make_dynomite_item(
     vis: &Visibility,
     name: &Ident,
     fields: &[Field],
+    attrs: &[StructAttr],
 ) -> syn::Result<impl ToTokens> {
+    if let Some(attr) = attrs
+        .iter()
+        .find(|attr| matches!(attr.kind, StructAttrKind::Transparent))
+    {
+        return Err(syn::Error::new_spanned(
+            &attr.ident,
+            "#[dynomite(transparent)] is only supported by #[derive(Attributes)]",
+        ));
+    }
     let item_fields = fields.iter().map(ItemField::new).collect::<Vec<_>>();
+    validate_unique_names(&item_fields);
     // all items must have 1 primary_key
     let partition_key_count = item_fields.iter().filter(|f| f.is_partition_key()).count();
     if partition_key_count != 1 {
@@ -508,29 +1393,206 @@ fn make_dynomite_item(
             ),
         ));
     }
+    // and at most 1 sort_key
+    let sort_keys = item_fields
+        .iter()
+        .filter(|f| f.is_sort_key())
+        .collect::<Vec<_>>();
+    if let [_, extra, ..] = sort_keys.as_slice() {
+        let names = sort_keys
+            .iter()
+            .map(|f| {
+                format!(
+                    "`{}`",
+                    f.field.ident.as_ref().expect("should have an identifier")
+                )
+            })
+            .collect::<Vec<_>>();
+        return Err(syn::Error::new_spanned(
+            &extra.field.ident,
+            format!(
+                "Item's may declare at most one sort_key. The `{}` Item declared {}: {}",
+                name,
+                sort_keys.len(),
+                names.join(", ")
+            ),
+        ));
+    }
+    // a sort key may be computed rather than stored in a field, but not both
+    if let (Some(field), Some(computed)) = (sort_keys.first(), computed_sort_key(attrs)) {
+        return Err(syn::Error::new_spanned(
+            &field.field.ident,
+            format!(
+                "The `{}` Item declares a computed sort_key named \"{}\" so may not also declare a sort_key field",
+                name,
+                computed.name.value()
+            ),
+        ));
+    }
+    // and at most 1 version, which isn't part of the key
+    let versions = item_fields
+        .iter()
+        .filter(|f| f.is_version())
+        .collect::<Vec<_>>();
+    if let [_, extra, ..] = versions.as_slice() {
+        return Err(syn::Error::new_spanned(
+            &extra.field.ident,
+            format!(
+                "Item's may declare at most one version. The `{}` Item declared {}",
+                name,
+                versions.len()
+            ),
+        ));
+    }
+    if let Some(field) = versions
+        .iter()
+        .find(|f| f.is_partition_key() || f.is_sort_key())
+    {
+        return Err(syn::Error::new_spanned(
+            &field.field.ident,
+            "A version may not also be a partition_key or sort_key, as it changes with every write",
+        ));
+    }
+    // key attributes must be strings, numbers or binary
+    for field in item_fields
+        .iter()
+        .filter(|f| f.is_partition_key() || f.is_sort_key())
+    {
+        if let Some(represented) = non_key_representation(&field.field.ty) {
+            return Err(syn::Error::new_spanned(
+                &field.field.ty,
+                format!(
+                    "partition_key and sort_key fields must be represented as a string (S), number (N) \
+                    or binary (B) value but this type is represented as {}",
+                    represented
+                ),
+            ));
+        }
+    }
     // impl Item for Name + NameKey struct
-    let dynamodb_traits = get_dynomite_item_traits(vis, name, &item_fields)?;
+    let dynamodb_traits = get_dynomite_item_traits(vis, name, &item_fields, attrs)?;
     // impl ::dynomite::FromAttributes for Name
-    let from_attribute_map = get_from_attributes_trait(name, &item_fields);
+    let from_attribute_map = get_from_attributes_trait(name, &item_fields, attrs);
     // impl ::dynomite::IntoAttributes for Name
-    let to_attribute_map = get_into_attribute_map_trait(name, &item_fields);
+    let to_attribute_map = get_into_attribute_map_trait(name, &item_fields, attrs, true);
     // impl TryFrom<::dynomite::Attributes> for Name
     // impl From<Name> for ::dynomite::Attributes
     let std_into_attrs = get_std_convert_traits(name);
+    // pub struct NamePresence ...
+    let presence = get_presence(vis, name, &item_fields, attrs);
 
     Ok(quote! {
         #from_attribute_map
         #to_attribute_map
         #std_into_attrs
         #dynamodb_traits
+        #presence
     })
 }
 
+/// Generated for structs declaring `#[dynomite(presence)]`
+///
+/// ```rust,ignore
+/// #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// pub struct NamePresence {
+///     optional_field: bool,
+/// }
+///
+/// impl Name {
+///     pub fn from_attrs_with_presence(
+///         attrs: &mut ::dynomite::Attributes
+///     ) -> Result<(Self, NamePresence), ::dynomite::AttributeError> {
+///         let presence = NamePresence {
+///             optional_field: attrs.contains_key("optional_field_deser_name"),
+///         };
+///         Ok((::dynomite::FromAttributes::from_attrs(attrs)?, presence))
+///     }
+/// }
+/// ```
+fn get_presence(
+    vis: &Visibility,
+    name: &Ident,
+    fields: &[ItemField],
+    attrs: &[StructAttr],
+) -> proc_macro2::TokenStream {
+    if !attrs
+        .iter()
+        .any(|attr| matches!(attr.kind, StructAttrKind::Presence))
+    {
+        return proc_macro2::TokenStream::new();
+    }
+    let default = attrs
+        .iter()
+        .any(|attr| matches!(attr.kind, StructAttrKind::Default));
+    // fields which may be absent from ddb
+    let optional_fields = fields
+        .iter()
+        .filter(|field| {
+            !field.is_flatten()
+                && !field.is_phantom()
+                && (field.is_optional()
+                    || field.is_default_when_absent()
+                    || field.is_container_default(default)
+                    || option_inner(&field.field.ty).is_some())
+        })
+        .collect::<Vec<_>>();
+    let presence_name = Ident::new(&format!("{}Presence", name), Span::call_site());
+    // fields share the struct's visibility, as presence is useful even for private fields
+    let field_defs = optional_fields.iter().map(|field| {
+        let field_ident = &field.field.ident;
+        let doc = format!("Whether the `{}` attribute was present", field.deser_name());
+        quote! {
+            #[doc = #doc]
+            #vis #field_ident: bool
+        }
+    });
+    let field_checks = optional_fields.iter().map(|field| {
+        let field_ident = &field.field.ident;
+        let field_deser_name = field.deser_name();
+        quote! {
+            #field_ident: attrs.contains_key(#field_deser_name)
+        }
+    });
+    let struct_doc = format!(
+        "Which of the optional fields of a `{}` were present in the attributes it was resolved from",
+        name
+    );
+
+    quote! {
+        #[doc = #struct_doc]
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        #vis struct #presence_name {
+            #(#field_defs),*
+        }
+
+        impl #name {
+            /// Returns an instance resolved from attributes along with which of its optional fields were present,
+            /// distinguishing absent fields from those stored as `NULL`
+            #vis fn from_attrs_with_presence(
+                attrs: &mut ::dynomite::Attributes
+            ) -> ::std::result::Result<(Self, #presence_name), ::dynomite::AttributeError> {
+                let presence = #presence_name {
+                    #(#field_checks),*
+                };
+                let value = <Self as ::dynomite::FromAttributes>::from_attrs(attrs)?;
+                ::std::result::Result::Ok((value, presence))
+            }
+        }
+    }
+}
+
+/// `is_item` is true for `Item`s, whose keys `prepare` is checked not to modify in debug builds
 fn get_into_attribute_map_trait(
     name: &Ident,
     fields: &[ItemField],
+    attrs: &[StructAttr],
+    is_item: bool,
 ) -> impl ToTokens {
-    let into_attrs = get_into_attrs(fields);
+    let prepare = attrs.iter().find_map(|attr| match &attr.kind {
+        StructAttrKind::Prepare(path) => Some(path),
+        _ => None,
+    });
+    let into_attrs = get_into_attrs(fields, prepare, computed_sort_key(attrs), is_item);
 
     quote! {
         impl ::dynomite::IntoAttributes for #name {
@@ -559,24 +1621,41 @@ fn get_std_convert_traits(entity_name: &Ident) -> impl ToTokens {
     }
 }
 
-fn get_into_attrs(fields: &[ItemField]) -> impl ToTokens {
+fn get_into_attrs(
+    fields: &[ItemField],
+    prepare: Option<&Path>,
+    computed_sort_key: Option<&ComputedSortKey>,
+    is_item: bool,
+) -> impl ToTokens {
     let field_conversions = fields.iter().map(|field| {
         let field_deser_name = field.deser_name();
         let field_ident = &field.field.ident;
-
+        let value = field.to_attr(quote!(self.#field_ident));
         let insert_attr = quote! {
             attrs.insert(
                 #field_deser_name.to_string(),
-                ::dynomite::Attribute::into_attr(self.#field_ident)
+                #value
             );
         };
 
-        if let Some(skip_serializing_if) = field.skip_serializing_if() {
+        if field.is_phantom() {
+            quote!()
+        } else if let Some(skip_serializing_if) = field.skip_serializing_if() {
             quote! {
                 if !#skip_serializing_if(&self.#field_ident) {
                     #insert_attr
                 }
             }
+        } else if let Some(prefix) = field.flatten_prefix() {
+            quote! {
+                ::dynomite::flatten::into_prefixed_attrs(self.#field_ident, #prefix, attrs);
+            }
+        } else if field.is_flatten() && option_inner(&field.field.ty).is_some() {
+            quote! {
+                if let ::std::option::Option::Some(value) = self.#field_ident {
+                    ::dynomite::IntoAttributes::into_attrs(value, attrs);
+                }
+            }
         } else if field.is_flatten() {
             quote! {
                 ::dynomite::IntoAttributes::into_attrs(self.#field_ident, attrs);
@@ -586,9 +1665,41 @@ fn get_into_attrs(fields: &[ItemField]) -> impl ToTokens {
         }
     });
 
+    // #[dynomite(prepare = "...")] is called before any fields are written. Keys are read from
+    // unprepared items, e.g. by `Item::key`, so prepare mustn't modify them
+    let (binding, prepare) = match prepare {
+        Some(prepare) if is_item => (
+            quote!(mut self),
+            quote! {
+                #[cfg(debug_assertions)]
+                let key = ::dynomite::Item::key(&self);
+                #prepare(&mut self);
+                #[cfg(debug_assertions)]
+                assert!(
+                    key == ::dynomite::Item::key(&self),
+                    "#[dynomite(prepare = \"...\")] must not modify an item's key fields"
+                );
+            },
+        ),
+        Some(prepare) => (quote!(mut self), quote!(#prepare(&mut self);)),
+        None => (quote!(self), quote!()),
+    };
+
+    // #[dynomite(sort_key(...))] is computed before fields are moved out of self
+    let (compute_sort_key, insert_sort_key) = match computed_sort_key {
+        Some(ComputedSortKey { name, compute }) => (
+            quote!(let sort_key: ::std::string::String = #compute(&self);),
+            quote!(attrs.insert(#name.to_string(), ::dynomite::Attribute::into_attr(sort_key));),
+        ),
+        None => (quote!(), quote!()),
+    };
+
     quote! {
-        fn into_attrs(self, attrs: &mut ::dynomite::Attributes) {
+        fn into_attrs(#binding, attrs: &mut ::dynomite::Attributes) {
+            #prepare
+            #compute_sort_key
             #(#field_conversions)*
+            #insert_sort_key
         }
     }
 }
@@ -608,46 +1719,126 @@ fn get_into_attrs(fields: &[ItemField]) -> impl ToTokens {
 fn get_from_attributes_trait(
     name: &Ident,
     fields: &[ItemField],
+    attrs: &[StructAttr],
 ) -> impl ToTokens {
     let from_attrs = quote!(::dynomite::FromAttributes);
-    let from_attrs_fn = get_from_attrs_function(fields);
+    let finalize = attrs.iter().find_map(|attr| match &attr.kind {
+        StructAttrKind::Finalize(path) => Some(path),
+        _ => None,
+    });
+    let default = attrs
+        .iter()
+        .any(|attr| matches!(attr.kind, StructAttrKind::Default));
+    let deny_unknown_fields = attrs
+        .iter()
+        .any(|attr| matches!(attr.kind, StructAttrKind::DenyUnknownFields));
+    let from_attrs_fn = get_from_attrs_function(
+        fields,
+        finalize,
+        default,
+        deny_unknown_fields,
+        computed_sort_key(attrs),
+    );
+
+    // optionally flattened structs are absent when none of their own fields are present
+    let own_names = own_names(fields);
+    let from_attrs_if_present_fn = (!own_names.is_empty()).then(|| {
+        quote! {
+            fn from_attrs_if_present(
+                attrs: &mut ::dynomite::Attributes
+            ) -> ::std::result::Result<::std::option::Option<Self>, ::dynomite::AttributeError> {
+                if [#(#own_names),*].iter().all(|name| !attrs.contains_key(*name)) {
+                    return ::std::result::Result::Ok(::std::option::Option::None);
+                }
+                #from_attrs::from_attrs(attrs).map(::std::option::Option::Some)
+            }
+        }
+    });
 
     quote! {
         impl #from_attrs for #name {
             #from_attrs_fn
+            #from_attrs_if_present_fn
         }
     }
 }
 
-fn get_from_attrs_function(fields: &[ItemField]) -> impl ToTokens {
-    let var_init_statements = fields
+/// The attribute names of a struct's own fields. Flattened fields are read from the
+/// same attributes so aren't included
+fn own_names(fields: &[ItemField]) -> Vec<String> {
+    fields
         .iter()
-        .map(|field| {
-            // field might have #[dynomite(rename = "...")] attribute
-            let field_deser_name = field.deser_name();
-            let field_ident = &field.field.ident;
-            let expr = if field.is_default_when_absent() {
-                quote! {
-                    match attrs.remove(#field_deser_name) {
-                        Some(field) => ::dynomite::Attribute::from_attr(field)?,
-                        _ => ::std::default::Default::default()
-                    }
-                }
-            } else if field.is_flatten() {
-                quote! { ::dynomite::FromAttributes::from_attrs(attrs)? }
-            } else {
-                quote! {
-                    ::dynomite::Attribute::from_attr(
-                        attrs.remove(#field_deser_name).ok_or_else(|| ::dynomite::AttributeError::MissingField {
-                            name: #field_deser_name.to_string()
-                        })?
-                    )?
+        .filter(|field| !field.is_flatten() && !field.is_phantom())
+        .map(|field| field.deser_name())
+        .collect()
+}
+
+fn get_from_attrs_function(
+    fields: &[ItemField],
+    finalize: Option<&Path>,
+    default: bool,
+    deny_unknown_fields: bool,
+    computed_sort_key: Option<&ComputedSortKey>,
+) -> impl ToTokens {
+    let var_init_statements = fields.iter().map(|field| {
+        // field might have #[dynomite(rename = "...")] attribute
+        let field_deser_name = field.deser_name();
+        let field_ident = &field.field.ident;
+        let from_field = field.parse_attr(quote!(field));
+        // conversion errors are located by the names of the fields leading to them
+        let in_field =
+            quote!(.map_err(|err| ::dynomite::AttributeError::in_field(err, #field_deser_name))?);
+        let expr = if field.is_phantom() {
+            quote!(::std::marker::PhantomData)
+        } else if field.is_optional() {
+            quote! {
+                match attrs.remove(#field_deser_name) {
+                    Some(field) => #from_field #in_field,
+                    _ => ::std::option::Option::None
                 }
+            }
+        } else if field.is_default_when_absent() {
+            let default = match field.default_fn() {
+                Some(path) => quote!(#path()),
+                None => quote!(::std::default::Default::default()),
             };
             quote! {
-                let #field_ident = #expr;
+                match attrs.remove(#field_deser_name) {
+                    Some(field) => #from_field #in_field,
+                    _ => #default
+                }
             }
-        });
+        } else if field.is_container_default(default) {
+            quote! {
+                match attrs.remove(#field_deser_name) {
+                    Some(field) => #from_field #in_field,
+                    _ => __dynomite_default.#field_ident
+                }
+            }
+        } else if let Some(prefix) = field.flatten_prefix() {
+            quote! { ::dynomite::flatten::from_prefixed_attrs(#prefix, attrs)#in_field }
+        } else if field.is_flatten() && option_inner(&field.field.ty).is_some() {
+            quote! { ::dynomite::FromAttributes::from_attrs_if_present(attrs)#in_field }
+        } else if field.is_flatten() {
+            quote! { ::dynomite::FromAttributes::from_attrs(attrs)#in_field }
+        } else {
+            // a missing partition or sort key is reported distinctly from other fields
+            let missing = if field.is_partition_key() || field.is_sort_key() {
+                quote!(MissingKey)
+            } else {
+                quote!(MissingField)
+            };
+            let value = field.parse_attr(quote! {
+                attrs.remove(#field_deser_name).ok_or_else(|| ::dynomite::AttributeError::#missing {
+                    name: #field_deser_name.to_string()
+                })?
+            });
+            quote!(#value #in_field)
+        };
+        quote! {
+            let #field_ident = #expr;
+        }
+    });
 
     let field_names = fields.iter().map(|it| &it.field.ident);
 
@@ -659,12 +1850,49 @@ fn get_from_attrs_function(fields: &[ItemField]) -> impl ToTokens {
     // This is important, because the order of declaration and evaluation
     // of `flatten` fields matters.
 
+    // #[dynomite(finalize = "...")] is called once all fields are read
+    let (binding, finalize) = match finalize {
+        Some(finalize) => (quote!(let mut value), quote!(#finalize(&mut value);)),
+        None => (quote!(let value), quote!()),
+    };
+
+    // #[dynomite(default)] structs take the value of absent fields from `Default::default()`,
+    // while key and flattened fields are read as usual
+    let default = fields
+        .iter()
+        .any(|field| field.is_container_default(default))
+        .then(|| quote!(let __dynomite_default: Self = ::std::default::Default::default();));
+
+    // computed sort keys are write only so are discarded rather than left for flattened fields
+    let discard_sort_key = computed_sort_key.map(|ComputedSortKey { name, .. }| {
+        quote! {
+            attrs.remove(#name);
+        }
+    });
+
+    // #[dynomite(deny_unknown_fields)] structs fail on any attributes left once all fields are read.
+    // The smallest name is reported so that errors don't depend on the map's iteration order
+    let deny_unknown_fields = deny_unknown_fields.then(|| {
+        quote! {
+            if let ::std::option::Option::Some(name) = attrs.keys().min() {
+                return ::std::result::Result::Err(::dynomite::AttributeError::UnknownField {
+                    name: name.clone()
+                });
+            }
+        }
+    });
+
     quote! {
         fn from_attrs(attrs: &mut ::dynomite::Attributes) -> ::std::result::Result<Self, ::dynomite::AttributeError> {
+            #default
+            #discard_sort_key
             #(#var_init_statements)*
-            ::std::result::Result::Ok(Self {
+            #deny_unknown_fields
+            #binding = Self {
                 #(#field_names),*
-            })
+            };
+            #finalize
+            ::std::result::Result::Ok(value)
         }
     }
 }
@@ -673,8 +1901,9 @@ fn get_dynomite_item_traits(
     vis: &Visibility,
     name: &Ident,
     fields: &[ItemField],
+    attrs: &[StructAttr],
 ) -> syn::Result<impl ToTokens> {
-    let impls = get_item_impls(vis, name, fields)?;
+    let impls = get_item_impls(vis, name, fields, attrs)?;
 
     Ok(quote! {
         #impls
@@ -685,15 +1914,28 @@ fn get_item_impls(
     vis: &Visibility,
     name: &Ident,
     fields: &[ItemField],
+    attrs: &[StructAttr],
 ) -> syn::Result<impl ToTokens> {
     // impl ::dynomite::Item for Name ...
-    let item_trait = get_item_trait(name, fields)?;
+    let item_trait = get_item_trait(name, fields, computed_sort_key(attrs))?;
     // pub struct NameKey ...
-    let key_struct = get_key_struct(vis, name, fields)?;
+    let key_struct = get_key_struct(vis, name, fields, attrs)?;
+    // pub struct NameIndexNameKey ...
+    let gsi_key_structs = get_gsi_key_structs(vis, name, fields, attrs)?;
+    // impl Name { fn global_secondary_indexes() ... }
+    let gsis = get_global_secondary_indexes(name, fields)?;
+    // impl Name { fn create_table_input(...) ... }
+    let create_table_input = get_create_table_input(name, fields, attrs)?;
+    // impl Name { fn put_if_not_exists_input(...) ... }
+    let put_if_not_exists_input = get_put_if_not_exists_input(name, fields);
 
     Ok(quote! {
         #item_trait
         #key_struct
+        #gsi_key_structs
+        #gsis
+        #create_table_input
+        #put_if_not_exists_input
     })
 }
 
@@ -704,11 +1946,24 @@ fn get_item_impls(
 ///     keys.insert("field_deser_name", to_attribute_value(field));
 ///     keys
 ///   }
+///
+///   // moves rather than clones the key fields, so like into_attrs can't be derived for Drop types
+///   fn into_key(self) -> ::std::collections::HashMap<String, ::dynomite::dynamodb::AttributeValue> {
+///     let mut keys = ::std::collections::HashMap::new();
+///     keys.insert("field_deser_name", to_attribute_value(self.field));
+///     keys
+///   }
+///
+///   // only for items with a sort key, which may be computed by a function of &self
+///   fn sort_key(&self) -> Option<(String, ::dynomite::dynamodb::AttributeValue)> {
+///     Some(("field_deser_name".to_string(), to_attribute_value(field)))
+///   }
 /// }
 /// ```
 fn get_item_trait(
     name: &Ident,
     fields: &[ItemField],
+    computed_sort_key: Option<&ComputedSortKey>,
 ) -> syn::Result<impl ToTokens> {
     let item = quote!(::dynomite::Item);
     let attribute_map = quote!(
@@ -716,8 +1971,52 @@ fn get_item_trait(
     );
     let partition_key_field = fields.iter().find(|f| f.is_partition_key());
     let sort_key_field = fields.iter().find(|f| f.is_sort_key());
-    let partition_key_insert = partition_key_field.map(get_key_inserter).transpose()?;
-    let sort_key_insert = sort_key_field.map(get_key_inserter).transpose()?;
+    let partition_key_insert = partition_key_field
+        .map(|field| get_key_inserter(field, false))
+        .transpose()?;
+    let owned_partition_key_insert = partition_key_field
+        .map(|field| get_key_inserter(field, true))
+        .transpose()?;
+    let owned_sort_key_insert = sort_key_field
+        .map(|field| get_key_inserter(field, true))
+        .transpose()?;
+    let sort_key_value = sort_key_field
+        .map(|field| {
+            let field_deser_name = field.deser_name();
+            let field_ident = &field.field.ident;
+            (
+                quote!(#field_deser_name),
+                field.to_attr(quote!(self.#field_ident.clone())),
+            )
+        })
+        .or_else(|| {
+            computed_sort_key.map(|ComputedSortKey { name, compute }| {
+                (
+                    quote!(#name),
+                    quote!(::dynomite::Attribute::into_attr(#compute(self))),
+                )
+            })
+        });
+    // computed sort keys borrow the item so are inserted before any fields are moved out of it
+    let computed_sort_key_insert = computed_sort_key.map(|ComputedSortKey { name, compute }| {
+        quote! {
+            keys.insert(#name.to_string(), ::dynomite::Attribute::into_attr(#compute(&self)));
+        }
+    });
+    let sort_key_insert = sort_key_value.as_ref().map(|(name, value)| {
+        quote! {
+            keys.insert(#name.to_string(), #value);
+        }
+    });
+    // fn put_input(...) overriding the trait's for versioned items
+    let put_input = get_put_input(fields);
+    let sort_key_fn = sort_key_value.map(|(name, value)| {
+        quote! {
+            fn sort_key(&self) -> ::std::option::Option<(String, ::dynomite::dynamodb::AttributeValue)> {
+                ::std::option::Option::Some((#name.to_string(), #value))
+            }
+        }
+    });
 
     Ok(partition_key_field
         .map(|_| {
@@ -729,6 +2028,26 @@ fn get_item_trait(
                         #sort_key_insert
                         keys
                     }
+
+                    fn into_key(self) -> #attribute_map {
+                        let mut keys = ::std::collections::HashMap::new();
+                        #computed_sort_key_insert
+                        #owned_partition_key_insert
+                        #owned_sort_key_insert
+                        keys
+                    }
+
+                    #sort_key_fn
+
+                    #put_input
+
+                    fn key_schema() -> ::std::option::Option<(
+                        ::std::vec::Vec<::dynomite::dynamodb::KeySchemaElement>,
+                        ::std::vec::Vec<::dynomite::dynamodb::AttributeDefinition>,
+                    )> {
+                        let input = Self::create_table_input(::std::string::String::new());
+                        ::std::option::Option::Some((input.key_schema, input.attribute_definitions))
+                    }
                 }
             }
         })
@@ -740,21 +2059,29 @@ fn get_item_trait(
 ///   "field_deser_name", to_attribute_value(field)
 /// );
 /// ```
-fn get_key_inserter(field: &ItemField) -> syn::Result<impl ToTokens> {
-    let to_attribute_value = quote!(::dynomite::Attribute::into_attr);
-
+///
+/// The field is moved out of `self` when `owned`, and cloned otherwise
+fn get_key_inserter(
+    field: &ItemField,
+    owned: bool,
+) -> syn::Result<impl ToTokens> {
     let field_deser_name = field.deser_name();
     let field_ident = &field.field.ident;
+    let value = field.to_attr(if owned {
+        quote!(self.#field_ident)
+    } else {
+        quote!(self.#field_ident.clone())
+    });
     Ok(quote! {
         keys.insert(
             #field_deser_name.to_string(),
-            #to_attribute_value(self.#field_ident.clone())
+            #value
         );
     })
 }
 
 /// ```rust,ignore
-/// #[derive(Item, Debug, Clone, PartialEq)]
+/// #[derive(Item, Debug, Clone, PartialEq, ...key_derive)]
 /// pub struct NameKey {
 ///    partition_key_field,
 ///    range_key
@@ -764,52 +2091,462 @@ fn get_key_struct(
     vis: &Visibility,
     name: &Ident,
     fields: &[ItemField],
+    attrs: &[StructAttr],
 ) -> syn::Result<impl ToTokens> {
     let name = Ident::new(&format!("{}Key", name), Span::call_site());
+    let partition_key_field = fields.iter().find(|field| field.is_partition_key());
+    // computed sort keys are represented by a `sort_key` field named after their attribute
+    let sort_key_field = fields
+        .iter()
+        .find(|field| field.is_sort_key())
+        .map(key_field)
+        .or_else(|| {
+            computed_sort_key(attrs).map(|ComputedSortKey { name, .. }| {
+                quote! {
+                    #[dynomite(rename = #name)]
+                    #vis sort_key: ::std::string::String
+                }
+            })
+        });
+    let derives = key_derives(attrs);
 
-    let partition_key_field = fields
+    Ok(partition_key_field
+        .map(|partition_key_field| {
+            key_struct(vis, &name, partition_key_field, sort_key_field, &derives)
+        })
+        .unwrap_or_else(proc_macro2::TokenStream::new))
+}
+
+/// ```rust,ignore
+/// #[derive(Item, Debug, Clone, PartialEq, ...key_derive)]
+/// pub struct NameIndexNameKey {
+///    index_partition_key_field,
+///    index_range_key
+/// }
+/// ```
+fn get_gsi_key_structs(
+    vis: &Visibility,
+    name: &Ident,
+    fields: &[ItemField],
+    attrs: &[StructAttr],
+) -> syn::Result<impl ToTokens> {
+    let derives = key_derives(attrs);
+    let structs = get_gsi_fields(fields)?
+        .into_iter()
+        .map(|(index, (partition_key_field, sort_key_field))| {
+            let name = Ident::new(
+                &format!("{}{}Key", name, upper_camel_case(&index)),
+                Span::call_site(),
+            );
+            key_struct(
+                vis,
+                &name,
+                partition_key_field,
+                sort_key_field.map(key_field),
+                &derives,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        #(#structs)*
+    })
+}
+
+fn key_struct(
+    vis: &Visibility,
+    name: &Ident,
+    partition_key_field: &ItemField,
+    sort_key_field: Option<proc_macro2::TokenStream>,
+    derives: &[&Path],
+) -> proc_macro2::TokenStream {
+    let partition_key_field = key_field(partition_key_field);
+
+    quote! {
+        #[derive(::dynomite::Attributes, Debug, Clone, PartialEq #(, #derives)*)]
+        #vis struct #name {
+            #partition_key_field,
+            #sort_key_field
+        }
+    }
+}
+
+fn key_field(field: &ItemField) -> proc_macro2::TokenStream {
+    // clone because this is a new struct
+    // note: this in inherits field attrs so that
+    // we retain dynomite(rename = "xxx")
+    let mut field = field.field.clone();
+    field.attrs.retain(is_dynomite_attr);
+
+    quote! {
+        #field
+    }
+}
+
+/// The additional traits declared with `#[dynomite(key_derive(...))]` for key structs
+fn key_derives(attrs: &[StructAttr]) -> Vec<&Path> {
+    attrs
         .iter()
-        .find(|field| field.is_partition_key())
-        .cloned()
-        .map(|field| {
-            // clone because this is a new struct
-            // note: this in inherits field attrs so that
-            // we retain dynomite(rename = "xxx")
-            let mut field = field.field.clone();
-            field.attrs.retain(is_dynomite_attr);
+        .filter_map(|attr| match &attr.kind {
+            StructAttrKind::KeyDerive(paths) => Some(paths.as_slice()),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
 
-            quote! {
-                #field
+fn computed_sort_key(attrs: &[StructAttr]) -> Option<&ComputedSortKey> {
+    attrs.iter().find_map(|attr| match &attr.kind {
+        StructAttrKind::ComputedSortKey(computed) => Some(computed),
+        _ => None,
+    })
+}
+
+/// Converts an index name such as `by_status` or `status-index` into `ByStatus` or `StatusIndex`
+fn upper_camel_case(index: &str) -> String {
+    index
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// Groups the fields of each declared global secondary index by index name
+/// as `(partition_key_field, sort_key_field)` pairs
+fn get_gsi_fields<'a>(
+    fields: &'a [ItemField<'a>]
+) -> syn::Result<BTreeMap<String, (&'a ItemField<'a>, Option<&'a ItemField<'a>>)>> {
+    let mut partition_keys = BTreeMap::new();
+    let mut sort_keys = BTreeMap::new();
+    for field in fields {
+        for gsi in field.gsis() {
+            let keys = match gsi.key {
+                KeyKind::Partition => &mut partition_keys,
+                KeyKind::Sort => &mut sort_keys,
+            };
+            if keys.insert(gsi.name.value(), field).is_some() {
+                return Err(syn::Error::new(
+                    gsi.name.span(),
+                    format!(
+                        "Global secondary index `{}` may only declare one {}",
+                        gsi.name.value(),
+                        match gsi.key {
+                            KeyKind::Partition => "partition_key",
+                            KeyKind::Sort => "sort_key",
+                        }
+                    ),
+                ));
             }
-        });
+        }
+    }
+    if let Some((index, field)) = sort_keys
+        .iter()
+        .find(|(index, _)| !partition_keys.contains_key(*index))
+    {
+        let gsi = field
+            .gsis()
+            .find(|gsi| &gsi.name.value() == index)
+            .expect("index was declared on this field");
+        return Err(syn::Error::new(
+            gsi.name.span(),
+            format!(
+                "Global secondary index `{}` must declare a partition_key",
+                index
+            ),
+        ));
+    }
+    Ok(partition_keys
+        .into_iter()
+        .map(|(index, partition_key)| {
+            let sort_key = sort_keys.remove(&index);
+            (index, (partition_key, sort_key))
+        })
+        .collect())
+}
 
-    let sort_key_field = fields
+/// ```rust,ignore
+/// impl Name {
+///     pub fn global_secondary_indexes() -> Vec<::dynomite::dynamodb::GlobalSecondaryIndex> {
+///         vec![
+///             ::dynomite::dynamodb::GlobalSecondaryIndex {
+///                 index_name: "IndexName".to_string(),
+///                 key_schema: vec![..],
+///                 projection: ..,
+///                 ..Default::default()
+///             }
+///         ]
+///     }
+/// }
+/// ```
+fn get_global_secondary_indexes(
+    name: &Ident,
+    fields: &[ItemField],
+) -> syn::Result<impl ToTokens> {
+    let gsis = get_gsi_fields(fields)?;
+    if gsis.is_empty() {
+        return Ok(proc_macro2::TokenStream::new());
+    }
+    let indexes = gsis.iter().map(|(index, (partition_key, sort_key))| {
+        let key_schema = std::iter::once((partition_key, "HASH"))
+            .chain(sort_key.iter().map(|sort_key| (sort_key, "RANGE")))
+            .map(|(field, key_type)| {
+                let attribute_name = field.deser_name();
+                quote! {
+                    ::dynomite::dynamodb::KeySchemaElement {
+                        attribute_name: #attribute_name.to_string(),
+                        key_type: #key_type.to_string(),
+                    }
+                }
+            });
+        quote! {
+            ::dynomite::dynamodb::GlobalSecondaryIndex {
+                index_name: #index.to_string(),
+                key_schema: ::std::vec![#(#key_schema),*],
+                projection: ::dynomite::dynamodb::Projection {
+                    projection_type: ::std::option::Option::Some("ALL".to_string()),
+                    ..::std::default::Default::default()
+                },
+                ..::std::default::Default::default()
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #name {
+            /// Returns the definitions of the global secondary indexes declared on this item's fields
+            pub fn global_secondary_indexes() -> ::std::vec::Vec<::dynomite::dynamodb::GlobalSecondaryIndex> {
+                ::std::vec![#(#indexes),*]
+            }
+        }
+    })
+}
+
+/// ```rust,ignore
+/// impl Name {
+///     pub fn create_table_input(table_name: impl Into<String>) -> ::dynomite::dynamodb::CreateTableInput {
+///         ::dynomite::dynamodb::CreateTableInput {
+///             table_name: table_name.into(),
+///             key_schema: vec![..],
+///             attribute_definitions: vec![..],
+///             billing_mode: Some("PAY_PER_REQUEST".to_string()),
+///             global_secondary_indexes: Some(Self::global_secondary_indexes()),
+///             ..Default::default()
+///         }
+///     }
+/// }
+/// ```
+fn get_create_table_input(
+    name: &Ident,
+    fields: &[ItemField],
+    attrs: &[StructAttr],
+) -> syn::Result<impl ToTokens> {
+    let partition_key = match fields.iter().find(|field| field.is_partition_key()) {
+        Some(field) => (field.deser_name(), key_attribute_type(field)),
+        None => return Ok(proc_macro2::TokenStream::new()),
+    };
+    let sort_key = fields
         .iter()
         .find(|field| field.is_sort_key())
-        .cloned()
-        .map(|field| {
-            // clone because this is a new struct
-            // note: this in inherits field attrs so that
-            // we retain dynomite(rename = "xxx")
-            let mut field = field.field.clone();
-            field.attrs.retain(is_dynomite_attr);
-
+        .map(|field| (field.deser_name(), key_attribute_type(field)))
+        .or_else(|| computed_sort_key(attrs).map(|computed| (computed.name.value(), quote!("S"))));
+    let key_schema = std::iter::once((&partition_key, "HASH"))
+        .chain(sort_key.iter().map(|sort_key| (sort_key, "RANGE")))
+        .map(|((attribute_name, _), key_type)| {
             quote! {
-                #field
+                ::dynomite::dynamodb::KeySchemaElement {
+                    attribute_name: #attribute_name.to_string(),
+                    key_type: #key_type.to_string(),
+                }
             }
         });
 
-    Ok(partition_key_field
-        .map(|partition_key_field| {
-            quote! {
-                #[derive(::dynomite::Attributes, Debug, Clone, PartialEq)]
-                #vis struct #name {
-                    #partition_key_field,
-                    #sort_key_field
+    // every key attribute of the table and its indexes must be defined, once
+    let gsis = get_gsi_fields(fields)?;
+    let mut definitions: Vec<(String, proc_macro2::TokenStream)> = Vec::new();
+    let keys = std::iter::once(partition_key.clone())
+        .chain(sort_key.clone())
+        .chain(gsis.values().flat_map(|(partition_key, sort_key)| {
+            std::iter::once(*partition_key)
+                .chain(*sort_key)
+                .map(|field| (field.deser_name(), key_attribute_type(field)))
+        }));
+    for key in keys {
+        if !definitions.iter().any(|(name, _)| *name == key.0) {
+            definitions.push(key);
+        }
+    }
+    let attribute_definitions = definitions.iter().map(|(attribute_name, attribute_type)| {
+        quote! {
+            ::dynomite::dynamodb::AttributeDefinition {
+                attribute_name: #attribute_name.to_string(),
+                attribute_type: #attribute_type.to_string(),
+            }
+        }
+    });
+    let global_secondary_indexes = if gsis.is_empty() {
+        quote!(::std::option::Option::None)
+    } else {
+        quote!(::std::option::Option::Some(Self::global_secondary_indexes()))
+    };
+
+    Ok(quote! {
+        impl #name {
+            /// Returns the input for creating an on-demand table of this item, defining its key
+            /// attributes and global secondary indexes
+            pub fn create_table_input(
+                table_name: impl ::std::convert::Into<::std::string::String>
+            ) -> ::dynomite::dynamodb::CreateTableInput {
+                ::dynomite::dynamodb::CreateTableInput {
+                    table_name: table_name.into(),
+                    key_schema: ::std::vec![#(#key_schema),*],
+                    attribute_definitions: ::std::vec![#(#attribute_definitions),*],
+                    billing_mode: ::std::option::Option::Some("PAY_PER_REQUEST".to_string()),
+                    global_secondary_indexes: #global_secondary_indexes,
+                    ..::std::default::Default::default()
                 }
             }
-        })
-        .unwrap_or_else(proc_macro2::TokenStream::new))
+        }
+    })
+}
+
+/// ```rust,ignore
+/// impl Name {
+///     pub fn put_if_not_exists_input(self, table_name: impl Into<String>) -> ::dynomite::dynamodb::PutItemInput {
+///         ::dynomite::dynamodb::PutItemInput {
+///             table_name: table_name.into(),
+///             item: self.into(),
+///             condition_expression: Some("attribute_not_exists(#pk)".to_string()),
+///             expression_attribute_names: Some(hashmap! { "#pk" => "partition_key_deser_name" }),
+///             ..Default::default()
+///         }
+///     }
+/// }
+/// ```
+fn get_put_if_not_exists_input(
+    name: &Ident,
+    fields: &[ItemField],
+) -> proc_macro2::TokenStream {
+    let partition_key = match fields.iter().find(|field| field.is_partition_key()) {
+        Some(field) => field.deser_name(),
+        None => return proc_macro2::TokenStream::new(),
+    };
+
+    quote! {
+        impl #name {
+            /// Returns the input for putting this item unless an item with the same partition key
+            /// already exists, in which case the put fails with a `ConditionalCheckFailed` error
+            pub fn put_if_not_exists_input(
+                self,
+                table_name: impl ::std::convert::Into<::std::string::String>
+            ) -> ::dynomite::dynamodb::PutItemInput {
+                let mut names = ::std::collections::HashMap::new();
+                names.insert("#pk".to_string(), #partition_key.to_string());
+                ::dynomite::dynamodb::PutItemInput {
+                    table_name: table_name.into(),
+                    item: self.into(),
+                    condition_expression: ::std::option::Option::Some(
+                        "attribute_not_exists(#pk)".to_string()
+                    ),
+                    expression_attribute_names: ::std::option::Option::Some(names),
+                    ..::std::default::Default::default()
+                }
+            }
+        }
+    }
+}
+
+/// Generated within `impl ::dynomite::Item for Name` for items with a `#[dynomite(version)]` field,
+/// overriding the trait's unconditional put so that generic code puts items with their versions
+///
+/// ```rust,ignore
+/// fn put_input(mut self, table_name: impl Into<String>) -> ::dynomite::dynamodb::PutItemInput {
+///     let expected = self.version;
+///     self.version += 1;
+///     if expected == Default::default() {
+///         // condition `attribute_not_exists(#pk)`, as in `put_if_not_exists_input`
+///     } else {
+///         // condition `#version = :expected`
+///     }
+/// }
+/// ```
+fn get_put_input(fields: &[ItemField]) -> proc_macro2::TokenStream {
+    let (partition_key, version) = match (
+        fields.iter().find(|field| field.is_partition_key()),
+        fields.iter().find(|field| field.is_version()),
+    ) {
+        (Some(partition_key), Some(version)) => (partition_key, version),
+        _ => return proc_macro2::TokenStream::new(),
+    };
+    let partition_key = partition_key.deser_name();
+    let version_deser_name = version.deser_name();
+    let version_ident = &version.field.ident;
+    let version_ty = &version.field.ty;
+    let expected_value = version.to_attr(quote!(expected));
+
+    quote! {
+        /// Returns the input for putting this item with its version incremented, on the condition
+        /// that the stored item still has this item's version. Items with a default version are
+        /// expected not to have been stored yet, so are put on the condition that no item with the
+        /// same partition key exists. Either condition failing fails the put with a
+        /// `ConditionalCheckFailed` error
+        fn put_input(
+            mut self,
+            table_name: impl ::std::convert::Into<::std::string::String>
+        ) -> ::dynomite::dynamodb::PutItemInput {
+            let expected = self.#version_ident;
+            self.#version_ident += 1;
+            let mut names = ::std::collections::HashMap::new();
+            let (condition, values) = if expected == <#version_ty as ::std::default::Default>::default() {
+                names.insert("#pk".to_string(), #partition_key.to_string());
+                ("attribute_not_exists(#pk)", ::std::option::Option::None)
+            } else {
+                names.insert("#version".to_string(), #version_deser_name.to_string());
+                let mut values = ::std::collections::HashMap::new();
+                values.insert(":expected".to_string(), #expected_value);
+                ("#version = :expected", ::std::option::Option::Some(values))
+            };
+            ::dynomite::dynamodb::PutItemInput {
+                table_name: table_name.into(),
+                item: self.into(),
+                condition_expression: ::std::option::Option::Some(condition.to_string()),
+                expression_attribute_names: ::std::option::Option::Some(names),
+                expression_attribute_values: values,
+                ..::std::default::Default::default()
+            }
+        }
+    }
+}
+
+/// Returns the type descriptor, `S`, `N` or `B`, a key field is represented by, inferred from the
+/// name of its type. Types which aren't known to be numbers or binary are assumed to be strings
+/// The `S`, `N` or `B` type of a key field's attribute definition
+///
+/// Prefixed and base64 encoded values are always strings, other fields are defined by their type's
+/// `Attribute::ATTRIBUTE_TYPE`, which fails to compile when it's `None`
+fn key_attribute_type(field: &ItemField) -> proc_macro2::TokenStream {
+    if field.is_base64() || field.value_prefix().is_some() {
+        return quote!("S");
+    }
+    let ty = &field.field.ty;
+    let message = format!(
+        "the type of key attribute `{}` must declare the `S`, `N` or `B` type it's represented by as `Attribute::ATTRIBUTE_TYPE`",
+        field.deser_name()
+    );
+    quote_spanned! {ty.span()=>
+        {
+            const ATTRIBUTE_TYPE: &str = match <#ty as ::dynomite::Attribute>::ATTRIBUTE_TYPE {
+                ::std::option::Option::Some(attribute_type) => attribute_type,
+                ::std::option::Option::None => ::std::panic!(#message),
+            };
+            ATTRIBUTE_TYPE
+        }
+    }
 }
 
 fn is_dynomite_attr(suspect: &syn::Attribute) -> bool {
@@ -0,0 +1,66 @@
+//! Compact renderings of attribute values for logs and example output
+//!
+//! Where the [`test`](../test/index.html) module's [`Av`](../test/struct.Av.html) wrapper is geared
+//! towards assertions, the functions here render borrowed values as text directly, showing
+//! only the type of each value which is set, with map entries ordered by name.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{attr_map, debug};
+//!
+//! let attrs = attr_map! {
+//!     "id" => "123",
+//!     "count" => 5,
+//! };
+//! assert_eq!(debug::attrs(&attrs), r#"{ count: N("5"), id: S("123") }"#);
+//! ```
+
+use crate::{test::Av, AttributeValue, Attributes};
+use std::collections::BTreeMap;
+
+/// Renders a map of attributes, e.g. `{ count: N("5"), id: S("123") }`
+pub fn attrs(attrs: &Attributes) -> String {
+    if attrs.is_empty() {
+        return "{}".into();
+    }
+    let entries = attrs
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(name, value)| format!("{}: {}", name, self::value(value)))
+        .collect::<Vec<_>>();
+    format!("{{ {} }}", entries.join(", "))
+}
+
+/// Renders a single attribute value, e.g. `S("123")` or `L([BOOL(true)])`
+pub fn value(value: &AttributeValue) -> String {
+    format!("{:?}", Av(value.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attribute;
+    use maplit::hashmap;
+
+    #[test]
+    fn renders_mixed_attributes() {
+        let attrs = hashmap! {
+            "id".to_string() => "123".to_string().into_attr(),
+            "count".to_string() => 5.into_attr(),
+            "active".to_string() => true.into_attr(),
+            "deleted".to_string() => None::<String>.into_attr(),
+            "tags".to_string() => vec!["a".to_string()].into_attr(),
+            "address".to_string() => hashmap! {
+                "city".to_string() => "Berlin".to_string(),
+            }
+            .into_attr(),
+        };
+        assert_eq!(
+            super::attrs(&attrs),
+            r#"{ active: BOOL(true), address: M({"city": S("Berlin")}), count: N("5"), deleted: NULL, id: S("123"), tags: L([S("a")]) }"#
+        );
+        assert_eq!(super::attrs(&Attributes::new()), "{}");
+    }
+}
@@ -0,0 +1,163 @@
+//! Limits on how deeply nested maps and lists may be when converting `AttributeValue`s
+//!
+//! Conversions of recursive types, such as a tree whose nodes contain a `Vec` of their
+//! children, recurse once per level of nesting. To avoid overflowing the stack on
+//! pathologically deep values, conversions from `M` and `L` values fail with
+//! `AttributeError::TooDeep` once they are nested more than [`DEFAULT_MAX_DEPTH`](constant.DEFAULT_MAX_DEPTH.html)
+//! levels deep. DynamoDB itself supports at most 32 levels of nesting.
+//!
+//! Conversions into `AttributeValue`s are not limited, as their depth is bounded by that of
+//! a value which already exists.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{depth, Attribute};
+//!
+//! let nested = vec![vec![vec![1]]].into_attr();
+//! assert!(depth::with_max_depth(2, || Vec::<Vec<Vec<u32>>>::from_attr(nested.clone())).is_err());
+//! assert!(Vec::<Vec<Vec<u32>>>::from_attr(nested).is_ok());
+//! ```
+
+use crate::AttributeError;
+use std::cell::Cell;
+
+/// The default maximum number of nested maps and lists converted from `AttributeValue`s
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static MAX_DEPTH: Cell<usize> = const { Cell::new(DEFAULT_MAX_DEPTH) };
+}
+
+/// Calls `f` with conversions on the current thread limited to `max` levels of nesting,
+/// restoring the previous limit afterwards
+pub fn with_max_depth<T>(
+    max: usize,
+    f: impl FnOnce() -> T,
+) -> T {
+    let previous = MAX_DEPTH.with(|depth| depth.replace(max));
+    let _restore = Restore(&MAX_DEPTH, previous);
+    f()
+}
+
+/// Calls `f` one level of nesting deeper, failing with `AttributeError::TooDeep`
+/// rather than calling it when that exceeds the maximum depth
+pub(crate) fn nested<T>(
+    f: impl FnOnce() -> Result<T, AttributeError>
+) -> Result<T, AttributeError> {
+    let max = MAX_DEPTH.with(Cell::get);
+    let depth = DEPTH.with(|depth| depth.get()) + 1;
+    if depth > max {
+        return Err(AttributeError::TooDeep { max });
+    }
+    let previous = DEPTH.with(|current| current.replace(depth));
+    let _restore = Restore(&DEPTH, previous);
+    f()
+}
+
+/// Restores a thread local value when dropped, including when unwinding
+struct Restore(&'static std::thread::LocalKey<Cell<usize>>, usize);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let Restore(key, value) = *self;
+        key.with(|current| current.set(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Attribute, AttributeValue};
+    use maplit::hashmap;
+    use std::collections::HashMap;
+
+    /// A recursive type whose depth is only known at runtime
+    #[derive(Debug, PartialEq)]
+    struct Tree(Vec<Tree>);
+
+    impl Attribute for Tree {
+        fn into_attr(self) -> AttributeValue {
+            self.0.into_attr()
+        }
+        fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+            Vec::from_attr(value).map(Tree)
+        }
+    }
+
+    /// Builds a tree `depth` lists deep
+    fn tree(depth: usize) -> Tree {
+        (1..depth).fold(Tree(vec![]), |tree, _| Tree(vec![tree]))
+    }
+
+    #[test]
+    fn converts_values_at_max_depth() {
+        assert_eq!(
+            Tree::from_attr(tree(DEFAULT_MAX_DEPTH).into_attr()),
+            Ok(tree(DEFAULT_MAX_DEPTH))
+        );
+    }
+
+    #[test]
+    fn rejects_values_beyond_max_depth() {
+        assert_eq!(
+            Tree::from_attr(tree(DEFAULT_MAX_DEPTH + 1).into_attr()),
+            Err(AttributeError::TooDeep {
+                max: DEFAULT_MAX_DEPTH
+            })
+        );
+        // the depth is unwound after failing
+        assert_eq!(Tree::from_attr(tree(2).into_attr()), Ok(tree(2)));
+    }
+
+    /// A recursive map type
+    #[derive(Debug, PartialEq)]
+    enum Node {
+        Leaf(u32),
+        Branch(HashMap<String, Node>),
+    }
+
+    impl Attribute for Node {
+        fn into_attr(self) -> AttributeValue {
+            match self {
+                Node::Leaf(n) => n.into_attr(),
+                Node::Branch(map) => map.into_attr(),
+            }
+        }
+        fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+            match value.n {
+                Some(_) => u32::from_attr(value).map(Node::Leaf),
+                None => Attribute::from_attr(value).map(Node::Branch),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_deeply_nested_maps() {
+        let node = (0..=DEFAULT_MAX_DEPTH).fold(Node::Leaf(1), |node, _| {
+            Node::Branch(hashmap! { "a".to_string() => node })
+        });
+        let source = match Node::from_attr(node.into_attr()) {
+            Err(AttributeError::Path { path, source }) => {
+                assert_eq!(path.len(), DEFAULT_MAX_DEPTH);
+                *source
+            }
+            other => panic!("expected a path error but got {:?}", other),
+        };
+        assert_eq!(
+            source,
+            AttributeError::TooDeep {
+                max: DEFAULT_MAX_DEPTH
+            }
+        );
+    }
+
+    #[test]
+    fn with_max_depth_restores_previous_limit() {
+        let nested = tree(4).into_attr();
+        assert!(with_max_depth(3, || Tree::from_attr(nested.clone())).is_err());
+        assert!(with_max_depth(4, || Tree::from_attr(nested.clone())).is_ok());
+        assert_eq!(MAX_DEPTH.with(Cell::get), DEFAULT_MAX_DEPTH);
+    }
+}
@@ -0,0 +1,773 @@
+//! Alternative encodings for attribute values
+//!
+//! These are used by `dynomite-derive` field attributes which change how a field is
+//! represented in DynamoDB, but can also be used directly when implementing [`Attribute`](../trait.Attribute.html)
+//! by hand.
+
+use crate::{Attribute, AttributeError, AttributeValue};
+use bytes::Bytes;
+use std::convert::TryFrom;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+/// Binary values represented as base64 encoded strings by the `S` AttributeValue type
+///
+/// Some producers store binary data as base64 strings rather than DynamoDB's native binary
+/// type. This encoding uses the standard, padded base64 alphabet defined in
+/// [RFC 4648 §4](https://tools.ietf.org/html/rfc4648#section-4).
+///
+/// This is what `#[dynomite(base64)]` fields are serialized with.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::encoding::base64;
+///
+/// let value = base64::into_attr(b"foo".to_vec());
+/// assert_eq!(value.s, Some("Zm9v".to_string()));
+/// assert_eq!(Ok(b"foo".to_vec()), base64::from_attr(value));
+/// ```
+pub mod base64 {
+    use crate::{AttributeError, AttributeValue};
+
+    /// Returns an `S` AttributeValue containing the base64 encoding of `value`
+    pub fn into_attr<B: AsRef<[u8]>>(value: B) -> AttributeValue {
+        AttributeValue {
+            s: Some(::base64::encode(value)),
+            ..AttributeValue::default()
+        }
+    }
+
+    /// Returns the bytes decoded from a base64 encoded `S` AttributeValue
+    pub fn from_attr<B: From<Vec<u8>>>(value: AttributeValue) -> Result<B, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value
+            .s
+            .ok_or(err)
+            .and_then(|s| ::base64::decode(s).map_err(|_| AttributeError::InvalidFormat))
+            .map(B::from)
+    }
+}
+
+/// Enum variants and their optional scalar payload represented together by a single `S` AttributeValue
+///
+/// A variant is encoded as its name, followed by the separator and its payload's `S` or `N`
+/// value when it has one, e.g. `Failed:42`. Values are split at the first occurrence of the
+/// separator, so variant names must not contain it.
+///
+/// This is what `#[dynomite(compact_string)]` enums are serialized with.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::encoding::{compact, Scalar};
+///
+/// let value = compact::into_attr("Failed", ":", Some(42.into_scalar_string()));
+/// assert_eq!(value.s, Some("Failed:42".to_string()));
+///
+/// let (variant, payload) = compact::from_attr(value, ":").unwrap();
+/// assert_eq!(variant, "Failed");
+/// assert_eq!(Ok(42), compact::payload::<u32>(payload));
+/// ```
+pub mod compact {
+    use crate::{Attribute, AttributeError, AttributeValue};
+
+    /// Returns an `S` AttributeValue containing `variant`, followed by `separator` and
+    /// `payload`, the [`Scalar`](../trait.Scalar.html) string of a value, when present
+    pub fn into_attr(
+        variant: &str,
+        separator: &str,
+        payload: Option<String>,
+    ) -> AttributeValue {
+        let s = match payload {
+            Some(payload) => format!("{}{}{}", variant, separator, payload),
+            None => variant.to_string(),
+        };
+        AttributeValue {
+            s: Some(s),
+            ..AttributeValue::default()
+        }
+    }
+
+    /// Splits an `S` AttributeValue into its variant name and payload, if any, at the first `separator`
+    pub fn from_attr(
+        value: AttributeValue,
+        separator: &str,
+    ) -> Result<(String, Option<String>), AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        let s = value.s.ok_or(err)?;
+        Ok(match s.find(separator) {
+            Some(index) => (
+                s[..index].to_string(),
+                Some(s[index + separator.len()..].to_string()),
+            ),
+            None => (s, None),
+        })
+    }
+
+    /// Resolves a payload split from a compact value, reading it as an `S` or else an `N` AttributeValue
+    pub fn payload<P: Attribute>(payload: Option<String>) -> Result<P, AttributeError> {
+        super::scalar_from_string(payload.ok_or(AttributeError::InvalidFormat)?)
+    }
+}
+
+/// Scalar values represented together by a single `S` AttributeValue according to a pattern
+///
+/// Patterns such as `ORDER#{year}#{seq}` interleave literal text with values, each of which is
+/// represented by its `S` or `N` value. Values are split at the first occurrence of the literal
+/// text which follows them, so they must not contain it.
+///
+/// This is what `#[dynomite(compose = "...")]` structs are serialized with, where the pattern is
+/// given as the literal text surrounding each value, e.g. `["ORDER#", "#", ""]`.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::encoding::{compose, Scalar};
+///
+/// let literals = ["ORDER#", "#", ""];
+/// let value = compose::into_attr(
+///     &literals,
+///     vec![2024.into_scalar_string(), 123.into_scalar_string()],
+/// );
+/// assert_eq!(value.s, Some("ORDER#2024#123".to_string()));
+///
+/// let mut values = compose::from_attr(&literals, value).unwrap().into_iter();
+/// assert_eq!(Ok(2024), compose::value::<u16>(values.next()));
+/// assert_eq!(Ok(123), compose::value::<u32>(values.next()));
+/// ```
+pub mod compose {
+    use crate::{Attribute, AttributeError, AttributeValue};
+
+    /// Returns an `S` AttributeValue of `values`, the [`Scalar`](../trait.Scalar.html) strings of
+    /// each value, interleaved with `literals`, which surround each value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `literals` is not one longer than `values`
+    pub fn into_attr(
+        literals: &[&str],
+        values: Vec<String>,
+    ) -> AttributeValue {
+        assert_eq!(
+            literals.len(),
+            values.len() + 1,
+            "composed values must be surrounded by literals"
+        );
+        let mut s = literals[0].to_string();
+        for (value, literal) in values.into_iter().zip(&literals[1..]) {
+            s.push_str(&value);
+            s.push_str(literal);
+        }
+        AttributeValue {
+            s: Some(s),
+            ..AttributeValue::default()
+        }
+    }
+
+    /// Splits an `S` AttributeValue into the values surrounded by `literals`
+    pub fn from_attr(
+        literals: &[&str],
+        value: AttributeValue,
+    ) -> Result<Vec<String>, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        let s = value.s.ok_or(err)?;
+        let (first, rest) = literals
+            .split_first()
+            .ok_or(AttributeError::InvalidFormat)?;
+        let mut remaining = s.strip_prefix(first).ok_or(AttributeError::InvalidFormat)?;
+        let mut values = Vec::with_capacity(rest.len());
+        for (index, literal) in rest.iter().enumerate() {
+            let value = if index + 1 == rest.len() {
+                let value = remaining
+                    .strip_suffix(literal)
+                    .ok_or(AttributeError::InvalidFormat)?;
+                remaining = "";
+                value
+            } else {
+                let end = remaining
+                    .find(literal)
+                    .filter(|_| !literal.is_empty())
+                    .ok_or(AttributeError::InvalidFormat)?;
+                let value = &remaining[..end];
+                remaining = &remaining[end + literal.len()..];
+                value
+            };
+            values.push(value.to_string());
+        }
+        if !remaining.is_empty() {
+            return Err(AttributeError::InvalidFormat);
+        }
+        Ok(values)
+    }
+
+    /// Resolves a value split from a composed value, reading it as an `S` or else an `N` AttributeValue
+    pub fn value<P: Attribute>(value: Option<String>) -> Result<P, AttributeError> {
+        super::scalar_from_string(value.ok_or(AttributeError::InvalidFormat)?)
+    }
+}
+
+/// Scalar values represented by the `S` AttributeValue type with a constant prefix
+///
+/// Single-table designs commonly decorate keys with the kind of item they identify, e.g.
+/// `USER#<uuid>`, while the value itself is better typed without it. Values are represented by
+/// their `S` or `N` value following the prefix. A value which doesn't start with the prefix is
+/// an `InvalidFormat`.
+///
+/// This is what `#[dynomite(prefix = "...")]` fields are serialized with.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::encoding::prefixed;
+///
+/// let value = prefixed::into_attr("USER#", 42);
+/// assert_eq!(value.s, Some("USER#42".to_string()));
+/// assert_eq!(Ok(42), prefixed::from_attr("USER#", value));
+/// ```
+pub mod prefixed {
+    use super::Scalar;
+    use crate::{Attribute, AttributeError, AttributeValue};
+
+    /// Returns an `S` AttributeValue of `value` following `prefix`
+    pub fn into_attr<A: Scalar>(
+        prefix: &str,
+        value: A,
+    ) -> AttributeValue {
+        AttributeValue {
+            s: Some(format!("{}{}", prefix, value.into_scalar_string())),
+            ..AttributeValue::default()
+        }
+    }
+
+    /// Returns `A` read from the remainder of an `S` AttributeValue following `prefix`
+    pub fn from_attr<A: Attribute>(
+        prefix: &str,
+        value: AttributeValue,
+    ) -> Result<A, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        let s = value.s.ok_or(err)?;
+        let value = s
+            .strip_prefix(prefix)
+            .ok_or(AttributeError::InvalidFormat)?;
+        super::scalar_from_string(value.to_string())
+    }
+}
+
+/// A type represented by the `S` or `N` AttributeValue types
+///
+/// Only these values may be embedded in strings, so this bounds the values of `#[dynomite(prefix = "...")]`
+/// fields, the fields of `#[dynomite(compose = "...")]` structs and the payloads of
+/// `#[dynomite(compact_string)]` enum variants, which fail to compile for other types.
+/// `#[derive(Attribute)]` implements it for enums, composed structs and newtypes of scalar types.
+///
+/// Types represented by any other AttributeValue type must not implement it.
+pub trait Scalar: Attribute {
+    /// Returns the `S` or `N` value this is represented by
+    fn into_scalar_string(self) -> String {
+        let value = self.into_attr();
+        value
+            .s
+            .or(value.n)
+            .expect("Scalar types are represented by S or N values")
+    }
+}
+
+/// Reads a string embedded from a `Scalar` as an `S` or else an `N` AttributeValue
+fn scalar_from_string<P: Attribute>(value: String) -> Result<P, AttributeError> {
+    match P::from_attr(AttributeValue {
+        s: Some(value.clone()),
+        ..AttributeValue::default()
+    }) {
+        Err(AttributeError::WrongType { .. }) => P::from_attr(AttributeValue {
+            n: Some(value),
+            ..AttributeValue::default()
+        }),
+        result => result,
+    }
+}
+
+/// Values read leniently from either of the `S` or `N` AttributeValue types
+///
+/// While migrating a table, the same attribute may be stored as a number in older items
+/// and as a string in newer ones. Values of the wrong one of these types are read as though
+/// they were stored as the other, so a `String` may be read from an `N` value and a number
+/// may be read from an `S` value holding its digits. Values are written as usual.
+///
+/// This is what `#[dynomite(lenient)]` fields are deserialized with.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::{encoding::lenient, Attribute};
+///
+/// assert_eq!(Ok("42".to_string()), lenient::from_attr(42.into_attr()));
+/// assert_eq!(Ok(42), lenient::from_attr("42".to_string().into_attr()));
+/// ```
+pub mod lenient {
+    use crate::{Attribute, AttributeError, AttributeValue};
+
+    /// Returns `A` read from `value`, or from the same string or number as the other of the `S`
+    /// or `N` types should `value` be of the wrong type
+    pub fn from_attr<A: Attribute>(value: AttributeValue) -> Result<A, AttributeError> {
+        let other = match &value {
+            AttributeValue {
+                s: Some(s),
+                n: None,
+                ..
+            } => Some(AttributeValue {
+                n: Some(s.clone()),
+                ..AttributeValue::default()
+            }),
+            AttributeValue {
+                s: None,
+                n: Some(n),
+                ..
+            } => Some(AttributeValue {
+                s: Some(n.clone()),
+                ..AttributeValue::default()
+            }),
+            _ => None,
+        };
+        match (A::from_attr(value), other) {
+            (Err(AttributeError::WrongType { .. }), Some(other)) => A::from_attr(other),
+            (result, _) => result,
+        }
+    }
+}
+
+macro_rules! big_endian_attr {
+    ($(#[$meta:meta])* $name:ident($type:ty; $len:expr)) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub $type);
+
+        impl From<$type> for $name {
+            fn from(value: $type) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for $type {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Attribute for $name {
+            const ATTRIBUTE_TYPE: Option<&'static str> = Some("B");
+
+            fn into_attr(self) -> AttributeValue {
+                AttributeValue {
+                    b: Some(Bytes::copy_from_slice(&self.0.to_be_bytes())),
+                    ..AttributeValue::default()
+                }
+            }
+            fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+                let err = AttributeError::wrong_type("B", &value);
+                let bytes = value.b.ok_or(err)?;
+                <[u8; $len]>::try_from(&bytes[..])
+                    .map(|bytes| $name(<$type>::from_be_bytes(bytes)))
+                    .map_err(|_| AttributeError::InvalidFormat)
+            }
+        }
+    };
+}
+
+big_endian_attr!(
+    /// A `u32` represented by the `B` AttributeValue type as 4 big-endian bytes
+    ///
+    /// DynamoDB compares binary sort keys byte by byte, so unlike the `N` representation
+    /// of a `u32` this is both compact and sorts in numeric order. Little-endian bytes
+    /// were considered but don't: `256` would sort before `1`, as its first byte is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynomite::{encoding::Be32, Attribute};
+    ///
+    /// let value = Be32(1).into_attr();
+    /// assert_eq!(value.b.as_deref(), Some(&[0, 0, 0, 1][..]));
+    /// assert_eq!(Ok(Be32(1)), Be32::from_attr(value));
+    /// ```
+    Be32(u32; 4)
+);
+
+big_endian_attr!(
+    /// A `u64` represented by the `B` AttributeValue type as 8 big-endian bytes
+    ///
+    /// See [`Be32`](struct.Be32.html) for details.
+    Be64(u64; 8)
+);
+
+/// A `Uuid` represented by the `B` AttributeValue type as its 16 bytes
+///
+/// `Uuid`s are represented by their 36 character hyphenated string form by default. Storing
+/// their bytes instead more than halves the size of the attribute and of any index containing it.
+/// A value whose length isn't 16 bytes is an `InvalidFormat`.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::{encoding::UuidBin, Attribute};
+/// use uuid::Uuid;
+///
+/// let id = UuidBin(Uuid::new_v4());
+/// let value = id.into_attr();
+/// assert_eq!(value.b.as_ref().map(|b| b.len()), Some(16));
+/// assert_eq!(Ok(id), UuidBin::from_attr(value));
+/// ```
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UuidBin(pub Uuid);
+
+#[cfg(feature = "uuid")]
+impl From<Uuid> for UuidBin {
+    fn from(value: Uuid) -> Self {
+        UuidBin(value)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<UuidBin> for Uuid {
+    fn from(value: UuidBin) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Attribute for UuidBin {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("B");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            b: Some(Bytes::copy_from_slice(self.0.as_bytes())),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("B", &value);
+        let bytes = value.b.ok_or(err)?;
+        Uuid::from_slice(&bytes)
+            .map(UuidBin)
+            .map_err(|_| AttributeError::InvalidFormat)
+    }
+}
+
+/// Two fixed length byte arrays represented together by the `B` AttributeValue type as their concatenation
+///
+/// This makes for compact binary composite keys, such as a tenant id followed by a record id,
+/// which DynamoDB compares byte by byte, so sort by their first array and then their second.
+/// A value whose length isn't `A + B` is an `InvalidFormat`.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::{Attribute, CompositeBinary};
+///
+/// let key = CompositeBinary([1, 2], [3, 4, 5]);
+/// let value = key.into_attr();
+/// assert_eq!(value.b.as_deref(), Some(&[1, 2, 3, 4, 5][..]));
+/// assert_eq!(Ok(key), CompositeBinary::from_attr(value));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompositeBinary<const A: usize, const B: usize>(pub [u8; A], pub [u8; B]);
+
+impl<const A: usize, const B: usize> Attribute for CompositeBinary<A, B> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("B");
+
+    fn into_attr(self) -> AttributeValue {
+        let mut bytes = Vec::with_capacity(A + B);
+        bytes.extend_from_slice(&self.0);
+        bytes.extend_from_slice(&self.1);
+        AttributeValue {
+            b: Some(bytes.into()),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("B", &value);
+        let bytes = value.b.ok_or(err)?;
+        if bytes.len() != A + B {
+            return Err(AttributeError::InvalidFormat);
+        }
+        let (first, second) = bytes.split_at(A);
+        match (<[u8; A]>::try_from(first), <[u8; B]>::try_from(second)) {
+            (Ok(first), Ok(second)) => Ok(CompositeBinary(first, second)),
+            _ => Err(AttributeError::InvalidFormat),
+        }
+    }
+}
+
+/// A 64 bit identifier, such as a snowflake id, represented by the `S` AttributeValue type as its decimal digits
+///
+/// Integers are represented by the `N` type by default, which preserves them in DynamoDB, but
+/// JavaScript clients reading them as numbers lose precision above 2^53. Storing them as strings
+/// lets them round trip through such clients. A value which isn't a decimal `u64` is an `InvalidFormat`.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::{Attribute, StringId};
+///
+/// let id = StringId(1_382_211_358_735_306_752);
+/// let value = id.into_attr();
+/// assert_eq!(value.s.as_deref(), Some("1382211358735306752"));
+/// assert_eq!(Ok(id), StringId::from_attr(value));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StringId(pub u64);
+
+impl From<u64> for StringId {
+    fn from(value: u64) -> Self {
+        StringId(value)
+    }
+}
+
+impl From<StringId> for u64 {
+    fn from(value: StringId) -> Self {
+        value.0
+    }
+}
+
+impl Attribute for StringId {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            s: Some(self.0.to_string()),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err).and_then(|s| {
+            s.parse()
+                .map(StringId)
+                .map_err(|_| AttributeError::InvalidFormat)
+        })
+    }
+}
+
+impl Scalar for StringId {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_splits_at_first_separator() {
+        let value = compact::into_attr("Failed", ":", Some("a:b".to_string()));
+        assert_eq!(
+            Ok(("Failed".to_string(), Some("a:b".to_string()))),
+            compact::from_attr(value, ":")
+        );
+        assert_eq!(
+            Ok(("Pending".to_string(), None)),
+            compact::from_attr(compact::into_attr("Pending", ":", None), ":")
+        );
+    }
+
+    #[test]
+    fn compact_payload_requires_value() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            compact::payload::<u32>(None)
+        );
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            compact::payload::<u32>(Some("forty-two".into()))
+        );
+    }
+
+    #[test]
+    fn compose_round_trip() {
+        let literals = ["ORDER#", "#", ""];
+        let value = compose::into_attr(
+            &literals,
+            vec![2024.into_scalar_string(), "a#b".to_string()],
+        );
+        assert_eq!(value.s.as_deref(), Some("ORDER#2024#a#b"));
+        assert_eq!(
+            Ok(vec!["2024".to_string(), "a#b".to_string()]),
+            compose::from_attr(&literals, value)
+        );
+    }
+
+    #[test]
+    fn compose_with_suffix() {
+        let literals = ["", "-", "!"];
+        assert_eq!(
+            Ok(vec!["a".to_string(), "b".to_string()]),
+            compose::from_attr(&literals, "a-b!".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    fn compose_malformed() {
+        let literals = ["ORDER#", "#", ""];
+        for s in &["INVOICE#2024#1", "ORDER#2024"] {
+            assert_eq!(
+                Err(AttributeError::InvalidFormat),
+                compose::from_attr(&literals, s.to_string().into_attr())
+            );
+        }
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            compose::from_attr(&["", "!"], "a!b".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    fn prefixed_requires_prefix() {
+        assert_eq!(
+            Ok("a#b".to_string()),
+            prefixed::from_attr("USER#", prefixed::into_attr("USER#", "a#b".to_string()))
+        );
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            prefixed::from_attr::<String>("USER#", "ORG#a".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "N"
+            }),
+            prefixed::from_attr::<u32>("USER#", 1.into_attr())
+        );
+    }
+
+    #[test]
+    fn big_endian_round_trip() {
+        for value in &[0, 1, 255, 256, u32::MAX] {
+            assert_eq!(Ok(Be32(*value)), Be32::from_attr(Be32(*value).into_attr()));
+        }
+        for value in &[0, 1, 255, 256, u64::MAX] {
+            assert_eq!(Ok(Be64(*value)), Be64::from_attr(Be64(*value).into_attr()));
+        }
+    }
+
+    #[test]
+    fn big_endian_invalid_length() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Be32::from_attr(Be64(1).into_attr())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_representations_round_trip() {
+        let id = Uuid::new_v4();
+        assert_eq!(id.into_attr().s, Some(id.to_string()));
+        assert_eq!(Ok(id), Uuid::from_attr(id.into_attr()));
+        assert_eq!(
+            UuidBin(id).into_attr().b.as_deref(),
+            Some(&id.as_bytes()[..])
+        );
+        assert_eq!(Ok(UuidBin(id)), UuidBin::from_attr(UuidBin(id).into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_bin_invalid_length() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            UuidBin::from_attr(Bytes::from_static(&[0; 15]).into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "B",
+                found: "S"
+            }),
+            UuidBin::from_attr(Uuid::new_v4().into_attr())
+        );
+    }
+
+    #[test]
+    fn composite_binary_round_trip() {
+        let value = CompositeBinary([0xde, 0xad], [0xbe, 0xef, 0x00]);
+        assert_eq!(
+            value.into_attr().b.as_deref(),
+            Some(&[0xde, 0xad, 0xbe, 0xef, 0x00][..])
+        );
+        assert_eq!(Ok(value), CompositeBinary::from_attr(value.into_attr()));
+        let empty = CompositeBinary([], [1]);
+        assert_eq!(Ok(empty), CompositeBinary::from_attr(empty.into_attr()));
+    }
+
+    #[test]
+    fn composite_binary_invalid_length() {
+        for bytes in &[&[1, 2, 3][..], &[1, 2, 3, 4, 5, 6]] {
+            assert_eq!(
+                Err(AttributeError::InvalidFormat),
+                CompositeBinary::<2, 3>::from_attr(Bytes::copy_from_slice(bytes).into_attr())
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_reads_strings_and_numbers() {
+        assert_eq!(
+            Ok("1.5".to_string()),
+            lenient::from_attr(AttributeValue {
+                n: Some("1.5".into()),
+                ..AttributeValue::default()
+            })
+        );
+        assert_eq!(Ok(Some(7)), lenient::from_attr("7".to_string().into_attr()));
+        assert_eq!(
+            Ok("abc".to_string()),
+            lenient::from_attr("abc".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            lenient::from_attr::<u32>("abc".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "BOOL"
+            }),
+            lenient::from_attr::<String>(true.into_attr())
+        );
+    }
+
+    #[test]
+    fn string_id_round_trip() {
+        for id in &[0, 1 << 53, (1 << 53) + 1, u64::MAX] {
+            let value = StringId(*id).into_attr();
+            assert_eq!(value.s, Some(id.to_string()));
+            assert_eq!(Ok(StringId(*id)), StringId::from_attr(value));
+        }
+    }
+
+    #[test]
+    fn string_id_malformed() {
+        for s in &["", "-1", "1.5", "18446744073709551616"] {
+            assert_eq!(
+                Err(AttributeError::InvalidFormat),
+                StringId::from_attr(s.to_string().into_attr())
+            );
+        }
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "N"
+            }),
+            StringId::from_attr(1.into_attr())
+        );
+    }
+
+    #[test]
+    fn big_endian_bytes_sort_numerically() {
+        let values = [0, 1, 2, 255, 256, 65_535, 65_536, 1 << 40, u64::MAX];
+        let bytes = values
+            .iter()
+            .map(|value| Be64(*value).into_attr().b.unwrap())
+            .collect::<Vec<_>>();
+        let mut sorted = bytes.clone();
+        sorted.sort();
+        assert_eq!(bytes, sorted);
+    }
+}
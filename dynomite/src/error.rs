@@ -1,20 +1,139 @@
 //! Dynomite error types
-use std::{error::Error, fmt};
+use crate::dynamodb::{
+    AttributeValue, BatchGetItemError, BatchWriteItemError, DeleteItemError, GetItemError,
+    PutItemError, QueryError, ScanError, TransactGetItemsError, UpdateItemError,
+};
+use rusoto_core::RusotoError;
+use std::{error::Error as StdError, fmt};
 
 /// Errors that may result of attribute value conversions
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum AttributeError {
     /// Will be returned if an AttributeValue is present, and is of the expected
     /// type but its contents are not well-formatted
     InvalidFormat,
     /// Will be returned if provided AttributeValue is not of the expected type
+    #[deprecated(
+        note = "use `AttributeError::WrongType`, which names the expected and found types"
+    )]
     InvalidType,
+    /// Will be returned if provided AttributeValue is not of the expected type,
+    /// naming both types by their DynamoDB type descriptors, e.g. `S` or `N`
+    WrongType {
+        /// Type descriptor of the expected type
+        expected: &'static str,
+        /// Type descriptor of the first populated field of the provided value,
+        /// or `empty` when no field is populated
+        found: &'static str,
+    },
     /// Will be returned if provided attributes does not included an
     /// expected named value
     MissingField {
         /// Name of the field that is missing
         name: String,
     },
+    /// Will be returned if provided attributes does not include the
+    /// partition or sort key value of an item
+    MissingKey {
+        /// Name of the key that is missing
+        name: String,
+    },
+    /// Will be returned if provided attributes include a named value which is
+    /// not a field of a struct declared `#[dynomite(deny_unknown_fields)]`
+    UnknownField {
+        /// Name of the unexpected value
+        name: String,
+    },
+    /// Will be returned if the estimated size of an item exceeds
+    /// DynamoDB's 400KB item size limit
+    ItemTooLarge {
+        /// Estimated size of the item in bytes
+        size: usize,
+    },
+    /// Will be returned if provided maps and lists are nested more deeply than
+    /// the maximum depth, see the [`depth`](../depth/index.html) module
+    TooDeep {
+        /// The maximum depth of nesting
+        max: usize,
+    },
+    /// Will be returned if a named value of provided attributes could not be converted,
+    /// locating the value by the names of the fields leading to it
+    Path {
+        /// Names of the fields leading to the value, outermost first
+        path: Vec<String>,
+        /// Error converting the value
+        source: Box<AttributeError>,
+    },
+}
+
+impl AttributeError {
+    /// Returns a `WrongType` error for a `value` which was expected to be of the `expected` type
+    pub fn wrong_type(
+        expected: &'static str,
+        value: &AttributeValue,
+    ) -> Self {
+        AttributeError::WrongType {
+            expected,
+            found: type_of(value),
+        }
+    }
+
+    /// Prepends the name of the field this error occurred in to its path
+    pub fn in_field(
+        self,
+        name: &str,
+    ) -> Self {
+        match self {
+            AttributeError::Path { mut path, source } => {
+                path.insert(0, name.to_string());
+                AttributeError::Path { path, source }
+            }
+            source => AttributeError::Path {
+                path: vec![name.to_string()],
+                source: Box::new(source),
+            },
+        }
+    }
+}
+
+/// Returns the type descriptor of the first populated field of `value`
+fn type_of(value: &AttributeValue) -> &'static str {
+    let AttributeValue {
+        b,
+        bool,
+        bs,
+        l,
+        m,
+        n,
+        ns,
+        null,
+        s,
+        ss,
+    } = value;
+    if s.is_some() {
+        "S"
+    } else if n.is_some() {
+        "N"
+    } else if b.is_some() {
+        "B"
+    } else if bool.is_some() {
+        "BOOL"
+    } else if null.unwrap_or_default() {
+        "NULL"
+    } else if ss.is_some() {
+        "SS"
+    } else if ns.is_some() {
+        "NS"
+    } else if bs.is_some() {
+        "BS"
+    } else if l.is_some() {
+        "L"
+    } else if m.is_some() {
+        "M"
+    } else {
+        "empty"
+    }
 }
 
 impl fmt::Display for AttributeError {
@@ -22,24 +141,174 @@ impl fmt::Display for AttributeError {
         &self,
         f: &mut fmt::Formatter<'_>,
     ) -> fmt::Result {
+        #[allow(deprecated)]
         match self {
             AttributeError::InvalidFormat => write!(f, "Invalid format"),
             AttributeError::InvalidType => write!(f, "Invalid type"),
+            AttributeError::WrongType { expected, found } => {
+                write!(f, "Wrong type, expected {} but found {}", expected, found)
+            }
             AttributeError::MissingField { name } => write!(f, "Missing field {}", name),
+            AttributeError::MissingKey { name } => write!(f, "Missing key {}", name),
+            AttributeError::UnknownField { name } => write!(f, "Unknown field {}", name),
+            AttributeError::ItemTooLarge { size } => {
+                write!(f, "Item size of {} bytes exceeds the 400KB limit", size)
+            }
+            AttributeError::TooDeep { max } => {
+                write!(f, "Nesting exceeds the maximum depth of {}", max)
+            }
+            AttributeError::Path { path, source } => write!(f, "{}: {}", path.join("."), source),
         }
     }
 }
 
-impl Error for AttributeError {}
+impl StdError for AttributeError {}
+
+/// Errors that may result of DynamoDB operations
+///
+/// Conditional write failures, which each operation's error type reports through its own
+/// `ConditionalCheckFailed` variant, are surfaced uniformly as `Error::ConditionFailed`
+///
+/// The errors of the item, batch, query and scan operations, as well as `AttributeError`s, convert
+/// into an `Error`, so that code combining operations with conversions can propagate a single error
+/// type with `?`. The underlying error is available through `source()`
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use dynomite::{
+///     dynamodb::{DynamoDb, DynamoDbClient, PutItemInput},
+///     Error,
+/// };
+///
+/// # async fn put(client: DynamoDbClient, input: PutItemInput) {
+/// match client.put_item(input).await.map_err(Error::from) {
+///     Err(Error::ConditionFailed(_)) => println!("item was not written"),
+///     other => println!("{:?}", other),
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// Will be returned if the condition expression of a conditional write was not met
+    ConditionFailed(String),
+    /// Will be returned for any other failure of an operation
+    Rusoto(Box<dyn StdError + Send + Sync>),
+    /// Will be returned if attributes returned by an operation could not be converted into the requested type
+    Attribute(AttributeError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Error::ConditionFailed(message) => write!(f, "Condition failed: {}", message),
+            Error::Rusoto(err) => write!(f, "{}", err),
+            Error::Attribute(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::ConditionFailed(_) => None,
+            Error::Rusoto(err) => Some(err.as_ref()),
+            Error::Attribute(err) => Some(err),
+        }
+    }
+}
+
+impl From<AttributeError> for Error {
+    fn from(err: AttributeError) -> Self {
+        Error::Attribute(err)
+    }
+}
+
+impl<E> From<RusotoError<E>> for Error
+where
+    E: ConditionalCheck + StdError + Send + Sync + 'static,
+{
+    fn from(err: RusotoError<E>) -> Self {
+        match err {
+            RusotoError::Service(ref e) => match e.condition_failed() {
+                Some(message) => Error::ConditionFailed(message.to_string()),
+                None => Error::Rusoto(Box::new(err)),
+            },
+            err => Error::Rusoto(Box::new(err)),
+        }
+    }
+}
+
+/// Predicate trait for operation error types which may report a conditional check failure
+pub trait ConditionalCheck {
+    /// Returns the failure message if this error is a conditional check failure
+    fn condition_failed(&self) -> Option<&str>;
+}
+
+/// Returns true if `error` is a conditional check failure of a write operation
+pub fn is_conditional_check_failed<E: ConditionalCheck>(error: &RusotoError<E>) -> bool {
+    match error {
+        RusotoError::Service(e) => e.condition_failed().is_some(),
+        _ => false,
+    }
+}
+
+macro_rules! conditional_check {
+    ($($e:ident),+) => {
+        $(
+            impl ConditionalCheck for $e {
+                fn condition_failed(&self) -> Option<&str> {
+                    match self {
+                        $e::ConditionalCheckFailed(message) => Some(message),
+                        _ => None,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+conditional_check!(DeleteItemError, PutItemError, UpdateItemError);
+
+/// Implements `ConditionalCheck` for the error types of operations which can't fail a condition,
+/// so that their errors convert into an `Error` all the same
+macro_rules! unconditional {
+    ($($e:ident),+) => {
+        $(
+            impl ConditionalCheck for $e {
+                fn condition_failed(&self) -> Option<&str> {
+                    None
+                }
+            }
+        )+
+    };
+}
+
+unconditional!(
+    BatchGetItemError,
+    BatchWriteItemError,
+    GetItemError,
+    QueryError,
+    ScanError,
+    TransactGetItemsError
+);
 
 #[cfg(test)]
 mod tests {
-    use super::AttributeError;
-    use std::error::Error;
+    use super::*;
 
     #[test]
     fn attribute_error_impl_std_error() {
-        fn test(_: impl Error) {}
+        fn test(_: impl StdError) {}
+        test(AttributeError::InvalidFormat)
+    }
+
+    #[test]
+    fn attribute_error_impl_clone() {
+        fn test(_: impl Clone + PartialEq) {}
         test(AttributeError::InvalidFormat)
     }
 
@@ -52,10 +321,34 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn invalid_type_displays() {
         assert_eq!("Invalid type", format!("{}", AttributeError::InvalidType))
     }
 
+    #[test]
+    fn wrong_type_displays() {
+        let value = AttributeValue {
+            n: Some("1".into()),
+            ..AttributeValue::default()
+        };
+        assert_eq!(
+            "Wrong type, expected S but found N",
+            format!("{}", AttributeError::wrong_type("S", &value))
+        )
+    }
+
+    #[test]
+    fn wrong_type_of_empty_value() {
+        assert_eq!(
+            AttributeError::WrongType {
+                expected: "S",
+                found: "empty"
+            },
+            AttributeError::wrong_type("S", &AttributeValue::default())
+        )
+    }
+
     #[test]
     fn missing_field_displays() {
         assert_eq!(
@@ -63,4 +356,90 @@ mod tests {
             format!("{}", AttributeError::MissingField { name: "foo".into() })
         )
     }
+
+    #[test]
+    fn missing_key_displays() {
+        assert_eq!(
+            "Missing key foo",
+            format!("{}", AttributeError::MissingKey { name: "foo".into() })
+        )
+    }
+
+    #[test]
+    fn path_displays() {
+        let err = AttributeError::MissingField { name: "b".into() }
+            .in_field("nested")
+            .in_field("outer");
+        assert_eq!(
+            AttributeError::Path {
+                path: vec!["outer".into(), "nested".into()],
+                source: Box::new(AttributeError::MissingField { name: "b".into() })
+            },
+            err
+        );
+        assert_eq!("outer.nested: Missing field b", format!("{}", err))
+    }
+
+    #[test]
+    fn put_item_condition_failed() {
+        let err = RusotoError::Service(PutItemError::ConditionalCheckFailed(
+            "The conditional request failed".into(),
+        ));
+        assert!(is_conditional_check_failed(&err));
+        match Error::from(err) {
+            Error::ConditionFailed(message) => {
+                assert_eq!(message, "The conditional request failed")
+            }
+            other => panic!("expected condition failure but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn delete_item_condition_failed() {
+        let err = RusotoError::Service(DeleteItemError::ConditionalCheckFailed(
+            "The conditional request failed".into(),
+        ));
+        assert!(is_conditional_check_failed(&err));
+        assert!(matches!(Error::from(err), Error::ConditionFailed(_)));
+    }
+
+    #[test]
+    fn attribute_errors_are_sources() {
+        let err = Error::from(AttributeError::MissingField { name: "foo".into() });
+        assert_eq!(err.to_string(), "Missing field foo");
+        assert_eq!(
+            err.source()
+                .and_then(|source| source.downcast_ref::<AttributeError>()),
+            Some(&AttributeError::MissingField { name: "foo".into() })
+        );
+    }
+
+    #[test]
+    fn read_errors_are_sources() {
+        let err = Error::from(RusotoError::Service(QueryError::ResourceNotFound(
+            "missing".into(),
+        )));
+        assert!(matches!(err, Error::Rusoto(_)));
+        assert_eq!(
+            err.source()
+                .and_then(|source| source.downcast_ref::<RusotoError<QueryError>>()),
+            Some(&RusotoError::Service(QueryError::ResourceNotFound(
+                "missing".into()
+            )))
+        );
+        assert!(matches!(
+            Error::from(RusotoError::<GetItemError>::Validation("invalid".into())),
+            Error::Rusoto(_)
+        ));
+    }
+
+    #[test]
+    fn other_errors_are_not_condition_failures() {
+        let service = RusotoError::Service(DeleteItemError::ResourceNotFound("missing".into()));
+        assert!(!is_conditional_check_failed(&service));
+        assert!(matches!(Error::from(service), Error::Rusoto(_)));
+        let validation = RusotoError::<PutItemError>::Validation("invalid".into());
+        assert!(!is_conditional_check_failed(&validation));
+        assert_eq!(Error::from(validation).to_string(), "invalid");
+    }
 }
@@ -0,0 +1,420 @@
+//! Builders for DynamoDB expressions
+//!
+//! DynamoDB [condition expressions](https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Expressions.OperatorsAndFunctions.html)
+//! are used both as query key conditions and as conditions on writes. Rather than
+//! writing these as raw strings and keeping placeholder maps in sync by hand, a [`Condition`](struct.Condition.html)
+//! can be composed fluently and then rendered into an [`Expression`](struct.Expression.html)
+//! containing the expression text along with its attribute name and value maps.
+//!
+//! Attribute path segments which begin with `#` are treated as
+//! [expression attribute name](https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Expressions.ExpressionAttributeNames.html)
+//! placeholders, which is useful for attributes whose names are reserved words. Values are
+//! given explicit `:placeholder` names and may be any type that implements [`Attribute`](../trait.Attribute.html).
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{
+//!     dynamodb::QueryInput,
+//!     expression::{attr, value},
+//! };
+//!
+//! let condition = attr("partitionKey")
+//!     .eq(value(":pk", "rust".to_string()))
+//!     .and(attr("sortKey").begins_with(value(":prefix", "2021-".to_string())))
+//!     .build();
+//!
+//! let query = QueryInput {
+//!     table_name: "some_table".into(),
+//!     key_condition_expression: Some(condition.expression),
+//!     expression_attribute_names: condition.names,
+//!     expression_attribute_values: condition.values,
+//!     ..QueryInput::default()
+//! };
+//! ```
+use crate::{Attribute, AttributeValue, Attributes};
+use std::collections::HashMap;
+
+/// A rendered expression, ready to be assigned to an input's expression fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    /// The expression text
+    pub expression: String,
+    /// Attribute name placeholders referenced in the expression, `None` when there are none
+    pub names: Option<HashMap<String, String>>,
+    /// Attribute value placeholders referenced in the expression, `None` when there are none
+    pub values: Option<Attributes>,
+}
+
+/// A placeholder and the value it stands for
+#[derive(Debug, Clone, PartialEq)]
+pub struct Value {
+    placeholder: String,
+    value: AttributeValue,
+}
+
+/// Returns a value to be referenced in an expression by the given `:placeholder`
+///
+/// A placeholder reused for a different value within the same condition is renamed with
+/// a numeric suffix, e.g. `:v_1`, so that neither value is lost
+pub fn value(
+    placeholder: impl Into<String>,
+    value: impl Attribute,
+) -> Value {
+    Value {
+        placeholder: placeholder.into(),
+        value: value.into_attr(),
+    }
+}
+
+/// A reference to an item attribute from which conditions are built
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operand {
+    path: String,
+}
+
+/// Returns a reference to an item attribute by its path
+///
+/// Path segments which begin with `#` are rendered as expression attribute name placeholders.
+pub fn attr(path: impl Into<String>) -> Operand {
+    Operand { path: path.into() }
+}
+
+impl Operand {
+    fn compare(
+        self,
+        op: &'static str,
+        value: Value,
+    ) -> Condition {
+        Condition(Kind::Compare(self, op, value))
+    }
+
+    /// `path = :value`
+    pub fn eq(
+        self,
+        value: Value,
+    ) -> Condition {
+        self.compare("=", value)
+    }
+
+    /// `path < :value`
+    pub fn lt(
+        self,
+        value: Value,
+    ) -> Condition {
+        self.compare("<", value)
+    }
+
+    /// `path <= :value`
+    pub fn le(
+        self,
+        value: Value,
+    ) -> Condition {
+        self.compare("<=", value)
+    }
+
+    /// `path > :value`
+    pub fn gt(
+        self,
+        value: Value,
+    ) -> Condition {
+        self.compare(">", value)
+    }
+
+    /// `path >= :value`
+    pub fn ge(
+        self,
+        value: Value,
+    ) -> Condition {
+        self.compare(">=", value)
+    }
+
+    /// `path BETWEEN :low AND :high`
+    pub fn between(
+        self,
+        low: Value,
+        high: Value,
+    ) -> Condition {
+        Condition(Kind::Between(self, low, high))
+    }
+
+    /// `begins_with(path, :prefix)`
+    pub fn begins_with(
+        self,
+        prefix: Value,
+    ) -> Condition {
+        Condition(Kind::BeginsWith(self, prefix))
+    }
+
+    /// `attribute_exists(path)`
+    pub fn exists(self) -> Condition {
+        Condition(Kind::AttributeExists(self))
+    }
+
+    /// `attribute_not_exists(path)`
+    pub fn not_exists(self) -> Condition {
+        Condition(Kind::AttributeNotExists(self))
+    }
+}
+
+/// A composable condition expression
+///
+/// Conditions are created from an [`Operand`](struct.Operand.html), see [`attr`](fn.attr.html),
+/// and combined with [`and`](#method.and) and [`or`](#method.or).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition(Kind);
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Compare(Operand, &'static str, Value),
+    Between(Operand, Value, Value),
+    BeginsWith(Operand, Value),
+    AttributeExists(Operand),
+    AttributeNotExists(Operand),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+/// Shorthand for [`attr(path).exists()`](struct.Operand.html#method.exists)
+pub fn attribute_exists(path: impl Into<String>) -> Condition {
+    attr(path).exists()
+}
+
+/// Shorthand for [`attr(path).not_exists()`](struct.Operand.html#method.not_exists)
+pub fn attribute_not_exists(path: impl Into<String>) -> Condition {
+    attr(path).not_exists()
+}
+
+impl Condition {
+    /// Returns a condition which holds when both `self` and `other` hold
+    pub fn and(
+        self,
+        other: Condition,
+    ) -> Condition {
+        match self {
+            Condition(Kind::And(mut conditions)) => {
+                conditions.push(other);
+                Condition(Kind::And(conditions))
+            }
+            condition => Condition(Kind::And(vec![condition, other])),
+        }
+    }
+
+    /// Returns a condition which holds when either `self` or `other` hold
+    pub fn or(
+        self,
+        other: Condition,
+    ) -> Condition {
+        match self {
+            Condition(Kind::Or(mut conditions)) => {
+                conditions.push(other);
+                Condition(Kind::Or(conditions))
+            }
+            condition => Condition(Kind::Or(vec![condition, other])),
+        }
+    }
+
+    /// Renders this condition into an expression and its placeholder maps
+    pub fn build(self) -> Expression {
+        let mut builder = Builder::default();
+        let expression = builder.render(self);
+        builder.finish(expression)
+    }
+}
+
+impl From<Condition> for Expression {
+    fn from(condition: Condition) -> Self {
+        condition.build()
+    }
+}
+
+/// Accumulates placeholders while rendering expressions
+#[derive(Default)]
+struct Builder {
+    names: HashMap<String, String>,
+    values: Attributes,
+}
+
+impl Builder {
+    fn path(
+        &mut self,
+        operand: Operand,
+    ) -> String {
+        for segment in operand.path.split('.') {
+            let name = segment.split('[').next().unwrap_or(segment);
+            if name.starts_with('#') {
+                self.names
+                    .insert(name.to_string(), name.trim_start_matches('#').to_string());
+            }
+        }
+        operand.path
+    }
+
+    fn value(
+        &mut self,
+        value: Value,
+    ) -> String {
+        let Value { placeholder, value } = value;
+        let placeholder = match self.values.get(&placeholder) {
+            Some(existing) if *existing != value => (1..)
+                .map(|n| format!("{}_{}", placeholder, n))
+                .find(|unique| !self.values.contains_key(unique))
+                .expect("unbounded suffixes"),
+            _ => placeholder,
+        };
+        self.values.insert(placeholder.clone(), value);
+        placeholder
+    }
+
+    fn render(
+        &mut self,
+        condition: Condition,
+    ) -> String {
+        match condition.0 {
+            Kind::Compare(operand, op, value) => {
+                format!("{} {} {}", self.path(operand), op, self.value(value))
+            }
+            Kind::Between(operand, low, high) => format!(
+                "{} BETWEEN {} AND {}",
+                self.path(operand),
+                self.value(low),
+                self.value(high)
+            ),
+            Kind::BeginsWith(operand, prefix) => format!(
+                "begins_with({}, {})",
+                self.path(operand),
+                self.value(prefix)
+            ),
+            Kind::AttributeExists(operand) => {
+                format!("attribute_exists({})", self.path(operand))
+            }
+            Kind::AttributeNotExists(operand) => {
+                format!("attribute_not_exists({})", self.path(operand))
+            }
+            Kind::And(conditions) => self.join(conditions, " AND "),
+            Kind::Or(conditions) => self.join(conditions, " OR "),
+        }
+    }
+
+    fn join(
+        &mut self,
+        conditions: Vec<Condition>,
+        separator: &str,
+    ) -> String {
+        conditions
+            .into_iter()
+            .map(|condition| match condition {
+                compound @ Condition(Kind::And(_)) | compound @ Condition(Kind::Or(_)) => {
+                    format!("({})", self.render(compound))
+                }
+                condition => self.render(condition),
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn finish(
+        self,
+        expression: String,
+    ) -> Expression {
+        let Builder { names, values } = self;
+        Expression {
+            expression,
+            names: Some(names).filter(|names| !names.is_empty()),
+            values: Some(values).filter(|values| !values.is_empty()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::hashmap;
+
+    #[test]
+    fn key_condition() {
+        let expression = attr("partitionKey")
+            .eq(value(":pk", "rust".to_string()))
+            .and(attr("sortKey").begins_with(value(":prefix", "2021-".to_string())))
+            .build();
+        assert_eq!(
+            expression.expression,
+            "partitionKey = :pk AND begins_with(sortKey, :prefix)"
+        );
+        assert_eq!(expression.names, None);
+        assert_eq!(
+            expression.values,
+            Some(hashmap! {
+                ":pk".to_string() => "rust".to_string().into_attr(),
+                ":prefix".to_string() => "2021-".to_string().into_attr()
+            })
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        let expression = attr("a")
+            .lt(value(":a", 1))
+            .and(attr("b").le(value(":b", 2)))
+            .and(attr("c").gt(value(":c", 3)))
+            .and(attr("d").ge(value(":d", 4)))
+            .and(attr("e").between(value(":lo", 5), value(":hi", 6)))
+            .build();
+        assert_eq!(
+            expression.expression,
+            "a < :a AND b <= :b AND c > :c AND d >= :d AND e BETWEEN :lo AND :hi"
+        );
+        assert_eq!(expression.values.map(|values| values.len()), Some(6));
+    }
+
+    #[test]
+    fn nested_composition_is_grouped() {
+        let expression = attribute_not_exists("id")
+            .or(attr("#status").eq(value(":status", "pending".to_string())))
+            .and(attribute_exists("owner"))
+            .build();
+        assert_eq!(
+            expression.expression,
+            "(attribute_not_exists(id) OR #status = :status) AND attribute_exists(owner)"
+        );
+        assert_eq!(
+            expression.names,
+            Some(hashmap! { "#status".to_string() => "status".to_string() })
+        );
+    }
+
+    #[test]
+    fn name_placeholders_in_nested_paths() {
+        let expression = attr("#a.b[0].#c").exists().build();
+        assert_eq!(expression.expression, "attribute_exists(#a.b[0].#c)");
+        assert_eq!(
+            expression.names,
+            Some(hashmap! {
+                "#a".to_string() => "a".to_string(),
+                "#c".to_string() => "c".to_string()
+            })
+        );
+        assert_eq!(expression.values, None);
+    }
+
+    #[test]
+    fn reused_value_placeholders_are_renamed() {
+        let expression = attr("low")
+            .eq(value(":v", 1))
+            .and(attr("high").eq(value(":v", 2)))
+            .and(attr("other").eq(value(":v", 1)))
+            .build();
+        assert_eq!(
+            expression.expression,
+            "low = :v AND high = :v_1 AND other = :v"
+        );
+        assert_eq!(
+            expression.values,
+            Some(hashmap! {
+                ":v".to_string() => 1.into_attr(),
+                ":v_1".to_string() => 2.into_attr()
+            })
+        );
+    }
+}
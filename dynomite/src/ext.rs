@@ -1,19 +1,104 @@
 //! Extention interfaces for rusoto `DynamoDb`
 
-use crate::dynamodb::{
-    AttributeValue, BackupSummary, DynamoDb, ListBackupsError, ListBackupsInput, ListTablesError,
-    ListTablesInput, QueryError, QueryInput, ScanError, ScanInput,
+use crate::{
+    dynamodb::{
+        AttributeDefinition, AttributeValue, BackupSummary, BatchGetItemError, BatchGetItemInput,
+        BatchWriteItemInput, DescribeTableError, DescribeTableInput, DynamoDb, KeySchemaElement,
+        KeysAndAttributes, ListBackupsError, ListBackupsInput, ListTablesError, ListTablesInput,
+        PutItemInput, PutRequest, QueryError, QueryInput, QueryOutput, ScanError, ScanInput,
+        ScanOutput, TransactWriteItem, TransactWriteItemsError, TransactWriteItemsInput,
+        TransactWriteItemsOutput, UpdateItemInput, WriteRequest,
+    },
+    retry::{drain_unprocessed_keys, drain_unprocessed_writes, Policy},
+    Error, FromAttributes, IntoAttributes, Item,
 };
-use futures::{stream, Stream, TryStreamExt};
+use futures::{stream, Future, Stream, TryStreamExt};
 use rusoto_core::RusotoError;
-use std::{collections::HashMap, pin::Pin};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+};
 
 type DynomiteStream<I, E> = Pin<Box<dyn Stream<Item = Result<I, RusotoError<E>>> + Send>>;
+type DynomiteFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, RusotoError<E>>> + Send>>;
+type ItemFuture<T> = Pin<Box<dyn Future<Output = Result<Option<T>, Error>> + Send>>;
+type ItemsFuture<T> = Pin<Box<dyn Future<Output = Result<Vec<T>, Error>> + Send>>;
+/// A page's items along with the key to resume from, if there are further pages
+type Page = (
+    Vec<HashMap<String, AttributeValue>>,
+    Option<HashMap<String, AttributeValue>>,
+);
+
+/// The maximum number of keys DynamoDB accepts in a single `batch_get_item` request
+const BATCH_GET_ITEM_LIMIT: usize = 100;
+
+/// The maximum number of requests DynamoDB accepts in a single `batch_write_item` request
+const BATCH_WRITE_ITEM_LIMIT: usize = 25;
+
+/// The maximum number of actions DynamoDB accepts in a single `transact_write_items` request
+const TRANSACT_WRITE_ITEMS_LIMIT: usize = crate::transact::MAX_ACTIONS;
+
+/// Which item attributes a write operation returns, as of before or after the write
+///
+/// See the `ReturnValues` parameter of [PutItem](https://docs.aws.amazon.com/amazondynamodb/latest/APIReference/API_PutItem.html#DDB-PutItem-request-ReturnValues)
+/// and [UpdateItem](https://docs.aws.amazon.com/amazondynamodb/latest/APIReference/API_UpdateItem.html#DDB-UpdateItem-request-ReturnValues).
+/// `put_item` only supports `None` and `AllOld`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReturnValues {
+    /// Return no attributes
+    None,
+    /// Return all of the item's attributes before the write
+    AllOld,
+    /// Return the updated attributes before the write
+    UpdatedOld,
+    /// Return all of the item's attributes after the write
+    AllNew,
+    /// Return the updated attributes after the write
+    UpdatedNew,
+}
+
+impl ReturnValues {
+    /// Returns the name DynamoDB uses for this setting, e.g. `ALL_OLD`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReturnValues::None => "NONE",
+            ReturnValues::AllOld => "ALL_OLD",
+            ReturnValues::UpdatedOld => "UPDATED_OLD",
+            ReturnValues::AllNew => "ALL_NEW",
+            ReturnValues::UpdatedNew => "UPDATED_NEW",
+        }
+    }
+}
 
 /// Extension methods for DynamoDb client types
 ///
 /// A default impl is provided for `DynamoDb  Clone + Send + Sync + 'static` which adds autopaginating `Stream` interfaces that require
 /// taking ownership.
+///
+/// Pages are only requested as their items are polled, so limiting a stream with
+/// [`StreamExt::take`](https://docs.rs/futures/0.3/futures/stream/trait.StreamExt.html#method.take)
+/// stops it from requesting further pages once that many items have been yielded,
+/// e.g. for the first items of a "load more" listing
+///
+/// ```rust,no_run
+/// use dynomite::{
+///     dynamodb::{DynamoDbClient, ScanInput},
+///     DynamoDbExt,
+/// };
+/// use futures::{StreamExt, TryStreamExt};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let first_ten = DynamoDbClient::new(Default::default())
+///     .scan_pages(ScanInput {
+///         table_name: "books".into(),
+///         ..ScanInput::default()
+///     })
+///     .take(10)
+///     .try_collect::<Vec<_>>()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
 pub trait DynamoDbExt {
     // see https://github.com/boto/botocore/blob/6906e8e7e8701c80f0b270c42be509cff4375e38/botocore/data/dynamodb/2012-08-10/paginators-1.json
 
@@ -30,16 +115,148 @@ pub trait DynamoDbExt {
     ) -> DynomiteStream<String, ListTablesError>;
 
     /// An auto-paginating `Stream` oriented version of `query`
+    ///
+    /// Items are yielded as their pages are received, so `.take(n)` requests only as many pages as needed for `n` items
     fn query_pages(
         self,
         input: QueryInput,
     ) -> DynomiteStream<HashMap<String, AttributeValue>, QueryError>;
 
+    /// A single page of `query` results, yielding its items along with the key to resume the
+    /// query from, or `None` when there are no further pages
+    ///
+    /// This is useful for stateless, cursor-based pagination where the returned key is handed to a client
+    /// and later passed back as the `exclusive_start_key` of the next page's input
+    fn query_page(
+        self,
+        input: QueryInput,
+    ) -> DynomiteFuture<Page, QueryError>;
+
+    /// An auto-paginating `Stream` oriented version of `query` which yields each page's `QueryOutput`
+    ///
+    /// This is useful for reporting progress through `count`, `scanned_count` and `last_evaluated_key`
+    fn query_page_stream(
+        self,
+        input: QueryInput,
+    ) -> DynomiteStream<QueryOutput, QueryError>;
+
+    /// The number of items matching a `query`, summing the `count` of each page
+    ///
+    /// Only counts are requested, by setting `select` to `COUNT`, so no items are read into memory.
+    /// Note that reading capacity is still consumed for each item evaluated
+    fn count_query(
+        self,
+        input: QueryInput,
+    ) -> DynomiteFuture<usize, QueryError>;
+
     /// An auto-paginating `Stream` oriented version of `scan`
+    ///
+    /// Items are yielded as their pages are received, so `.take(n)` requests only as many pages as needed for `n` items
     fn scan_pages(
         self,
         input: ScanInput,
     ) -> DynomiteStream<HashMap<String, AttributeValue>, ScanError>;
+
+    /// An auto-paginating `Stream` oriented version of `scan` which yields each page's `ScanOutput`
+    ///
+    /// This is useful for reporting progress through `count`, `scanned_count` and `last_evaluated_key`
+    fn scan_page_stream(
+        self,
+        input: ScanInput,
+    ) -> DynomiteStream<ScanOutput, ScanError>;
+
+    /// The number of items matching a `scan`, summing the `count` of each page
+    ///
+    /// Only counts are requested, by setting `select` to `COUNT`, so no items are read into memory.
+    /// Note that reading capacity is still consumed for each item evaluated
+    fn count_scan(
+        self,
+        input: ScanInput,
+    ) -> DynomiteFuture<usize, ScanError>;
+
+    /// A `batch_get_item` oriented interface for fetching any number of items from a table by key
+    ///
+    /// Keys are requested in batches of 100, resubmitting any `unprocessed_keys` of each batch with
+    /// [`drain_unprocessed_keys`](retry/fn.drain_unprocessed_keys.html) according to `policy`. Should
+    /// keys remain unprocessed once its retries are exhausted, this fails with a `ProvisionedThroughputExceeded`
+    /// error. Duplicate keys, which DynamoDB rejects, are requested once. Items are returned in the
+    /// positions of their keys, with `None` for keys which have no item
+    fn batch_get_all(
+        self,
+        table_name: String,
+        keys: Vec<HashMap<String, AttributeValue>>,
+        policy: Policy,
+    ) -> DynomiteFuture<Vec<Option<HashMap<String, AttributeValue>>>, BatchGetItemError>;
+
+    /// A typed `batch_write_item` oriented interface for putting any number of items in a table
+    ///
+    /// Items are put in batches of 25, resubmitting any `unprocessed_items` of each batch with
+    /// [`drain_unprocessed_writes`](retry/fn.drain_unprocessed_writes.html) according to `policy`.
+    /// Items which remain unprocessed once its retries are exhausted are converted back into `T`
+    /// and returned, so that they can be reported or retried later. An empty `Vec` means every
+    /// item was written.
+    fn put_all<T>(
+        self,
+        table_name: String,
+        items: Vec<T>,
+        policy: Policy,
+    ) -> ItemsFuture<T>
+    where
+        T: Item + Send + 'static;
+
+    /// A `transact_write_items` oriented interface for writing any number of actions
+    ///
+    /// Up to 100 actions are written in a single transaction. More than 100 actions fail with a
+    /// `RusotoError::Validation` error, without making any requests, unless `allow_split` is `true`,
+    /// in which case they are written in consecutive transactions of up to 100 actions each,
+    /// yielding the output of each transaction.
+    ///
+    /// **Split writes are not atomic.** Each transaction succeeds or fails on its own, so a failure
+    /// leaves the actions of any earlier transactions written and those of later transactions unattempted.
+    /// Only set `allow_split` when that partial outcome is acceptable.
+    fn transact_write_all(
+        self,
+        transact_items: Vec<TransactWriteItem>,
+        allow_split: bool,
+    ) -> DynomiteFuture<Vec<TransactWriteItemsOutput>, TransactWriteItemsError>;
+
+    /// Verifies that the key schema of a live table matches the declared keys of `T`, as returned by
+    /// [`Item::key_schema`](trait.Item.html#method.key_schema)
+    ///
+    /// This is useful for catching mismatches between code and deployed tables at startup. A table
+    /// whose partition or sort key differs in name, role or type from those of `T` fails with a
+    /// `RusotoError::Validation` error naming the offending attribute, as do items whose key schema
+    /// isn't known
+    fn verify_schema<T: Item>(
+        self,
+        table_name: String,
+    ) -> DynomiteFuture<(), DescribeTableError>;
+
+    /// A typed `put_item` which stores `item` in a table, returning the item it replaced, if any,
+    /// when `return_values` is `ReturnValues::AllOld`
+    ///
+    /// Returned attributes are converted back into `T`, saving a separate read of the previous item
+    fn put_item_returning<T>(
+        self,
+        table_name: String,
+        item: T,
+        return_values: ReturnValues,
+    ) -> ItemFuture<T>
+    where
+        T: IntoAttributes + FromAttributes + Send + 'static;
+
+    /// A typed `update_item` which applies `input`, returning the attributes selected by `return_values`
+    /// converted into `T`, or `None` for `ReturnValues::None`
+    ///
+    /// `UpdatedOld` and `UpdatedNew` return only the updated attributes, so `T` will typically be
+    /// a projection of only those attributes
+    fn update_item_returning<T>(
+        self,
+        input: UpdateItemInput,
+        return_values: ReturnValues,
+    ) -> ItemFuture<T>
+    where
+        T: FromAttributes + Send + 'static;
 }
 
 impl<D> DynamoDbExt for D
@@ -143,42 +360,72 @@ where
         self,
         input: QueryInput,
     ) -> DynomiteStream<HashMap<String, AttributeValue>, QueryError> {
+        Box::pin(
+            self.query_page_stream(input)
+                .map_ok(|page| stream::iter(page.items.unwrap_or_default().into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    fn query_page(
+        self,
+        input: QueryInput,
+    ) -> DynomiteFuture<Page, QueryError> {
+        Box::pin(async move {
+            let resp = self.query(input).await?;
+            Ok((
+                resp.items.unwrap_or_default(),
+                next_start_key(resp.last_evaluated_key),
+            ))
+        })
+    }
+
+    fn query_page_stream(
+        self,
+        input: QueryInput,
+    ) -> DynomiteStream<QueryOutput, QueryError> {
         #[allow(clippy::large_enum_variant)]
         enum PageState {
             Next(Option<HashMap<String, AttributeValue>>, QueryInput),
             End,
         }
+        Box::pin(stream::try_unfold(
+            PageState::Next(input.exclusive_start_key.clone(), input),
+            move |state| {
+                let clone = self.clone();
+                async move {
+                    let (exclusive_start_key, input) = match state {
+                        PageState::Next(start, input) => (start, input),
+                        PageState::End => return Ok(None) as Result<_, RusotoError<QueryError>>,
+                    };
+                    let resp = clone
+                        .query(QueryInput {
+                            exclusive_start_key,
+                            ..input.clone()
+                        })
+                        .await?;
+                    let next_state = match next_start_key(resp.last_evaluated_key.clone()) {
+                        Some(next) => PageState::Next(Some(next), input),
+                        _ => PageState::End,
+                    };
+                    Ok(Some((resp, next_state)))
+                }
+            },
+        ))
+    }
+
+    fn count_query(
+        self,
+        input: QueryInput,
+    ) -> DynomiteFuture<usize, QueryError> {
         Box::pin(
-            stream::try_unfold(
-                PageState::Next(input.exclusive_start_key.clone(), input),
-                move |state| {
-                    let clone = self.clone();
-                    async move {
-                        let (exclusive_start_key, input) = match state {
-                            PageState::Next(start, input) => (start, input),
-                            PageState::End => {
-                                return Ok(None) as Result<_, RusotoError<QueryError>>
-                            }
-                        };
-                        let resp = clone
-                            .query(QueryInput {
-                                exclusive_start_key,
-                                ..input.clone()
-                            })
-                            .await?;
-                        let next_state =
-                            match resp.last_evaluated_key.filter(|next| !next.is_empty()) {
-                                Some(next) => PageState::Next(Some(next), input),
-                                _ => PageState::End,
-                            };
-                        Ok(Some((
-                            stream::iter(resp.items.unwrap_or_default().into_iter().map(Ok)),
-                            next_state,
-                        )))
-                    }
-                },
-            )
-            .try_flatten(),
+            self.query_page_stream(QueryInput {
+                select: Some("COUNT".into()),
+                ..input
+            })
+            .try_fold(0, |count, page| async move {
+                Ok(count + page.count.unwrap_or_default() as usize)
+            }),
         )
     }
 
@@ -186,40 +433,974 @@ where
         self,
         input: ScanInput,
     ) -> DynomiteStream<HashMap<String, AttributeValue>, ScanError> {
+        Box::pin(
+            self.scan_page_stream(input)
+                .map_ok(|page| stream::iter(page.items.unwrap_or_default().into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    fn scan_page_stream(
+        self,
+        input: ScanInput,
+    ) -> DynomiteStream<ScanOutput, ScanError> {
         #[allow(clippy::large_enum_variant)]
         enum PageState {
             Next(Option<HashMap<String, AttributeValue>>, ScanInput),
             End,
         }
+        Box::pin(stream::try_unfold(
+            PageState::Next(input.exclusive_start_key.clone(), input),
+            move |state| {
+                let clone = self.clone();
+                async move {
+                    let (exclusive_start_key, input) = match state {
+                        PageState::Next(start, input) => (start, input),
+                        PageState::End => return Ok(None) as Result<_, RusotoError<ScanError>>,
+                    };
+                    let resp = clone
+                        .scan(ScanInput {
+                            exclusive_start_key,
+                            ..input.clone()
+                        })
+                        .await?;
+                    let next_state = match next_start_key(resp.last_evaluated_key.clone()) {
+                        Some(next) => PageState::Next(Some(next), input),
+                        _ => PageState::End,
+                    };
+                    Ok(Some((resp, next_state)))
+                }
+            },
+        ))
+    }
+
+    fn count_scan(
+        self,
+        input: ScanInput,
+    ) -> DynomiteFuture<usize, ScanError> {
         Box::pin(
-            stream::try_unfold(
-                PageState::Next(input.exclusive_start_key.clone(), input),
-                move |state| {
-                    let clone = self.clone();
-                    async move {
-                        let (exclusive_start_key, input) = match state {
-                            PageState::Next(start, input) => (start, input),
-                            PageState::End => return Ok(None) as Result<_, RusotoError<ScanError>>,
-                        };
-                        let resp = clone
-                            .scan(ScanInput {
-                                exclusive_start_key,
-                                ..input.clone()
-                            })
-                            .await?;
-                        let next_state =
-                            match resp.last_evaluated_key.filter(|next| !next.is_empty()) {
-                                Some(next) => PageState::Next(Some(next), input),
-                                _ => PageState::End,
-                            };
-                        Ok(Some((
-                            stream::iter(resp.items.unwrap_or_default().into_iter().map(Ok)),
-                            next_state,
-                        )))
+            self.scan_page_stream(ScanInput {
+                select: Some("COUNT".into()),
+                ..input
+            })
+            .try_fold(0, |count, page| async move {
+                Ok(count + page.count.unwrap_or_default() as usize)
+            }),
+        )
+    }
+
+    fn batch_get_all(
+        self,
+        table_name: String,
+        keys: Vec<HashMap<String, AttributeValue>>,
+        policy: Policy,
+    ) -> DynomiteFuture<Vec<Option<HashMap<String, AttributeValue>>>, BatchGetItemError> {
+        Box::pin(async move {
+            // the position of each key within the distinct keys, which are indexed by their serialized form
+            let mut distinct: Vec<HashMap<String, AttributeValue>> = Vec::new();
+            let mut indexes = HashMap::new();
+            let mut positions = Vec::with_capacity(keys.len());
+            for key in keys {
+                let position = *indexes.entry(key_id(&key)).or_insert_with(|| {
+                    distinct.push(key);
+                    distinct.len() - 1
+                });
+                positions.push(position);
+            }
+
+            let mut items = Vec::new();
+            for chunk in distinct.chunks(BATCH_GET_ITEM_LIMIT) {
+                let mut request_items = HashMap::new();
+                request_items.insert(
+                    table_name.clone(),
+                    KeysAndAttributes {
+                        keys: chunk.to_vec(),
+                        ..KeysAndAttributes::default()
+                    },
+                );
+                let output = self
+                    .batch_get_item(BatchGetItemInput {
+                        request_items,
+                        ..BatchGetItemInput::default()
+                    })
+                    .await?;
+                let output = drain_unprocessed_keys(&self, output, policy.clone()).await?;
+                let unprocessed = output
+                    .unprocessed_keys
+                    .and_then(|mut keys| keys.remove(&table_name))
+                    .map(|keys| keys.keys.len())
+                    .unwrap_or_default();
+                if unprocessed > 0 {
+                    return Err(RusotoError::Service(
+                        BatchGetItemError::ProvisionedThroughputExceeded(format!(
+                            "{} keys remained unprocessed once retries were exhausted",
+                            unprocessed
+                        )),
+                    ));
+                }
+                items.extend(
+                    output
+                        .responses
+                        .and_then(|mut responses| responses.remove(&table_name))
+                        .unwrap_or_default(),
+                );
+            }
+
+            // items are returned in no particular order so are matched to keys by their key attributes
+            let names = distinct
+                .first()
+                .map(|key| key.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let mut found = vec![None; distinct.len()];
+            for item in items {
+                let key = names
+                    .iter()
+                    .filter_map(|name| item.get_key_value(name))
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                if let Some(position) = indexes.get(&key_id(&key)) {
+                    found[*position] = Some(item);
+                }
+            }
+            Ok(positions
+                .into_iter()
+                .map(|position| found[position].clone())
+                .collect())
+        })
+    }
+
+    fn put_all<T>(
+        self,
+        table_name: String,
+        items: Vec<T>,
+        policy: Policy,
+    ) -> ItemsFuture<T>
+    where
+        T: Item + Send + 'static,
+    {
+        Box::pin(async move {
+            let requests = items
+                .into_iter()
+                .map(|item| {
+                    let mut attrs = HashMap::new();
+                    item.into_attrs(&mut attrs);
+                    WriteRequest {
+                        put_request: Some(PutRequest { item: attrs }),
+                        ..WriteRequest::default()
                     }
-                },
+                })
+                .collect::<Vec<_>>();
+            let mut unprocessed = Vec::new();
+            for chunk in requests.chunks(BATCH_WRITE_ITEM_LIMIT) {
+                let mut request_items = HashMap::new();
+                request_items.insert(table_name.clone(), chunk.to_vec());
+                let output = self
+                    .batch_write_item(BatchWriteItemInput {
+                        request_items,
+                        ..BatchWriteItemInput::default()
+                    })
+                    .await?;
+                let output = drain_unprocessed_writes(&self, output, policy.clone()).await?;
+                unprocessed.extend(
+                    output
+                        .unprocessed_items
+                        .and_then(|mut items| items.remove(&table_name))
+                        .unwrap_or_default(),
+                );
+            }
+            unprocessed
+                .into_iter()
+                .filter_map(|request| request.put_request)
+                .map(|mut put| T::from_attrs(&mut put.item).map_err(Error::from))
+                .collect()
+        })
+    }
+
+    fn transact_write_all(
+        self,
+        transact_items: Vec<TransactWriteItem>,
+        allow_split: bool,
+    ) -> DynomiteFuture<Vec<TransactWriteItemsOutput>, TransactWriteItemsError> {
+        Box::pin(async move {
+            if transact_items.len() > TRANSACT_WRITE_ITEMS_LIMIT && !allow_split {
+                return Err(RusotoError::Validation(format!(
+                    "{} actions exceed the limit of {} for a single atomic transaction",
+                    transact_items.len(),
+                    TRANSACT_WRITE_ITEMS_LIMIT
+                )));
+            }
+            let mut outputs = Vec::new();
+            for chunk in transact_items.chunks(TRANSACT_WRITE_ITEMS_LIMIT) {
+                outputs.push(
+                    self.transact_write_items(TransactWriteItemsInput {
+                        transact_items: chunk.to_vec(),
+                        ..TransactWriteItemsInput::default()
+                    })
+                    .await?,
+                );
+            }
+            Ok(outputs)
+        })
+    }
+
+    fn verify_schema<T: Item>(
+        self,
+        table_name: String,
+    ) -> DynomiteFuture<(), DescribeTableError> {
+        let expected = T::key_schema();
+        Box::pin(async move {
+            let (keys, definitions) = expected.ok_or_else(|| {
+                RusotoError::Validation(format!(
+                    "The key schema of {} isn't known",
+                    std::any::type_name::<T>()
+                ))
+            })?;
+            let table = self
+                .describe_table(DescribeTableInput {
+                    table_name: table_name.clone(),
+                })
+                .await?
+                .table
+                .unwrap_or_default();
+            match schema_mismatch(
+                &keys,
+                &definitions,
+                &table.key_schema.unwrap_or_default(),
+                &table.attribute_definitions.unwrap_or_default(),
+            ) {
+                Some(mismatch) => Err(RusotoError::Validation(format!(
+                    "Key schema of table `{}` doesn't match: {}",
+                    table_name, mismatch
+                ))),
+                None => Ok(()),
+            }
+        })
+    }
+
+    fn put_item_returning<T>(
+        self,
+        table_name: String,
+        item: T,
+        return_values: ReturnValues,
+    ) -> ItemFuture<T>
+    where
+        T: IntoAttributes + FromAttributes + Send + 'static,
+    {
+        Box::pin(async move {
+            let mut attrs = HashMap::new();
+            item.into_attrs(&mut attrs);
+            let output = self
+                .put_item(PutItemInput {
+                    table_name,
+                    item: attrs,
+                    return_values: Some(return_values.as_str().into()),
+                    ..PutItemInput::default()
+                })
+                .await?;
+            Ok(output
+                .attributes
+                .filter(|attrs| !attrs.is_empty())
+                .map(|mut attrs| T::from_attrs(&mut attrs))
+                .transpose()?)
+        })
+    }
+
+    fn update_item_returning<T>(
+        self,
+        input: UpdateItemInput,
+        return_values: ReturnValues,
+    ) -> ItemFuture<T>
+    where
+        T: FromAttributes + Send + 'static,
+    {
+        Box::pin(async move {
+            let output = self
+                .update_item(UpdateItemInput {
+                    return_values: Some(return_values.as_str().into()),
+                    ..input
+                })
+                .await?;
+            Ok(output
+                .attributes
+                .filter(|attrs| !attrs.is_empty())
+                .map(|mut attrs| T::from_attrs(&mut attrs))
+                .transpose()?)
+        })
+    }
+}
+
+/// Describes the first difference between the expected and actual key schemas of a table, if any
+fn schema_mismatch(
+    expected_keys: &[KeySchemaElement],
+    expected_definitions: &[AttributeDefinition],
+    keys: &[KeySchemaElement],
+    definitions: &[AttributeDefinition],
+) -> Option<String> {
+    let attribute_type = |definitions: &[AttributeDefinition], name: &str| {
+        definitions
+            .iter()
+            .find(|definition| definition.attribute_name == name)
+            .map(|definition| definition.attribute_type.clone())
+    };
+    for expected in expected_keys {
+        let name = &expected.attribute_name;
+        match keys.iter().find(|key| key.attribute_name == *name) {
+            None => {
+                return Some(format!(
+                    "expected a {} key named `{}` but the table has none",
+                    expected.key_type, name
+                ))
+            }
+            Some(key) if key.key_type != expected.key_type => {
+                return Some(format!(
+                    "expected `{}` to be a {} key but it is a {} key",
+                    name, expected.key_type, key.key_type
+                ))
+            }
+            Some(_) => {}
+        }
+        let expected_type = attribute_type(expected_definitions, name);
+        let actual_type = attribute_type(definitions, name);
+        if expected_type != actual_type {
+            return Some(format!(
+                "expected `{}` to be of type {} but it is of type {}",
+                name,
+                expected_type.as_deref().unwrap_or("unknown"),
+                actual_type.as_deref().unwrap_or("unknown")
+            ));
+        }
+    }
+    keys.iter()
+        .find(|key| {
+            !expected_keys
+                .iter()
+                .any(|expected| expected.attribute_name == key.attribute_name)
+        })
+        .map(|key| {
+            format!(
+                "the table has an unexpected {} key named `{}`",
+                key.key_type, key.attribute_name
             )
-            .try_flatten(),
+        })
+}
+
+/// The key to start the next page from, given the last evaluated key of a page.
+/// DynamoDB omits this on the last page, which some implementations represent as an empty map
+fn next_start_key(
+    last_evaluated_key: Option<HashMap<String, AttributeValue>>
+) -> Option<HashMap<String, AttributeValue>> {
+    last_evaluated_key.filter(|next| !next.is_empty())
+}
+
+/// Serializes a key such that equal keys serialize equally, whatever the order of their attributes
+fn key_id(key: &HashMap<String, AttributeValue>) -> String {
+    format!("{:?}", key.iter().collect::<BTreeMap<_, _>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dynamodb::{
+            BatchGetItemOutput, BatchWriteItemOutput, DescribeTableOutput, PutItemOutput,
+            TableDescription, UpdateItemOutput,
+        },
+        testing::MockDynamoDb,
+        Attribute,
+    };
+    use futures::executor::block_on;
+    use std::time::Duration;
+
+    /// An item, or key, with the given id
+    fn item(id: &str) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert("id".to_string(), id.to_string().into_attr());
+        item
+    }
+
+    #[test]
+    fn scan_page_stream_yields_each_page() {
+        let pages = block_on(
+            two_pages()
+                .scan_page_stream(ScanInput::default())
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("pages to be scanned");
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].last_evaluated_key.is_some());
+        assert_eq!(pages[1].last_evaluated_key, None);
+        assert_eq!(
+            pages
+                .iter()
+                .map(|page| page.scanned_count)
+                .collect::<Vec<_>>(),
+            vec![Some(2), Some(2)]
+        );
+    }
+
+    #[test]
+    fn query_page_stream_yields_each_page() {
+        let pages = block_on(
+            two_pages()
+                .query_page_stream(QueryInput::default())
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("pages to be queried");
+        assert_eq!(pages.len(), 2);
+    }
+
+    #[test]
+    fn query_pages_requests_each_page_from_the_last_key() {
+        let client = MockDynamoDb::default();
+        client
+            .query
+            .respond(Ok(QueryOutput {
+                items: Some(vec![item("1")]),
+                last_evaluated_key: Some(item("1")),
+                ..QueryOutput::default()
+            }))
+            .respond(Ok(QueryOutput {
+                items: Some(vec![item("2")]),
+                ..QueryOutput::default()
+            }));
+        let items = block_on(
+            client
+                .clone()
+                .query_pages(QueryInput::default())
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("items to be queried");
+        assert_eq!(items, vec![item("1"), item("2")]);
+        assert_eq!(
+            client
+                .query
+                .inputs()
+                .into_iter()
+                .map(|input| input.exclusive_start_key)
+                .collect::<Vec<_>>(),
+            vec![None, Some(item("1"))]
+        );
+    }
+
+    /// A client whose scans and queries return two pages of two items each
+    fn two_pages() -> MockDynamoDb {
+        let client = MockDynamoDb::default();
+        client
+            .scan
+            .respond(Ok(ScanOutput {
+                items: Some(vec![item("1"), item("2")]),
+                scanned_count: Some(2),
+                last_evaluated_key: Some(item("2")),
+                ..ScanOutput::default()
+            }))
+            .respond(Ok(ScanOutput {
+                items: Some(vec![item("3"), item("4")]),
+                scanned_count: Some(2),
+                ..ScanOutput::default()
+            }));
+        client
+            .query
+            .respond(Ok(QueryOutput {
+                items: Some(vec![item("1"), item("2")]),
+                last_evaluated_key: Some(item("2")),
+                ..QueryOutput::default()
+            }))
+            .respond(Ok(QueryOutput {
+                items: Some(vec![item("3"), item("4")]),
+                ..QueryOutput::default()
+            }));
+        client
+    }
+
+    #[test]
+    fn scan_pages_take_stops_requesting_pages() {
+        use futures::StreamExt;
+
+        for limit in 1..=2 {
+            let client = two_pages();
+            let items = block_on(
+                client
+                    .clone()
+                    .scan_pages(ScanInput::default())
+                    .take(limit)
+                    .try_collect::<Vec<_>>(),
+            )
+            .expect("items to be scanned");
+            assert_eq!(items, vec![item("1"), item("2")][..limit]);
+            assert_eq!(client.scan.inputs().len(), 1);
+        }
+    }
+
+    #[test]
+    fn query_pages_take_stops_requesting_pages() {
+        use futures::StreamExt;
+
+        let client = two_pages();
+        let items = block_on(
+            client
+                .clone()
+                .query_pages(QueryInput::default())
+                .take(3)
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("items to be queried");
+        assert_eq!(items, vec![item("1"), item("2"), item("3")]);
+        assert_eq!(client.query.inputs().len(), 2);
+
+        let client = two_pages();
+        let items = block_on(
+            client
+                .clone()
+                .query_pages(QueryInput::default())
+                .take(1)
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("items to be queried");
+        assert_eq!(items, vec![item("1")]);
+        assert_eq!(client.query.inputs().len(), 1);
+    }
+
+    #[test]
+    fn query_page_returns_cursor() {
+        let client = two_pages();
+        let (items, cursor) =
+            block_on(client.clone().query_page(QueryInput::default())).expect("page to be queried");
+        assert_eq!(items, vec![item("1"), item("2")]);
+        assert_eq!(cursor, Some(item("2")));
+
+        let (items, cursor) = block_on(client.clone().query_page(QueryInput {
+            exclusive_start_key: cursor,
+            ..QueryInput::default()
+        }))
+        .expect("page to be queried");
+        assert_eq!(items, vec![item("3"), item("4")]);
+        assert_eq!(cursor, None);
+        assert_eq!(
+            client
+                .query
+                .inputs()
+                .into_iter()
+                .map(|input| input.exclusive_start_key)
+                .collect::<Vec<_>>(),
+            vec![None, Some(item("2"))]
+        );
+    }
+
+    #[test]
+    fn count_query_sums_page_counts() {
+        let client = MockDynamoDb::default();
+        client
+            .query
+            .respond(Ok(QueryOutput {
+                count: Some(3),
+                last_evaluated_key: Some(item("3")),
+                ..QueryOutput::default()
+            }))
+            .respond(Ok(QueryOutput {
+                count: Some(2),
+                ..QueryOutput::default()
+            }));
+        let count = block_on(client.clone().count_query(QueryInput::default()))
+            .expect("items to be counted");
+        assert_eq!(count, 5);
+        assert_eq!(
+            client
+                .query
+                .inputs()
+                .into_iter()
+                .map(|input| (input.select, input.exclusive_start_key))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("COUNT".to_string()), None),
+                (Some("COUNT".to_string()), Some(item("3")))
+            ]
+        );
+    }
+
+    #[test]
+    fn count_scan_sums_page_counts() {
+        let client = MockDynamoDb::default();
+        client
+            .scan
+            .respond(Ok(ScanOutput {
+                count: Some(4),
+                last_evaluated_key: Some(item("4")),
+                ..ScanOutput::default()
+            }))
+            .respond(Ok(ScanOutput {
+                count: Some(1),
+                ..ScanOutput::default()
+            }));
+        let count =
+            block_on(client.clone().count_scan(ScanInput::default())).expect("items to be counted");
+        assert_eq!(count, 5);
+        assert!(client
+            .scan
+            .inputs()
+            .iter()
+            .all(|input| input.select.as_deref() == Some("COUNT")));
+    }
+
+    /// An item with a string partition key named `id` and a numeric sort key named `n`
+    #[derive(Debug, Clone, PartialEq)]
+    struct Keyed {
+        id: String,
+        n: u32,
+    }
+
+    impl IntoAttributes for Keyed {
+        fn into_attrs(
+            self,
+            attrs: &mut HashMap<String, AttributeValue>,
+        ) {
+            attrs.insert("id".into(), self.id.into_attr());
+            attrs.insert("n".into(), self.n.into_attr());
+        }
+    }
+
+    impl FromAttributes for Keyed {
+        fn from_attrs(
+            attrs: &mut HashMap<String, AttributeValue>
+        ) -> Result<Self, crate::AttributeError> {
+            Ok(Keyed {
+                id: crate::Attribute::from_attr(attrs.remove("id").unwrap_or_default())?,
+                n: crate::Attribute::from_attr(attrs.remove("n").unwrap_or_default())?,
+            })
+        }
+    }
+
+    impl Item for Keyed {
+        fn key(&self) -> HashMap<String, AttributeValue> {
+            let mut key = HashMap::new();
+            key.insert("id".into(), self.id.clone().into_attr());
+            key.insert("n".into(), self.n.into_attr());
+            key
+        }
+
+        fn key_schema() -> Option<(Vec<KeySchemaElement>, Vec<AttributeDefinition>)> {
+            Some((
+                vec![key("id", "HASH"), key("n", "RANGE")],
+                vec![definition("id", "S"), definition("n", "N")],
+            ))
+        }
+    }
+
+    fn key(
+        name: &str,
+        key_type: &str,
+    ) -> KeySchemaElement {
+        KeySchemaElement {
+            attribute_name: name.into(),
+            key_type: key_type.into(),
+        }
+    }
+
+    fn definition(
+        name: &str,
+        attribute_type: &str,
+    ) -> AttributeDefinition {
+        AttributeDefinition {
+            attribute_name: name.into(),
+            attribute_type: attribute_type.into(),
+        }
+    }
+
+    /// A client describing a table with the given key schema
+    fn described(
+        keys: Vec<KeySchemaElement>,
+        definitions: Vec<AttributeDefinition>,
+    ) -> MockDynamoDb {
+        let client = MockDynamoDb::default();
+        client.describe_table.respond(Ok(DescribeTableOutput {
+            table: Some(TableDescription {
+                key_schema: Some(keys),
+                attribute_definitions: Some(definitions),
+                ..TableDescription::default()
+            }),
+        }));
+        client
+    }
+
+    #[test]
+    fn put_all_returns_unprocessed_items() {
+        let items = (0..30)
+            .map(|n| Keyed { id: "a".into(), n })
+            .collect::<Vec<_>>();
+        let stuck = items[27].clone();
+        let unprocessed = || {
+            let mut attrs = HashMap::new();
+            stuck.clone().into_attrs(&mut attrs);
+            let mut requests = HashMap::new();
+            requests.insert(
+                "table".to_string(),
+                vec![WriteRequest {
+                    put_request: Some(PutRequest { item: attrs }),
+                    ..WriteRequest::default()
+                }],
+            );
+            Some(requests)
+        };
+        let client = MockDynamoDb::default();
+        client
+            .batch_write_item
+            // the first batch is written
+            .respond(Ok(BatchWriteItemOutput::default()))
+            // one item of the second remains unprocessed when it's resubmitted
+            .respond(Ok(BatchWriteItemOutput {
+                unprocessed_items: unprocessed(),
+                ..BatchWriteItemOutput::default()
+            }))
+            .respond(Ok(BatchWriteItemOutput {
+                unprocessed_items: unprocessed(),
+                ..BatchWriteItemOutput::default()
+            }))
+            .respond(Ok(BatchWriteItemOutput {
+                unprocessed_items: unprocessed(),
+                ..BatchWriteItemOutput::default()
+            }));
+        let failed = block_on(client.clone().put_all(
+            "table".into(),
+            items,
+            Policy::Pause(1, Duration::from_millis(1)),
+        ))
+        .expect("items to be put");
+        assert_eq!(failed, vec![stuck]);
+        let batches = client
+            .batch_write_item
+            .inputs()
+            .iter()
+            .map(|input| input.request_items["table"].len())
+            .collect::<Vec<_>>();
+        // the unprocessed item is resubmitted, and resubmitted again by the policy's single retry
+        assert_eq!(batches, vec![25, 5, 1, 1]);
+    }
+
+    #[test]
+    fn verify_schema_accepts_matching_tables() {
+        let client = described(
+            vec![key("id", "HASH"), key("n", "RANGE")],
+            vec![
+                definition("id", "S"),
+                definition("n", "N"),
+                definition("status", "S"),
+            ],
+        );
+        assert_eq!(
+            block_on(client.clone().verify_schema::<Keyed>("table".into())),
+            Ok(())
+        );
+        assert_eq!(client.describe_table.inputs()[0].table_name, "table");
+    }
+
+    #[test]
+    fn verify_schema_names_mismatched_attributes() {
+        let verify = |keys, definitions| match block_on(
+            described(keys, definitions).verify_schema::<Keyed>("table".into()),
+        ) {
+            Err(RusotoError::Validation(message)) => message,
+            other => panic!("expected a validation error but got {:?}", other),
+        };
+        assert_eq!(
+            verify(
+                vec![key("id", "HASH"), key("n", "RANGE")],
+                vec![definition("id", "S"), definition("n", "S")],
+            ),
+            "Key schema of table `table` doesn't match: expected `n` to be of type N but it is of type S"
+        );
+        assert_eq!(
+            verify(
+                vec![key("id", "HASH"), key("sk", "RANGE")],
+                vec![definition("id", "S"), definition("sk", "N")],
+            ),
+            "Key schema of table `table` doesn't match: expected a RANGE key named `n` but the table has none"
+        );
+        assert_eq!(
+            verify(
+                vec![key("n", "HASH"), key("id", "RANGE")],
+                vec![definition("id", "S"), definition("n", "N")],
+            ),
+            "Key schema of table `table` doesn't match: expected `id` to be a HASH key but it is a RANGE key"
+        );
+    }
+
+    #[test]
+    fn batch_get_all_deduplicates_keys() {
+        let client = MockDynamoDb::default();
+        let mut responses = HashMap::new();
+        responses.insert("test".to_string(), vec![item("2"), item("1")]);
+        client.batch_get_item.respond(Ok(BatchGetItemOutput {
+            responses: Some(responses),
+            ..BatchGetItemOutput::default()
+        }));
+        let keys = vec![item("1"), item("2"), item("1"), item("3"), item("2")];
+        let items = block_on(
+            client
+                .clone()
+                .batch_get_all("test".into(), keys, Policy::default()),
+        )
+        .expect("items to be fetched");
+        assert_eq!(
+            items,
+            vec![
+                Some(item("1")),
+                Some(item("2")),
+                Some(item("1")),
+                None,
+                Some(item("2"))
+            ]
+        );
+        // DynamoDB rejects batches with duplicate keys
+        let inputs = client.batch_get_item.inputs();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(
+            inputs[0].request_items["test"].keys,
+            vec![item("1"), item("2"), item("3")]
+        );
+    }
+
+    #[test]
+    fn batch_get_all_fails_on_keys_remaining_unprocessed() {
+        let client = MockDynamoDb::default();
+        let mut unprocessed = HashMap::new();
+        unprocessed.insert(
+            "test".to_string(),
+            KeysAndAttributes {
+                keys: vec![item("1")],
+                ..KeysAndAttributes::default()
+            },
+        );
+        for _ in 0..3 {
+            client.batch_get_item.respond(Ok(BatchGetItemOutput {
+                unprocessed_keys: Some(unprocessed.clone()),
+                ..BatchGetItemOutput::default()
+            }));
+        }
+        let result = block_on(client.clone().batch_get_all(
+            "test".into(),
+            vec![item("1")],
+            Policy::Pause(1, Duration::from_millis(1)),
+        ));
+        assert!(matches!(
+            result,
+            Err(RusotoError::Service(
+                BatchGetItemError::ProvisionedThroughputExceeded(_)
+            ))
+        ));
+        // the first request, followed by the policy's attempts at draining its unprocessed keys
+        assert_eq!(client.batch_get_item.inputs().len(), 3);
+    }
+
+    #[test]
+    fn transact_write_all_writes_single_transaction() {
+        let client = MockDynamoDb::default();
+        let outputs = block_on(client.clone().transact_write_all(
+            vec![TransactWriteItem::default(); TRANSACT_WRITE_ITEMS_LIMIT],
+            false,
+        ))
+        .expect("items to be written");
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(client.transact_write_items.inputs().len(), 1);
+    }
+
+    #[test]
+    fn transact_write_all_rejects_too_many_actions() {
+        let client = MockDynamoDb::default();
+        let result = block_on(client.clone().transact_write_all(
+            vec![TransactWriteItem::default(); TRANSACT_WRITE_ITEMS_LIMIT + 1],
+            false,
+        ));
+        assert!(matches!(result, Err(RusotoError::Validation(_))));
+        assert_eq!(client.transact_write_items.inputs().len(), 0);
+    }
+
+    #[test]
+    fn transact_write_all_splits_when_allowed() {
+        let client = MockDynamoDb::default();
+        let outputs = block_on(client.clone().transact_write_all(
+            vec![TransactWriteItem::default(); 2 * TRANSACT_WRITE_ITEMS_LIMIT + 1],
+            true,
+        ))
+        .expect("items to be written");
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(
+            client
+                .transact_write_items
+                .inputs()
+                .iter()
+                .map(|input| input.transact_items.len())
+                .collect::<Vec<_>>(),
+            vec![TRANSACT_WRITE_ITEMS_LIMIT, TRANSACT_WRITE_ITEMS_LIMIT, 1]
+        );
+    }
+
+    #[test]
+    fn put_item_returning_decodes_old_item() {
+        let client = MockDynamoDb::default();
+        client.put_item.respond(Ok(PutItemOutput {
+            attributes: Some(item("1")),
+            ..PutItemOutput::default()
+        }));
+        let mut new = HashMap::new();
+        new.insert("id".to_string(), "2".to_string());
+        let old = block_on(client.clone().put_item_returning(
+            "test".into(),
+            new,
+            ReturnValues::AllOld,
+        ))
+        .expect("item to be put");
+        let mut expected = HashMap::new();
+        expected.insert("id".to_string(), "1".to_string());
+        assert_eq!(old, Some(expected));
+        let inputs = client.put_item.inputs();
+        assert_eq!(inputs[0].return_values.as_deref(), Some("ALL_OLD"));
+        assert_eq!(inputs[0].item, item("2"));
+    }
+
+    #[test]
+    fn put_item_returning_nothing() {
+        let client = MockDynamoDb::default();
+        let old = block_on(client.put_item_returning(
+            "test".into(),
+            HashMap::<String, String>::new(),
+            ReturnValues::None,
+        ))
+        .expect("item to be put");
+        assert_eq!(old, None);
+    }
+
+    #[test]
+    fn update_item_returning_fails_on_invalid_attributes() {
+        let client = MockDynamoDb::default();
+        client.update_item.respond(Ok(UpdateItemOutput {
+            attributes: Some(item("1")),
+            ..UpdateItemOutput::default()
+        }));
+        let result = block_on(
+            client
+                .clone()
+                .update_item_returning::<HashMap<String, u32>>(
+                    UpdateItemInput::default(),
+                    ReturnValues::UpdatedNew,
+                ),
+        );
+        assert!(matches!(result, Err(Error::Attribute(_))));
+        assert_eq!(
+            client.update_item.inputs()[0].return_values.as_deref(),
+            Some("UPDATED_NEW")
+        );
+    }
+
+    #[test]
+    fn scan_pages_flattens_items() {
+        let client = two_pages();
+        let items = block_on(
+            client
+                .clone()
+                .scan_pages(ScanInput::default())
+                .try_collect::<Vec<_>>(),
         )
+        .expect("items to be scanned");
+        assert_eq!(items, vec![item("1"), item("2"), item("3"), item("4")]);
+        assert_eq!(client.scan.inputs().len(), 2);
     }
 }
@@ -0,0 +1,95 @@
+//! Support for flattening fixed size arrays of attribute maps
+//!
+//! Single-table designs sometimes denormalize a fixed number of repeated sub-objects into
+//! an item's top level attributes, distinguishing each by an index-based prefix such as
+//! `item_0_name`, `item_1_name`. These functions are what `#[dynomite(flatten, prefix = "...")]`
+//! array fields are serialized with, but can also be used directly when implementing
+//! [`FromAttributes`](../trait.FromAttributes.html) and [`IntoAttributes`](../trait.IntoAttributes.html) by hand.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{flatten, Attributes};
+//!
+//! #[derive(Attributes, Debug, PartialEq)]
+//! struct Slot {
+//!     name: String,
+//! }
+//!
+//! let mut attrs = Attributes::new();
+//! flatten::into_prefixed_attrs(
+//!     [Slot { name: "a".into() }, Slot { name: "b".into() }],
+//!     "item_",
+//!     &mut attrs,
+//! );
+//! assert!(attrs.contains_key("item_0_name"));
+//! assert!(attrs.contains_key("item_1_name"));
+//!
+//! let slots: [Slot; 2] = flatten::from_prefixed_attrs("item_", &mut attrs).unwrap();
+//! assert_eq!(slots[1], Slot { name: "b".into() });
+//! ```
+
+use crate::{AttributeError, Attributes, FromAttributes, IntoAttributes};
+use std::convert::TryFrom;
+
+/// Inserts the attributes of each element into `sink`, with keys prefixed by `{prefix}{index}_`
+pub fn into_prefixed_attrs<T: IntoAttributes, const N: usize>(
+    values: [T; N],
+    prefix: &str,
+    sink: &mut Attributes,
+) {
+    for (index, value) in IntoIterator::into_iter(values).enumerate() {
+        let mut attrs = Attributes::new();
+        value.into_attrs(&mut attrs);
+        sink.extend(
+            attrs
+                .into_iter()
+                .map(|(key, value)| (format!("{}{}_{}", prefix, index, key), value)),
+        );
+    }
+}
+
+/// Removes the attributes with keys prefixed by `{prefix}{index}_` from `attrs`
+/// and resolves an element from each
+pub fn from_prefixed_attrs<T: FromAttributes, const N: usize>(
+    prefix: &str,
+    attrs: &mut Attributes,
+) -> Result<[T; N], AttributeError> {
+    let values = (0..N)
+        .map(|index| {
+            let element_prefix = format!("{}{}_", prefix, index);
+            let keys = attrs
+                .keys()
+                .filter(|key| key.starts_with(&element_prefix))
+                .cloned()
+                .collect::<Vec<_>>();
+            let mut element = keys
+                .into_iter()
+                .filter_map(|key| attrs.remove_entry(&key))
+                .map(|(key, value)| (key[element_prefix.len()..].to_string(), value))
+                .collect();
+            T::from_attrs(&mut element)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(<[T; N]>::try_from(values)
+        .unwrap_or_else(|_| unreachable!("exactly {} elements are resolved", N)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attribute;
+    use maplit::hashmap;
+    use std::collections::HashMap;
+
+    #[test]
+    fn prefixes_do_not_overlap() {
+        let mut attrs: Attributes = hashmap! {
+            "item_1_a".to_string() => 1.into_attr(),
+            "item_10_a".to_string() => 10.into_attr(),
+        };
+        let values: [HashMap<String, u32>; 2] = from_prefixed_attrs("item_", &mut attrs).unwrap();
+        assert_eq!(values, [HashMap::new(), hashmap! { "a".to_string() => 1 }]);
+        assert_eq!(attrs.keys().collect::<Vec<_>>(), vec!["item_10_a"]);
+    }
+}
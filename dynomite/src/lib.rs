@@ -97,6 +97,34 @@
 //! }
 //! ```
 //!
+//! An associated `fn create_table_input(table_name: impl Into<String>) -> CreateTableInput` function
+//! is also generated, which defines an on-demand table with the item's key schema, attribute
+//! definitions and global secondary indexes. This is useful for creating tables in tests and
+//! bootstrapping code. Key attribute types are taken from the `Attribute::ATTRIBUTE_TYPE` of the key
+//! fields' types, which `#[derive(Attribute)]` fills in for enums, composed structs and newtypes.
+//! Key fields of types which leave it as `None` fail to compile.
+//!
+//! ```
+//! use dynomite::Item;
+//!
+//! #[derive(Item)]
+//! struct Order {
+//!     #[dynomite(partition_key)]
+//!     user: String,
+//!     #[dynomite(sort_key)]
+//!     created_at: u64,
+//! }
+//!
+//! let input = Order::create_table_input("orders");
+//! assert_eq!(input.key_schema[1].attribute_name, "created_at");
+//! assert_eq!(input.attribute_definitions[1].attribute_type, "N");
+//! ```
+//!
+//! An associated `fn put_if_not_exists_input(self, table_name: impl Into<String>) -> PutItemInput`
+//! method is generated as well, which puts the item with a condition expression of
+//! `attribute_not_exists(#pk)`, `#pk` being a placeholder for the partition key's attribute name, so
+//! that the put fails rather than replacing an item with the same key.
+//!
 //! - `#[dynomite(partition_key)]` - required attribute, expected to be applied the target
 //!  [partition attribute][partition-key] field with a derivable DynamoDB attribute value
 //!  of String, Number or Binary
@@ -105,14 +133,73 @@
 //!  [sort attribute](sort-key) field with an derivable DynamoDB attribute value
 //!  of String, Number or Binary
 //!
+//! - `#[dynomite(gsi(name = "IndexName", partition_key))]` /
+//!   `#[dynomite(gsi(name = "IndexName", sort_key))]` - optional attribute, declares the field
+//!   as the partition or sort key of a [global secondary index][gsi]. Each index must declare
+//!   exactly one `partition_key` and at most one `sort_key`. When present, an associated
+//!   `fn global_secondary_indexes() -> Vec<GlobalSecondaryIndex>` function is generated which
+//!   returns index definitions, projecting all attributes, suitable for creating tables.
+//!   An `<Item><IndexName>Key` struct, like `<Item>Key`, is also generated for each index
+//!   with the index name converted to upper camel case
+//!
+//!   ```
+//!   use dynomite::{Attributes, Item};
+//!
+//!   #[derive(Item)]
+//!   struct Order {
+//!       #[dynomite(partition_key)]
+//!       id: String,
+//!       #[dynomite(gsi(name = "by_status", partition_key))]
+//!       status: String,
+//!       #[dynomite(gsi(name = "by_status", sort_key))]
+//!       created_at: u64,
+//!   }
+//!
+//!   let indexes = Order::global_secondary_indexes();
+//!   assert_eq!(indexes[0].index_name, "by_status");
+//!
+//!   let key: Attributes = OrderByStatusKey {
+//!       status: "shipped".into(),
+//!       created_at: 1,
+//!   }
+//!   .into();
+//!   assert_eq!(key.len(), 2);
+//!   ```
+//!
 //! - All other attributes are the same as for [`#[derive(Attributes)]`](#deriveattributes)
 //!
+//! The generated `into_key`, like `into_attrs`, moves fields out of the item rather than cloning
+//! them. As fields can't be moved out of types which implement `Drop`, deriving `Item` for these
+//! fails with `E0509`; implement `Item`, `IntoAttributes` and `FromAttributes` for them by hand instead.
+//!
 //! ### `#[derive(Attributes)]`
 //!
 //! Used to derive an implementation of `From/IntoAttributes` trait to allow for
 //! serializing/deserializing map-like types into [`AttributeValue`].
 //! This also generates `TryFrom<Attributes>` and `Into<Attributes>` implementations.
 //!
+//! `PhantomData` marker fields, e.g. for type states, are neither written nor read, so don't
+//! need to implement `Attribute` or to be stored in DynamoDB.
+//!
+//! ```
+//! use dynomite::{attr_map, Attributes, FromAttributes};
+//! use std::marker::PhantomData;
+//!
+//! struct Draft;
+//!
+//! #[derive(Attributes)]
+//! struct Post {
+//!     title: String,
+//!     state: PhantomData<Draft>,
+//! }
+//!
+//! let post = Post::from_attrs(&mut attr_map! { "title" => "hello" }).unwrap();
+//! assert_eq!(Attributes::from(post).len(), 1);
+//! ```
+//!
+//! Unit structs are written as an empty map and read from any map, unless they are declared
+//! `#[dynomite(deny_unknown_fields)]`, in which case the map must be empty.
+//!
 //! - `#[dynomite(rename = "actualName")]` - optional attribute, may be applied to any item
 //!   attribute field, useful when the DynamoDB table you're interfacing with has
 //!   attributes whose names don't following Rust's naming conventions
@@ -120,7 +207,9 @@
 //! - `#[dynomite(skip_serializing_if = "expr_that_returns_function")]` - place this on a field
 //!   that should be skipped in the output map entirely if the given function returns `true`.
 //!   The value of this attribute must be a path to a function that satisfies the signature
-//!   `FnOnce(&T) -> bool`, where `T` is the field type (possibly after some auto-deref coertions).
+//!   `FnOnce(&T) -> bool`, where `T` is the field type (possibly after some auto-deref coertions),
+//!   as it is called with a reference to the field, `path(&self.field)`. This includes inherent
+//!   methods taking `&self`, such as `Vec::is_empty` or `Option::is_none`, as well as free functions.
 //!
 //!   This is is inspired by [`#[serde(skip_serializing_if = "...")]`][serde-skip-serializing-if].
 //!
@@ -146,7 +235,8 @@
 //!   ```
 //!
 //! - `#[dynomite(default)]` - use [`Default::default`] implementation of the field type
-//!   if the attribute is absent when deserializing from `Attributes`
+//!   if the attribute is absent when deserializing from `Attributes`.
+//!   `#[dynomite(default = "path")]` instead calls the function at the given path
 //!
 //!   ```
 //!   use dynomite::Attributes;
@@ -157,14 +247,100 @@
 //!       #[dynomite(default)]
 //!       items: Vec<String>,
 //!       list_name: String,
+//!       #[dynomite(default = "default_limit")]
+//!       limit: u32,
+//!   }
+//!
+//!   fn default_limit() -> u32 {
+//!       10
+//!   }
+//!   ```
+//!
+//! - `#[dynomite(optional)]` - may be applied to an `Option<T>` field so that it deserializes
+//!   to `None` when the attribute is absent, as well as when it is present as `NULL`.
+//!   When serializing, `None` is represented as `NULL` by default. Combine this with
+//!   `skip_serializing_if = "Option::is_none"` to omit the attribute instead
+//!
+//!   ```
+//!   use dynomite::Attributes;
+//!
+//!   #[derive(Attributes)]
+//!   struct Contact {
+//!       email: String,
+//!       // written as `NULL` when `None`
+//!       #[dynomite(optional)]
+//!       phone: Option<String>,
+//!       // omitted when `None`
+//!       #[dynomite(optional, skip_serializing_if = "Option::is_none")]
+//!       fax: Option<String>,
+//!   }
+//!   ```
+//!
+//! - `#[dynomite(base64)]` - may be applied to a `Vec<u8>` or `Bytes` field to store its
+//!   value as a base64 encoded string (`S`) rather than as binary (`B`). This is useful for
+//!   interoperating with producers which store binary data as strings.
+//!   See [`encoding::base64`](encoding/base64/index.html) for details on the alphabet used.
+//!
+//!   ```
+//!   use dynomite::Attributes;
+//!
+//!   #[derive(Attributes)]
+//!   struct Document {
+//!       #[dynomite(base64)]
+//!       checksum: Vec<u8>,
+//!   }
+//!   ```
+//!
+//! - `#[dynomite(lenient)]` - may be applied to a field to read its value from either of the
+//!   string (`S`) or number (`N`) types, whichever it is stored as. This eases migrating an attribute
+//!   from one type to the other, e.g. a `String` field may read items which stored a number.
+//!   Values are written as usual. See [`encoding::lenient`](encoding/lenient/index.html) for details.
+//!
+//!   ```
+//!   use dynomite::{attr_map, Attributes, FromAttributes};
+//!
+//!   #[derive(Attributes)]
+//!   struct Account {
+//!       #[dynomite(lenient)]
+//!       number: String,
+//!   }
+//!
+//!   let account = Account::from_attrs(&mut attr_map! { "number" => 1234 }).unwrap();
+//!   assert_eq!(account.number, "1234");
+//!   ```
+//!
+//! - `#[dynomite(version)]` - may be applied to an integer field of an `Item` to use it for
+//!   optimistic locking. The item's `Item::put_input` is then generated to increment the version
+//!   and put the item on the condition that the stored item's version is still the one it was read
+//!   with, including when it's called by code generic over `Item`s. An item whose version is `0` is taken to be new,
+//!   so is put on the condition that no item with its partition key exists yet. Either way, a
+//!   concurrent write fails the put with a `ConditionalCheckFailed` error.
+//!
+//!   ```
+//!   use dynomite::Item;
+//!
+//!   #[derive(Item)]
+//!   struct Account {
+//!       #[dynomite(partition_key)]
+//!       id: String,
+//!       #[dynomite(version)]
+//!       version: u64,
+//!   }
+//!
+//!   let input = Account {
+//!       id: "a".into(),
+//!       version: 3,
 //!   }
+//!   .put_input("accounts");
+//!   assert_eq!(input.condition_expression.as_deref(), Some("#version = :expected"));
+//!   assert_eq!(input.item["version"].n.as_deref(), Some("4"));
 //!   ```
 //!
 //! - `#[dynomite(flatten)]` - flattens the fields of other struct that also derives `Attributes`
 //!   into the current struct.
 //!
 //!   💡 If this attribute is placed onto a field, no other `dynomite` attributes
-//!   are alowed on this field (this restriction may be relaxed in future).
+//!   are alowed on this field, except for `prefix` described below (this restriction may be relaxed in future).
 //!
 //!   This is reminiscent of [`#[serde(flatten)]`][serde-flatten]. The order of
 //!   declaration of `flatten`ed fields matters, if the struct has to fields with
@@ -197,6 +373,271 @@
 //!   }
 //!   ```
 //!
+//!   Any map type implementing `IntoAttributes` and `FromAttributes` may collect additional
+//!   properties, e.g. a `BTreeMap<String, AttributeValue>` to order them by name.
+//!
+//!   An `Option` of a struct may be flattened too. `None` writes no attributes, and the field is read
+//!   as `None` when none of the struct's own fields are present, or as `Some` otherwise, failing as
+//!   usual should any of its required fields be missing.
+//!
+//!   ```
+//!   use dynomite::{attr_map, Attributes, FromAttributes};
+//!
+//!   #[derive(Attributes)]
+//!   struct Billing {
+//!       card: String,
+//!   }
+//!
+//!   #[derive(Attributes)]
+//!   struct Customer {
+//!       name: String,
+//!       #[dynomite(flatten)]
+//!       billing: Option<Billing>,
+//!   }
+//!
+//!   let customer = Customer::from_attrs(&mut attr_map! { "name" => "foo" }).unwrap();
+//!   assert!(customer.billing.is_none());
+//!   ```
+//!
+//! - `#[dynomite(flatten, prefix = "item_")]` - flattens each element of a fixed size array
+//!   `[T; N]` of structs that also derive `Attributes` into the current struct, prefixing each
+//!   element's attribute names with the given prefix and the element's index, e.g. `item_0_name`.
+//!   This is useful for denormalized single-table layouts which store a fixed number of repeated
+//!   sub-objects. See [`flatten`](flatten/index.html) for the underlying conversions.
+//!
+//!   ```
+//!   use dynomite::Attributes;
+//!
+//!   #[derive(Attributes)]
+//!   struct Slot {
+//!       name: String,
+//!   }
+//!
+//!   #[derive(Attributes)]
+//!   struct Schedule {
+//!       // stored as `slot_0_name` and `slot_1_name`
+//!       #[dynomite(flatten, prefix = "slot_")]
+//!       slots: [Slot; 2],
+//!   }
+//!   ```
+//!
+//! - `#[dynomite(prefix = "USER#")]` - stores a field's value as a string following the given
+//!   prefix, and strips it when reading the field, failing with `AttributeError::InvalidFormat`
+//!   when it's absent. This keeps decorated keys of single-table designs typed, e.g. as a `Uuid`
+//!   stored as `USER#<uuid>`. The field's type must be represented by the `S` or `N` types, so
+//!   `Option`s, booleans, collections and binary values are rejected.
+//!   See [`encoding::prefixed`](encoding/prefixed/index.html) for the underlying conversions.
+//!
+//!   ```
+//!   use dynomite::{Attributes, Item};
+//!   use uuid::Uuid;
+//!
+//!   #[derive(Item)]
+//!   struct User {
+//!       #[dynomite(partition_key, prefix = "USER#")]
+//!       id: Uuid,
+//!   }
+//!
+//!   let id = Uuid::new_v4();
+//!   let attrs: Attributes = User { id }.into();
+//!   assert_eq!(attrs["id"].s, Some(format!("USER#{}", id)));
+//!   ```
+//!
+//! - `#[dynomite(finalize = "path")]` - placed on the struct itself, names a function with the
+//!   signature `fn(&mut Self)` which is called once all fields have been deserialized from
+//!   `Attributes`. This is useful for reconstructing fields computed from other, possibly
+//!   `flatten`ed, fields
+//!
+//!   ```
+//!   use dynomite::Attributes;
+//!
+//!   #[derive(Attributes)]
+//!   #[dynomite(finalize = "Rectangle::compute_area")]
+//!   struct Rectangle {
+//!       width: u32,
+//!       height: u32,
+//!       // recomputed on read
+//!       #[dynomite(default)]
+//!       area: u32,
+//!   }
+//!
+//!   impl Rectangle {
+//!       fn compute_area(&mut self) {
+//!           self.area = self.width * self.height;
+//!       }
+//!   }
+//!   ```
+//!
+//! - `#[dynomite(transparent)]` - placed on a struct with exactly one field, represents the
+//!   struct in the same way as that field, which must itself implement `Attributes`,
+//!   rather than as a map containing it. With `#[derive(Attribute)]` the field need only
+//!   implement `Attribute`, as for single field tuple structs
+//!
+//!   ```
+//!   use dynomite::Attributes;
+//!
+//!   #[derive(Attributes)]
+//!   struct Address {
+//!       street: String,
+//!   }
+//!
+//!   // stored as `{ "street": "..." }`
+//!   #[derive(Attributes)]
+//!   #[dynomite(transparent)]
+//!   struct BillingAddress(Address);
+//!   ```
+//!
+//! - `#[dynomite(prepare = "path")]` - placed on the struct itself, names a function with the
+//!   signature `fn(&mut Self)` which is called before any fields are serialized into `Attributes`.
+//!   This is useful for normalizing values consistently on every write. An `Item`'s key is read
+//!   without preparing it, e.g. by `Item::key` for `get_input` and `delete_input`, so `prepare` must
+//!   not modify key fields, which should be normalized when the item is constructed instead. Debug
+//!   builds panic when it does
+//!
+//!   ```
+//!   use dynomite::Attributes;
+//!
+//!   #[derive(Attributes)]
+//!   #[dynomite(prepare = "Account::normalize")]
+//!   struct Account {
+//!       email: String,
+//!   }
+//!
+//!   impl Account {
+//!       fn normalize(&mut self) {
+//!           self.email = self.email.to_lowercase();
+//!       }
+//!   }
+//!   ```
+//!
+//! - `#[dynomite(default)]` - placed on the struct itself, which must implement `Default`, takes the
+//!   value of each absent field from the struct's `std::default::Default::default()` value, rather than
+//!   failing on the first missing field, as though every field was declared `#[dynomite(default)]`.
+//!   This pairs well with `flatten`ed structs whose attributes may all be absent, and with
+//!   partially populated items, e.g. from a projection. Field level `default` and `optional`
+//!   attributes take precedence, while `flatten`ed fields and key fields are read as usual
+//!
+//!   ```
+//!   use dynomite::{attr_map, Attributes, FromAttributes};
+//!
+//!   #[derive(Attributes, Default, Debug, PartialEq)]
+//!   #[dynomite(default)]
+//!   struct Dimensions {
+//!       width: u32,
+//!       height: u32,
+//!   }
+//!
+//!   assert_eq!(
+//!       Dimensions::from_attrs(&mut Default::default()),
+//!       Ok(Dimensions::default())
+//!   );
+//!   assert_eq!(
+//!       Dimensions::from_attrs(&mut attr_map! { "width" => 2 }),
+//!       Ok(Dimensions {
+//!           width: 2,
+//!           height: 0
+//!       })
+//!   );
+//!   ```
+//!
+//! - `#[dynomite(presence)]` - placed on the struct itself, additionally generates a `<Name>Presence`
+//!   struct with the same visibility as the struct. It has a `bool` for each optional field, that is each
+//!   `Option` field and each field with the `default` or `optional` attribute. A `<Name>::from_attrs_with_presence`
+//!   function is generated along with it, which returns which of these fields were present. This distinguishes
+//!   fields which were absent from those stored as `NULL`, which is useful for merging partial updates
+//!
+//!   ```
+//!   use dynomite::{attr_map, Attributes};
+//!
+//!   #[derive(Attributes)]
+//!   #[dynomite(presence)]
+//!   struct Profile {
+//!       name: String,
+//!       #[dynomite(default)]
+//!       nickname: Option<String>,
+//!   }
+//!
+//!   let mut attrs = attr_map! { "name" => "rust" };
+//!   let (profile, presence) = Profile::from_attrs_with_presence(&mut attrs).unwrap();
+//!   assert_eq!(profile.nickname, None);
+//!   assert!(!presence.nickname);
+//!   ```
+//!
+//! - `#[dynomite(deny_unknown_fields)]` - placed on the struct itself, fails with
+//!   `AttributeError::UnknownField` when attributes remain once all of its fields are read, rather
+//!   than silently ignoring them, which helps detect schema drift. This is inspired by serde's
+//!   `deny_unknown_fields`. A `flatten`ed map field collects all remaining attributes so none are unknown.
+//!   Structs which are themselves `flatten`ed into others should not use this, as they share their attributes
+//!
+//!   ```
+//!   use dynomite::{attr_map, AttributeError, Attributes, FromAttributes};
+//!
+//!   #[derive(Attributes, Debug)]
+//!   #[dynomite(deny_unknown_fields)]
+//!   struct Strict {
+//!       name: String,
+//!   }
+//!
+//!   assert_eq!(
+//!       Strict::from_attrs(&mut attr_map! { "name" => "rust", "age" => 1 }).unwrap_err(),
+//!       AttributeError::UnknownField { name: "age".into() }
+//!   );
+//!   ```
+//!
+//! - `#[dynomite(key_derive(Hash, Eq))]` - placed on an `Item` struct, additionally derives the listed
+//!   traits for its generated key structs, both the `<Name>Key` struct and those of its global secondary
+//!   indexes, which always derive `Debug`, `Clone` and `PartialEq`, e.g. to use them as `HashMap` keys.
+//!   This is opt-in as not all key field types, such as `f64`, implement `Hash` or `Eq`
+//!
+//!   ```
+//!   use dynomite::Item;
+//!   use std::collections::HashMap;
+//!
+//!   #[derive(Item)]
+//!   #[dynomite(key_derive(Hash, Eq))]
+//!   struct Person {
+//!       #[dynomite(partition_key)]
+//!       id: String,
+//!   }
+//!
+//!   let mut cache = HashMap::new();
+//!   cache.insert(PersonKey { id: "123".into() }, "rust");
+//!   ```
+//!
+//! - `#[dynomite(sort_key(name = "sk", compute = "path"))]` - placed on an `Item` struct in place of a
+//!   `sort_key` field, names a sort key attribute whose value is computed by a function with the signature
+//!   `fn(&Self) -> String`. This is common in single table designs, where sort keys are often prefixed
+//!   strings. The computed attribute is written along with the item's fields and its `key()`, but is
+//!   discarded when reading, so any state derived from it should be stored in fields as well. The generated
+//!   `<Name>Key` struct represents it as a `sort_key` field
+//!
+//!   ```
+//!   use dynomite::{attr_map, Item};
+//!
+//!   #[derive(Item)]
+//!   #[dynomite(sort_key(name = "sk", compute = "Order::format_sort_key"))]
+//!   struct Order {
+//!       #[dynomite(partition_key)]
+//!       customer: String,
+//!       id: u32,
+//!   }
+//!
+//!   impl Order {
+//!       fn format_sort_key(&self) -> String {
+//!           format!("ORDER#{}", self.id)
+//!       }
+//!   }
+//!
+//!   let order = Order {
+//!       customer: "rust".into(),
+//!       id: 1,
+//!   };
+//!   assert_eq!(
+//!       order.key(),
+//!       attr_map! { "customer" => "rust", "sk" => "ORDER#1" }
+//!   );
+//!   ```
+//!
 //! #### Fat enums
 //!
 //! Fat enums are naturally supported by `#[derive(Attribute)]`.
@@ -262,6 +703,28 @@
 //!   }
 //!   ```
 //!
+//! The structs wrapped by variants may `#[dynomite(flatten)]` an `Attributes` field to collect
+//! any attributes they don't name. This preserves attributes added by other writers
+//! as your enum's payloads evolve. The tag is never collected.
+//!
+//! ```
+//! use dynomite::Attributes;
+//!
+//! #[derive(Attributes)]
+//! #[dynomite(tag = "kind")]
+//! enum Event {
+//!     Created(Created),
+//! }
+//!
+//! #[derive(Attributes)]
+//! struct Created {
+//!     id: String,
+//!     // attributes other than `kind` and `id`
+//!     #[dynomite(flatten)]
+//!     unknown: Attributes,
+//! }
+//! ```
+//!
 //! If you have a plain old enum (without any data fields), you should use
 //! [`#[derive(Attribute)]`](#deriveattribute) instead.
 //!
@@ -304,23 +767,199 @@
 //! ```
 //!
 //! `role` field here may be any of `Admin`, `Moderator`, or `Regular` strings.
+//! Use `#[dynomite(rename = "...")]` on a variant to represent it by a different string,
+//! e.g. `#[dynomite(rename = "admin")]` to represent `Admin` as `admin`.
+//!
+//! Reading any other string fails with `AttributeError::InvalidFormat`. To remain compatible
+//! with variants added by other writers, a single unit variant may be declared `#[dynomite(other)]`,
+//! in the same way as serde's `#[serde(other)]`, to read unrecognized strings as that variant.
+//! Note that the original string is not retained, so that variant is written as its own name.
+//!
+//! ```
+//! use dynomite::Attribute;
+//!
+//! #[derive(Attribute, Debug, PartialEq)]
+//! enum UserRole {
+//!     Admin,
+//!     #[dynomite(other)]
+//!     Unknown,
+//! }
+//!
+//! assert_eq!(
+//!     UserRole::from_attr("Moderator".to_string().into_attr()),
+//!     Ok(UserRole::Unknown)
+//! );
+//! ```
+//!
+//! Maps are keyed by strings, converted with `FromStr` and `ToString`. Declaring
+//! `#[dynomite(map_key)]` on such an enum additionally implements `FromStr` and `Display` using the
+//! same strings, so that it may key maps such as `HashMap<UserRole, V>`. Unknown keys fail to parse
+//! with `AttributeError::InvalidFormat` unless a variant is declared `#[dynomite(other)]`.
+//!
+//! ```
+//! use dynomite::{Attribute, Attributes};
+//! use std::collections::HashMap;
+//!
+//! #[derive(Attribute, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! #[dynomite(map_key)]
+//! enum UserRole {
+//!     #[dynomite(rename = "admin")]
+//!     Admin,
+//!     Regular,
+//! }
+//!
+//! #[derive(Attributes)]
+//! struct Permissions {
+//!     grants: HashMap<UserRole, Vec<String>>,
+//! }
+//! ```
+//!
+//! Declaring `#[dynomite(numeric)]` on an enum whose variants are all units instead represents
+//! each variant by its discriminant as a number (`N`), honoring explicit discriminants such as
+//! `Admin = 10`, for tables which store numeric codes. Discriminants are converted as `i64`.
+//! Reading any other number fails with `AttributeError::InvalidFormat`, unless a variant is
+//! declared `#[dynomite(other)]`.
+//!
+//! ```
+//! use dynomite::Attribute;
+//!
+//! #[derive(Attribute, Debug, PartialEq)]
+//! #[dynomite(numeric)]
+//! enum UserRole {
+//!     Regular,
+//!     Moderator,
+//!     Admin = 10,
+//! }
+//!
+//! assert_eq!(UserRole::Moderator.into_attr().n.as_deref(), Some("1"));
+//! assert_eq!(UserRole::from_attr(10.into_attr()), Ok(UserRole::Admin));
+//! ```
+//!
+//! It may also be derived for single field tuple structs, or "newtypes", which
+//! are represented in the same way as the value they wrap. This is useful for
+//! strongly typed identifiers, including partition and sort keys, and for binary
+//! blobs such as a `struct Thumbnail(Vec<u8>)`, represented by `B`. Note that key
+//! fields are copied into the generated `<Item>Key` struct, which derives `Debug`,
+//! `Clone` and `PartialEq`.
+//!
+//! ```
+//! use dynomite::{Attribute, Item};
+//!
+//! #[derive(Attribute, Debug, Clone, PartialEq)]
+//! struct UserId(String);
+//!
+//! #[derive(Item)]
+//! struct User {
+//!     #[dynomite(partition_key)]
+//!     id: UserId,
+//! }
+//! ```
+//!
+//! Enums whose variants carry a single scalar payload, represented by the `S` or `N`
+//! AttributeValue types, may be stored compactly as a single string of the variant name
+//! and its payload with `#[dynomite(compact_string)]`. The separator defaults to `:` and
+//! may be changed with `#[dynomite(separator = "...")]`. Variant names, including those
+//! given with `#[dynomite(rename = "...")]`, must not contain the separator.
+//!
+//! ```
+//! use dynomite::Attribute;
+//!
+//! #[derive(Attribute, Debug, PartialEq)]
+//! #[dynomite(compact_string, separator = ":")]
+//! enum Status {
+//!     Pending,
+//!     Failed(u32),
+//! }
+//!
+//! assert_eq!(Status::Failed(42).into_attr().s.as_deref(), Some("Failed:42"));
+//! assert_eq!(Status::Pending.into_attr().s.as_deref(), Some("Pending"));
+//! ```
+//!
+//! Structs with a single named field may likewise be represented in the same way as their field
+//! when declared with `#[dynomite(transparent)]`, mirroring `#[serde(transparent)]`.
+//!
+//! ```
+//! use dynomite::Attribute;
+//!
+//! #[derive(Attribute, Debug, PartialEq)]
+//! #[dynomite(transparent)]
+//! struct Email {
+//!     value: String,
+//! }
+//!
+//! // stored as a bare string (`S`) rather than as a map
+//! let value = Email {
+//!     value: "jane@example.com".into(),
+//! }
+//! .into_attr();
+//! assert_eq!(value.s.as_deref(), Some("jane@example.com"));
+//! ```
+//!
+//! Structs with named fields may be represented as a single string composed of their fields
+//! with `#[dynomite(compose = "...")]`, where each `{field}` placeholder is replaced by the
+//! field's `S` or `N` value. This is useful for the composite sort keys common to single-table
+//! designs, and like newtypes such a struct may be used as a partition or sort key. Placeholders
+//! must be separated by literal text, which field values must not contain.
+//!
+//! ```
+//! use dynomite::{Attribute, Item};
+//!
+//! #[derive(Attribute, Debug, Clone, PartialEq)]
+//! #[dynomite(compose = "ORDER#{year}#{seq}")]
+//! struct OrderSortKey {
+//!     year: u16,
+//!     seq: u32,
+//! }
+//!
+//! #[derive(Item)]
+//! struct Order {
+//!     #[dynomite(partition_key)]
+//!     customer: String,
+//!     #[dynomite(sort_key, rename = "sk")]
+//!     sort_key: OrderSortKey,
+//! }
+//!
+//! let order = Order {
+//!     customer: "foo".into(),
+//!     sort_key: OrderSortKey { year: 2024, seq: 123 },
+//! };
+//! assert_eq!(order.key()["sk"].s.as_deref(), Some("ORDER#2024#123"));
+//! ```
 //!
 //! ## Rusoto extensions
 //!
 //! By importing the [dynomite::DynamoDbExt](trait.DynamoDbExt.html) trait, dynomite
 //! adds client interfaces for creating async Stream-based auto pagination interfaces.
 //!
+//! ## Expressions
+//!
+//! The [dynomite::expression](expression/index.html) module provides a fluent builder for
+//! key condition and condition expressions which keeps the expression text and its
+//! placeholder maps together.
+//!
 //! ## Robust retries
 //!
 //! By importing the [dynomite::Retries](retry/trait.Retries.html) trait, dynomite
 //! provides an interface for adding configuration retry policies so your
 //! rusoto DynamoDb clients.
 //!
+//! ## Testing
+//!
+//! The [dynomite::test](test/index.html) module provides an [`Av`](test/struct.Av.html) wrapper for
+//! `AttributeValue`s with a concise `Debug` representation, making assertion failures involving
+//! `Attributes` readable. The [dynomite::debug](debug/index.html) module renders the same compact
+//! form as text, for logging or printing values in examples.
+//!
 //! # Errors
 //!
 //! Some operations which require coercion from AWS to Rust types may fail which results in an
 //! [AttributeError](error/enum.AttributeError.html).
 //!
+//! Conditional writes which fail their condition expression report this through a
+//! `ConditionalCheckFailed` variant of each operation's own error type.
+//! [`is_conditional_check_failed`](fn.is_conditional_check_failed.html) detects these uniformly, and
+//! converting an operation's error into an [Error](error/enum.Error.html) surfaces them as `Error::ConditionFailed`.
+//!
 //! # Cargo Features
 //!
 //! This crate has a few cargo features of note.
@@ -330,13 +969,73 @@
 //! Enabled by default, the `uuid` feature adds support for implementing `Attribute` for
 //! the [uuid](https://crates.io/crates/uuid) crate's type `Uuid`, a useful
 //! type for producing and representing
-//! unique identifiers for items that satisfy [effective characteristics for partition keys](https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/bp-partition-key-design.html)
+//! unique identifiers for items that satisfy [effective characteristics for partition keys](https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/bp-partition-key-design.html).
+//! `Uuid`s are represented by their hyphenated string form. The [`encoding::UuidBin`](encoding/struct.UuidBin.html)
+//! newtype represents them more compactly by their 16 bytes instead.
+//!
+//! ## ulid
+//!
+//! Disabled by default, the `ulid` feature adds an implementation of `Attribute` for
+//! the [ulid](https://crates.io/crates/ulid) crate's `Ulid` type, represented by its canonical
+//! string form. Ulids sort lexicographically by creation time which makes them a good fit for sort keys.
+//!
+//! ## url
+//!
+//! Disabled by default, the `url` feature adds an implementation of `Attribute` for
+//! the [url](https://crates.io/crates/url) crate's `Url` type, represented by its string form.
+//! Strings which aren't valid URLs fail to convert with `AttributeError::InvalidFormat`.
+//!
+//! ## testing
+//!
+//! Disabled by default, the `testing` feature adds a [`testing`](testing/index.html) module with
+//! a `MockDynamoDb` whose operations return scripted results, for unit testing code which depends on a DynamoDB client,
+//! and an `InMemoryDynamoDb` table for integration testing without a DynamoDB server.
+//!
+//! ## tracing
+//!
+//! Disabled by default, the `tracing` feature runs each operation of a [`RetryingDynamoDb`](retry/struct.RetryingDynamoDb.html)
+//! within a [tracing](https://crates.io/crates/tracing) span named after the operation, recording the number of attempts
+//! made and the outcome, in place of the `log` records otherwise emitted when retrying.
+//!
+//! ## indexmap
+//!
+//! Disabled by default, the `indexmap` feature adds an implementation of `Attribute` for
+//! the [indexmap](https://crates.io/crates/indexmap) crate's `IndexMap` type, represented like `HashMap`
+//! by the `M` AttributeValue type. `M` values, like DynamoDB itself, don't retain the order of their entries,
+//! so an `IndexMap` read from an `M` value has its entries in an arbitrary order. Use `IndexMap::sort_keys`
+//! when a deterministic order is required, e.g. for hashing.
+//!
+//! ## smallvec
+//!
+//! Disabled by default, the `smallvec` feature adds an implementation of `Attribute` for
+//! the [smallvec](https://crates.io/crates/smallvec) crate's `SmallVec` type, represented like `Vec`
+//! by the `L` AttributeValue type.
 //!
 //! ## chrono
 //!
 //! Enabled by default, the `chrono` feature adds an implementation of `Attribute` for
 //! the std's [SystemTime](https://doc.rust-lang.org/std/time/struct.SystemTime.html) and chrono [`DateTime`](https://docs.rs/chrono/0.4.11/chrono/struct.DateTime.html) types which
-//! internally use [rfc3339 timestamps](https://www.ietf.org/rfc/rfc3339.txt).
+//! internally use [rfc3339 timestamps](https://www.ietf.org/rfc/rfc3339.txt), as well as chrono's
+//! [`NaiveTime`](https://docs.rs/chrono/0.4.11/chrono/naive/struct.NaiveTime.html) type which uses `HH:MM:SS[.fff]` times of day.
+//!
+//! ## time
+//!
+//! Disabled by default, the `time` feature adds an implementation of `Attribute` for
+//! the [time](https://crates.io/crates/time) crate's `OffsetDateTime` type, which uses [rfc3339 timestamps](https://www.ietf.org/rfc/rfc3339.txt),
+//! and `Date` type, which uses ISO 8601 `YYYY-MM-DD` dates. It may be enabled alongside the `chrono` feature.
+//!
+//! ## jiff
+//!
+//! Disabled by default, the `jiff` feature adds an implementation of `Attribute` for
+//! the [jiff](https://crates.io/crates/jiff) crate's `Timestamp` type, which uses [rfc3339 timestamps](https://www.ietf.org/rfc/rfc3339.txt),
+//! and `Zoned` type, which uses [rfc9557 timestamps](https://www.rfc-editor.org/rfc/rfc9557) retaining the time zone, e.g.
+//! `2024-06-19T15:22:45-04:00[America/New_York]`.
+//!
+//! ## rust_decimal
+//!
+//! Disabled by default, the `rust_decimal` feature adds an implementation of `Attribute` for
+//! the [rust_decimal](https://crates.io/crates/rust_decimal) crate's `Decimal` type. DynamoDB numbers
+//! are arbitrary precision decimals so, unlike `f64`, values such as currency amounts are stored exactly.
 //!
 //! ## derive
 //!
@@ -360,6 +1059,7 @@
 //!
 //! [partition-key]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/HowItWorks.CoreComponents.html#HowItWorks.CoreComponents.PrimaryKey
 //! [sort-key]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/HowItWorks.CoreComponents.html#HowItWorks.CoreComponents.SecondaryIndexes
+//! [gsi]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/GSI.html
 //! [internally-tagged-enum]: https://serde.rs/enum-representations.html#internally-tagged
 //! [`Default::default`]: https://doc.rust-lang.org/stable/std/default/trait.Default.html#tymethod.default
 //! [`AttributeValue`]: https://docs.rs/rusoto_dynamodb/*/rusoto_dynamodb/struct.AttributeValue.html
@@ -380,29 +1080,72 @@ use bytes::Bytes;
 #[cfg(feature = "chrono")]
 use chrono::{
     offset::{FixedOffset, Local},
-    DateTime, Utc,
+    DateTime, NaiveTime, Utc,
 };
+#[cfg(feature = "jiff")]
+use jiff::{Timestamp, Zoned};
 pub use rusoto_dynamodb as dynamodb;
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "time")]
+use time::{
+    format_description::well_known::{Iso8601, Rfc3339},
+    Date, OffsetDateTime, UtcOffset,
+};
 
 // we re-export this because we
 // refer to it with in derive macros
 #[doc(hidden)]
 pub use dynamodb::AttributeValue;
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+#[cfg(feature = "smallvec")]
+use smallvec::{Array, SmallVec};
 use std::{
+    any::{Any, TypeId},
     borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
-    time::SystemTime,
+    convert::TryFrom,
+    hash::Hash,
+    marker::PhantomData,
+    num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU16, NonZeroU32, NonZeroU64},
+    rc::Rc,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
+#[cfg(feature = "ulid")]
+use ulid::Ulid;
+#[cfg(feature = "url")]
+use url::Url;
 #[cfg(feature = "uuid")]
 use uuid::Uuid;
 
+pub mod debug;
+pub mod depth;
+pub mod encoding;
 pub mod error;
+pub mod expression;
 mod ext;
+pub mod flatten;
+pub mod money;
+pub mod query;
 pub mod retry;
+pub mod size;
+pub mod test;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transact;
+
+pub use crate::{
+    encoding::{CompositeBinary, StringId},
+    ext::{DynamoDbExt, ReturnValues},
+    money::Money,
+    retry::Retries,
+    size::to_attrs_checked,
+};
 
-pub use crate::{ext::DynamoDbExt, retry::Retries};
-
-pub use crate::error::AttributeError;
+pub use crate::error::{is_conditional_check_failed, AttributeError, Error};
 /// Type alias for map of named attribute values
 pub type Attributes = HashMap<String, AttributeValue>;
 
@@ -548,6 +1291,76 @@ pub trait Item: IntoAttributes + FromAttributes {
     ///
     /// This is often used in item look ups
     fn key(&self) -> Attributes;
+
+    /// Returns the set of attributes which make up this item's primary key, consuming the item
+    ///
+    /// This avoids cloning large key values when the item is no longer needed. The default
+    /// implementation clones them with `key`, while `#[derive(Item)]` moves them out of the item,
+    /// which isn't possible for types which implement `Drop`
+    fn into_key(self) -> Attributes {
+        self.key()
+    }
+
+    /// Returns the name and value of this item's sort key attribute, or `None` for items
+    /// of tables keyed by their partition key alone
+    ///
+    /// This makes it possible for code generic over `Item` to treat items of tables with
+    /// and without sort keys uniformly. `#[derive(Item)]` implements this for types which declare
+    /// a `#[dynomite(sort_key)]` field
+    fn sort_key(&self) -> Option<(String, AttributeValue)> {
+        None
+    }
+
+    /// Returns the key schema of this item's table along with the definitions of the key attributes
+    /// of the table and its indexes, or `None` when they aren't known
+    ///
+    /// `#[derive(Item)]` implements this with the definitions of the generated `create_table_input`.
+    /// [`DynamoDbExt::verify_schema`](trait.DynamoDbExt.html#tymethod.verify_schema) compares these
+    /// with those of a live table
+    fn key_schema() -> Option<(
+        Vec<dynamodb::KeySchemaElement>,
+        Vec<dynamodb::AttributeDefinition>,
+    )> {
+        None
+    }
+
+    /// Returns a `GetItemInput` for looking up this item by its key in the given table
+    fn get_input(
+        &self,
+        table: impl Into<String>,
+    ) -> dynamodb::GetItemInput {
+        dynamodb::GetItemInput {
+            table_name: table.into(),
+            key: self.key(),
+            ..dynamodb::GetItemInput::default()
+        }
+    }
+
+    /// Returns a `DeleteItemInput` for removing this item by its key from the given table
+    fn delete_input(
+        &self,
+        table: impl Into<String>,
+    ) -> dynamodb::DeleteItemInput {
+        dynamodb::DeleteItemInput {
+            table_name: table.into(),
+            key: self.key(),
+            ..dynamodb::DeleteItemInput::default()
+        }
+    }
+
+    /// Returns a `PutItemInput` for storing this item's attributes in the given table
+    fn put_input(
+        self,
+        table: impl Into<String>,
+    ) -> dynamodb::PutItemInput {
+        let mut item = Attributes::new();
+        self.into_attrs(&mut item);
+        dynamodb::PutItemInput {
+            table_name: table.into(),
+            item,
+            ..dynamodb::PutItemInput::default()
+        }
+    }
 }
 
 /// A type capable of being converted into an or from and AWS `AttributeValue`
@@ -569,7 +1382,20 @@ pub trait Item: IntoAttributes + FromAttributes {
 ///     .s
 /// );
 /// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be stored as a DynamoDB attribute value",
+    label = "`{Self}` doesn't implement `dynomite::Attribute`",
+    note = "fields of `#[derive(Attributes)]` and `#[derive(Item)]` structs must implement `dynomite::Attribute`",
+    note = "implement `Attribute` for it, or use a type which does, such as an `Option`, a `#[derive(Attribute)]` enum or a `#[derive(Attributes)]` struct"
+)]
 pub trait Attribute: Sized {
+    /// The `S`, `N` or `B` AttributeValue type which every value of this type is represented by
+    ///
+    /// These are the types DynamoDB permits for key attributes. `#[derive(Item)]` uses this to define the
+    /// key attributes of its `create_table_input`, and fails to compile for key fields whose type leaves
+    /// it as the default `None`, as for types represented by any other AttributeValue type.
+    const ATTRIBUTE_TYPE: Option<&'static str> = None;
+
     /// Returns a conversion into an `AttributeValue`
     fn into_attr(self) -> AttributeValue;
     /// Returns a fallible conversion from an `AttributeValue`
@@ -601,29 +1427,107 @@ pub trait FromAttributes: Sized {
     ///
     /// [`AttributeValue`]: https://docs.rs/rusoto_dynamodb/*/rusoto_dynamodb/struct.AttributeValue.html
     fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError>;
+
+    /// Returns an instance of a type resolved from a collection of attributes, or `None`
+    /// when none of its attributes are present.
+    ///
+    /// This is what `Option` fields declared `#[dynomite(flatten)]` are resolved with. The default
+    /// implementation always resolves an instance, while derived implementations return `None`
+    /// when none of the struct's own, non-flattened, fields are present.
+    fn from_attrs_if_present(attrs: &mut Attributes) -> Result<Option<Self>, AttributeError> {
+        Self::from_attrs(attrs).map(Some)
+    }
+
+    /// Returns an instance of a type resolved from an owned collection of attributes
+    /// along with the attributes which were not consumed in the process.
+    ///
+    /// This is useful for layered parsing, where the remaining attributes are forwarded
+    /// on to another consumer.
+    fn from_attrs_with_remainder(
+        mut attrs: Attributes
+    ) -> Result<(Self, Attributes), AttributeError> {
+        let value = Self::from_attrs(&mut attrs)?;
+        Ok((value, attrs))
+    }
+
+    /// Returns an instance of a type resolved from a borrowed collection of attributes,
+    /// leaving `attrs` intact.
+    ///
+    /// This is useful for resolving the same attributes as more than one type, such as
+    /// different projections of an item. Unlike `from_attrs`, which moves values out of the map,
+    /// this clones the whole map first, so prefer `from_attrs` when the attributes are no longer needed.
+    fn from_attrs_ref(attrs: &Attributes) -> Result<Self, AttributeError> {
+        Self::from_attrs(&mut attrs.clone())
+    }
 }
 
 /// Coerces a homogeneous HashMap of attribute values into a homogeneous Map of types
 /// that implement `Attribute`
+///
+/// Keys are parsed with `FromStr`, failing with `AttributeError::InvalidFormat`.
+/// `String` keys are moved into the map as is
 #[allow(clippy::implicit_hasher)]
-impl<A: Attribute> FromAttributes for HashMap<String, A> {
+impl<K: FromStr + Eq + Hash + 'static, A: Attribute> FromAttributes for HashMap<K, A> {
     fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError> {
-        attrs
-            .drain()
-            .map(|(k, v)| Ok((k, A::from_attr(v)?)))
-            .collect()
+        attrs.drain().map(|(k, v)| from_entry(k, v)).collect()
     }
 }
 
 /// Coerces a homogenious Map of attribute values into a homogeneous BTreeMap of types
 /// that implement Attribute
-impl<A: Attribute> FromAttributes for BTreeMap<String, A> {
+///
+/// Keys are parsed with `FromStr`, failing with `AttributeError::InvalidFormat`.
+/// `String` keys are moved into the map as is
+impl<K: FromStr + Ord + 'static, A: Attribute> FromAttributes for BTreeMap<K, A> {
+    fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError> {
+        attrs.drain().map(|(k, v)| from_entry(k, v)).collect()
+    }
+}
+
+/// Coerces a homogenious Map of attribute values into a homogeneous IndexMap of types
+/// that implement Attribute
+///
+/// Keys are parsed with `FromStr`, failing with `AttributeError::InvalidFormat`.
+/// `String` keys are moved into the map as is.
+/// `M` values don't retain the order of their entries, so entries are inserted in an arbitrary order
+#[cfg(feature = "indexmap")]
+impl<K: FromStr + Eq + Hash + 'static, A: Attribute> FromAttributes for IndexMap<K, A> {
     fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError> {
-        attrs
-            .drain()
-            .map(|(k, v)| Ok((k, A::from_attr(v)?)))
-            .collect()
+        attrs.drain().map(|(k, v)| from_entry(k, v)).collect()
+    }
+}
+
+/// Resolves a map entry, naming its key in the error should either its key or value fail to convert
+fn from_entry<K: FromStr + 'static, A: Attribute>(
+    key: String,
+    value: AttributeValue,
+) -> Result<(K, A), AttributeError> {
+    // String keys, the common case, are moved rather than copied through FromStr
+    if TypeId::of::<K>() == TypeId::of::<String>() {
+        let value = A::from_attr(value).map_err(|err| err.in_field(&key))?;
+        let mut key = Some(key);
+        return (&mut key as &mut dyn Any)
+            .downcast_mut::<Option<K>>()
+            .and_then(Option::take)
+            .map(|k| (k, value))
+            .ok_or(AttributeError::InvalidFormat);
+    }
+    let entry = key
+        .parse()
+        .map_err(|_| AttributeError::InvalidFormat)
+        .and_then(|k| Ok((k, A::from_attr(value)?)));
+    entry.map_err(|err| err.in_field(&key))
+}
+
+/// Converts a map key into an `M` key, moving rather than copying `String` keys
+fn into_key<K: ToString + 'static>(key: K) -> String {
+    let mut key = Some(key);
+    if let Some(k) = (&mut key as &mut dyn Any).downcast_mut::<Option<String>>() {
+        if let Some(k) = k.take() {
+            return k;
+        }
     }
+    key.map(|k| k.to_string()).unwrap_or_default()
 }
 
 /// A type capable of being serialized into a set of string keys and [`AttributeValue`]s
@@ -645,21 +1549,72 @@ pub trait IntoAttributes: Sized {
     );
 }
 
-impl<A: Attribute> IntoAttributes for HashMap<String, A> {
+/// Keys are converted with `ToString`, `String` keys are moved as is
+#[allow(clippy::implicit_hasher)]
+impl<K: ToString + 'static, A: Attribute> IntoAttributes for HashMap<K, A> {
+    fn into_attrs(
+        self,
+        sink: &mut Attributes,
+    ) {
+        sink.extend(self.into_iter().map(|(k, v)| (into_key(k), v.into_attr())));
+    }
+}
+
+/// Keys are converted with `ToString`, `String` keys are moved as is
+impl<K: ToString + 'static, A: Attribute> IntoAttributes for BTreeMap<K, A> {
+    fn into_attrs(
+        self,
+        sink: &mut Attributes,
+    ) {
+        sink.extend(self.into_iter().map(|(k, v)| (into_key(k), v.into_attr())));
+    }
+}
+
+/// Keys are converted with `ToString`, `String` keys are moved as is
+#[cfg(feature = "indexmap")]
+impl<K: ToString + 'static, A: Attribute> IntoAttributes for IndexMap<K, A> {
     fn into_attrs(
         self,
         sink: &mut Attributes,
     ) {
-        sink.extend(self.into_iter().map(|(k, v)| (k, v.into_attr())));
+        sink.extend(self.into_iter().map(|(k, v)| (into_key(k), v.into_attr())));
     }
 }
 
-impl<A: Attribute> IntoAttributes for BTreeMap<String, A> {
+/// A `Box<T>` of a map-like type, represented in the same way as `T`
+///
+/// Note that coherence rules prevent `Attribute` from being implemented for
+/// `Box<T>` of any `T: Attribute`, as `Box` is a fundamental type.
+/// `Attribute` is instead implemented for boxed map-like types by the blanket
+/// implementation for `IntoAttributes + FromAttributes` types, and for `Box<T>`
+/// of each of the other `Attribute` types this crate provides
+impl<T: IntoAttributes> IntoAttributes for Box<T> {
     fn into_attrs(
         self,
         sink: &mut Attributes,
     ) {
-        sink.extend(self.into_iter().map(|(k, v)| (k, v.into_attr())));
+        (*self).into_attrs(sink)
+    }
+}
+
+impl<T: FromAttributes> FromAttributes for Box<T> {
+    fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError> {
+        T::from_attrs(attrs).map(Box::new)
+    }
+}
+
+/// Unit is represented by an empty map, and resolved from any map without consuming it
+impl IntoAttributes for () {
+    fn into_attrs(
+        self,
+        _: &mut Attributes,
+    ) {
+    }
+}
+
+impl FromAttributes for () {
+    fn from_attrs(_: &mut Attributes) -> Result<Self, AttributeError> {
+        Ok(())
     }
 }
 
@@ -674,13 +1629,17 @@ impl<T: IntoAttributes + FromAttributes> Attribute for T {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        T::from_attrs(&mut value.m.ok_or(AttributeError::InvalidType)?)
+        let err = AttributeError::wrong_type("M", &value);
+        let mut attrs = value.m.ok_or(err)?;
+        depth::nested(|| T::from_attrs(&mut attrs))
     }
 }
 
 /// A `String` type for `Uuids`, represented by the `S` AttributeValue type
 #[cfg(feature = "uuid")]
 impl Attribute for Uuid {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             s: Some(self.to_hyphenated().to_string()),
@@ -688,16 +1647,81 @@ impl Attribute for Uuid {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
         value
             .s
-            .ok_or(AttributeError::InvalidType)
+            .ok_or(err)
             .and_then(|s| Uuid::parse_str(s.as_str()).map_err(|_| AttributeError::InvalidFormat))
     }
 }
 
+/// A `String` type for `Ulids`, represented by the `S` AttributeValue type
+///
+/// The canonical 26 character string sorts lexicographically by creation time
+#[cfg(feature = "ulid")]
+impl Attribute for Ulid {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            s: Some(self.to_string()),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value
+            .s
+            .ok_or(err)
+            .and_then(|s| Ulid::from_string(s.as_str()).map_err(|_| AttributeError::InvalidFormat))
+    }
+}
+
+/// A `Url`, represented by the `S` AttributeValue type
+#[cfg(feature = "url")]
+impl Attribute for Url {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            s: Some(self.into()),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value
+            .s
+            .ok_or(err)
+            .and_then(|s| Url::parse(&s).map_err(|_| AttributeError::InvalidFormat))
+    }
+}
+
+/// An exact `Decimal` type, represented by the `N` AttributeValue type
+#[cfg(feature = "rust_decimal")]
+impl Attribute for Decimal {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("N");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            n: Some(self.to_string()),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("N", &value);
+        value
+            .n
+            .ok_or(err)
+            .and_then(|n| n.parse().map_err(|_| AttributeError::InvalidFormat))
+    }
+}
+
 /// An `rfc3339` formatted version of `DateTime<Utc>`, represented by the `S` AttributeValue type
 #[cfg(feature = "chrono")]
 impl Attribute for DateTime<Utc> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             s: Some(self.to_rfc3339()),
@@ -705,21 +1729,142 @@ impl Attribute for DateTime<Utc> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err).and_then(|s| {
+            match DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc)) {
+                Ok(date_time) => Ok(date_time),
+                Err(_) => Err(AttributeError::InvalidFormat),
+            }
+        })
+    }
+}
+
+/// An `rfc3339` formatted version of a time `OffsetDateTime`, represented by the `S` AttributeValue type
+///
+/// RFC 3339 can't represent offsets with seconds, which are stored converted to UTC instead, nor
+/// years before 0, which are stored as `[year sign:mandatory]-[month]-[day]T[hour]:[minute]:[second].[subsecond]`
+/// followed by an offset with hours, minutes and seconds
+#[cfg(feature = "time")]
+impl Attribute for OffsetDateTime {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        let formatted = self
+            .format(&Rfc3339)
+            .or_else(|_| {
+                self.checked_to_offset(UtcOffset::UTC)
+                    .ok_or(time::error::Format::InvalidComponent("offset"))
+                    .and_then(|utc| utc.format(&Rfc3339))
+            })
+            .or_else(|_| self.format(&extended_time_format(EXTENDED_OFFSET_DATE_TIME)))
+            .expect("offset date times to be formattable");
+        AttributeValue {
+            s: Some(formatted),
+            ..Default::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err).and_then(|s| {
+            OffsetDateTime::parse(&s, &Rfc3339)
+                .or_else(|_| {
+                    OffsetDateTime::parse(&s, &extended_time_format(EXTENDED_OFFSET_DATE_TIME))
+                })
+                .map_err(|_| AttributeError::InvalidFormat)
+        })
+    }
+}
+
+/// An ISO 8601 `YYYY-MM-DD` formatted version of a time `Date`, represented by the `S` AttributeValue type
+///
+/// Years before 0, which ISO 8601 only represents with an agreed number of extra digits, are stored
+/// as `[year sign:mandatory]-[month]-[day]`, e.g. `-0001-01-01`
+#[cfg(feature = "time")]
+impl Attribute for Date {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        let formatted = self
+            .format(&Iso8601::DATE)
+            .or_else(|_| self.format(&extended_time_format(EXTENDED_DATE)))
+            .expect("dates to be formattable");
+        AttributeValue {
+            s: Some(formatted),
+            ..Default::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err).and_then(|s| {
+            Date::parse(&s, &Iso8601::DATE)
+                .or_else(|_| Date::parse(&s, &extended_time_format(EXTENDED_DATE)))
+                .map_err(|_| AttributeError::InvalidFormat)
+        })
+    }
+}
+
+/// The format of `OffsetDateTime`s which RFC 3339 can't represent
+#[cfg(feature = "time")]
+const EXTENDED_OFFSET_DATE_TIME: &str = "[year sign:mandatory]-[month]-[day]T[hour]:[minute]:[second].[subsecond][offset_hour sign:mandatory]:[offset_minute]:[offset_second]";
+
+/// The format of `Date`s which ISO 8601 can't represent
+#[cfg(feature = "time")]
+const EXTENDED_DATE: &str = "[year sign:mandatory]-[month]-[day]";
+
+#[cfg(feature = "time")]
+fn extended_time_format(
+    description: &'static str
+) -> Vec<time::format_description::BorrowedFormatItem<'static>> {
+    time::format_description::parse(description).expect("valid format description")
+}
+
+/// An `rfc3339` formatted version of a jiff `Timestamp`, represented by the `S` AttributeValue type
+#[cfg(feature = "jiff")]
+impl Attribute for Timestamp {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            s: Some(self.to_string()),
+            ..Default::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
         value
             .s
-            .ok_or(AttributeError::InvalidType)
-            .and_then(
-                |s| match DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc)) {
-                    Ok(date_time) => Ok(date_time),
-                    Err(_) => Err(AttributeError::InvalidFormat),
-                },
-            )
+            .ok_or(err)
+            .and_then(|s| s.parse().map_err(|_| AttributeError::InvalidFormat))
+    }
+}
+
+/// An `rfc9557` formatted version of a jiff `Zoned`, represented by the `S` AttributeValue type
+///
+/// Unlike `rfc3339`, the format retains the time zone, e.g. `2024-06-19T15:22:45-04:00[America/New_York]`
+#[cfg(feature = "jiff")]
+impl Attribute for Zoned {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            s: Some(self.to_string()),
+            ..Default::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value
+            .s
+            .ok_or(err)
+            .and_then(|s| s.parse().map_err(|_| AttributeError::InvalidFormat))
     }
 }
 
 /// An `rfc3339` formatted version of `DateTime<Local>`, represented by the `S` AttributeValue type
 #[cfg(feature = "chrono")]
 impl Attribute for DateTime<Local> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             s: Some(self.to_rfc3339()),
@@ -727,21 +1872,21 @@ impl Attribute for DateTime<Local> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value
-            .s
-            .ok_or(AttributeError::InvalidType)
-            .and_then(|s| {
-                match DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Local)) {
-                    Ok(date_time) => Ok(date_time),
-                    Err(_) => Err(AttributeError::InvalidFormat),
-                }
-            })
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err).and_then(|s| {
+            match DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Local)) {
+                Ok(date_time) => Ok(date_time),
+                Err(_) => Err(AttributeError::InvalidFormat),
+            }
+        })
     }
 }
 
 /// An `rfc3339` formatted version of `DateTime<FixedOffset>`, represented by the `S` AttributeValue type
 #[cfg(feature = "chrono")]
 impl Attribute for DateTime<FixedOffset> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             s: Some(self.to_rfc3339()),
@@ -749,9 +1894,10 @@ impl Attribute for DateTime<FixedOffset> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
         value
             .s
-            .ok_or(AttributeError::InvalidType)
+            .ok_or(err)
             .and_then(|s| match DateTime::parse_from_rfc3339(&s) {
                 Ok(date_time) => Ok(date_time),
                 Err(_) => Err(AttributeError::InvalidFormat),
@@ -762,14 +1908,17 @@ impl Attribute for DateTime<FixedOffset> {
 /// An `rfc3339` formatted version of `SystemTime`, represented by the `S` AttributeValue type
 #[cfg(feature = "chrono")]
 impl Attribute for SystemTime {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         let dt: DateTime<Utc> = self.into();
         dt.into_attr()
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
         value
             .s
-            .ok_or(AttributeError::InvalidType)
+            .ok_or(err)
             .and_then(|s| match DateTime::parse_from_rfc3339(&s) {
                 Ok(date_time) => Ok(date_time.into()),
                 Err(_) => Err(AttributeError::InvalidFormat),
@@ -777,8 +1926,103 @@ impl Attribute for SystemTime {
     }
 }
 
+/// A `HH:MM:SS[.fff]` formatted version of `NaiveTime`, represented by the `S` AttributeValue type
+///
+/// The fractional seconds are only included when non-zero
+#[cfg(feature = "chrono")]
+impl Attribute for NaiveTime {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            s: Some(self.format("%H:%M:%S%.f").to_string()),
+            ..Default::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err).and_then(|s| {
+            NaiveTime::parse_from_str(&s, "%H:%M:%S%.f").map_err(|_| AttributeError::InvalidFormat)
+        })
+    }
+}
+
+/// Formats a `Duration` as a number of seconds, with a fractional part only when needed
+fn duration_to_number(duration: Duration) -> String {
+    match duration.subsec_nanos() {
+        0 => duration.as_secs().to_string(),
+        nanos => format!("{}.{:09}", duration.as_secs(), nanos)
+            .trim_end_matches('0')
+            .to_string(),
+    }
+}
+
+/// Parses a non-negative number of seconds, with up to nanosecond precision, as a `Duration`
+fn number_to_duration(number: &str) -> Result<Duration, AttributeError> {
+    let (secs, fraction) = match number.find('.') {
+        Some(index) => (&number[..index], &number[index + 1..]),
+        None => (number, ""),
+    };
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AttributeError::InvalidFormat);
+    }
+    let secs = secs.parse().map_err(|_| AttributeError::InvalidFormat)?;
+    let nanos = format!("{:0<9}", fraction)
+        .parse()
+        .map_err(|_| AttributeError::InvalidFormat)?;
+    Ok(Duration::new(secs, nanos))
+}
+
+/// A `Duration` type, represented by the N AttributeValue type as a number of seconds
+/// with up to nanosecond precision
+impl Attribute for Duration {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("N");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            n: Some(duration_to_number(self)),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("N", &value);
+        value.n.ok_or(err).and_then(|n| number_to_duration(&n))
+    }
+}
+
+macro_rules! duration_set_attr {
+    ($collection:ty) => {
+        /// A Number set type, represented by the NS AttributeValue type as numbers of seconds
+        impl Attribute for $collection {
+            fn into_attr(self) -> AttributeValue {
+                AttributeValue {
+                    ns: Some(self.into_iter().map(duration_to_number).collect()),
+                    ..AttributeValue::default()
+                }
+            }
+            fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+                let err = AttributeError::wrong_type("NS", &value);
+                value
+                    .ns
+                    .ok_or(err)?
+                    .iter()
+                    .map(|n| number_to_duration(n))
+                    .collect()
+            }
+        }
+    };
+}
+
+duration_set_attr!(HashSet<Duration>);
+duration_set_attr!(BTreeSet<Duration>);
+
 /// A `String` type, represented by the S AttributeValue type
+///
+/// Empty strings are represented as an empty `S` value rather than `NULL`,
+/// as DynamoDB permits them for non-key attributes
 impl Attribute for String {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             s: Some(self),
@@ -786,11 +2030,14 @@ impl Attribute for String {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value.s.ok_or(AttributeError::InvalidType)
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.ok_or(err)
     }
 }
 
 impl<'a> Attribute for Cow<'a, str> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("S");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             s: Some(match self {
@@ -801,7 +2048,29 @@ impl<'a> Attribute for Cow<'a, str> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value.s.map(Cow::Owned).ok_or(AttributeError::InvalidType)
+        let err = AttributeError::wrong_type("S", &value);
+        value.s.map(Cow::Owned).ok_or(err)
+    }
+}
+
+/// A Binary type, represented by the B AttributeValue type
+///
+/// Owned bytes are moved into the attribute value, borrowed bytes are copied
+impl<'a> Attribute for Cow<'a, [u8]> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("B");
+
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            b: Some(match self {
+                Cow::Owned(o) => o.into(),
+                Cow::Borrowed(b) => bytes::Bytes::copy_from_slice(b),
+            }),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("B", &value);
+        value.b.ok_or(err).map(|bs| Cow::Owned(bs.to_vec()))
     }
 }
 
@@ -815,9 +2084,10 @@ impl Attribute for HashSet<String> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("SS", &value);
         value
             .ss
-            .ok_or(AttributeError::InvalidType)
+            .ok_or(err)
             .map(|mut value| value.drain(..).collect())
     }
 }
@@ -830,45 +2100,75 @@ impl Attribute for BTreeSet<String> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("SS", &value);
         value
             .ss
-            .ok_or(AttributeError::InvalidType)
+            .ok_or(err)
             .map(|mut value| value.drain(..).collect())
     }
 }
 
-/// A Binary Set type, represented by the BS AttributeValue type
-#[allow(clippy::implicit_hasher)]
-impl Attribute for HashSet<Vec<u8>> {
-    fn into_attr(mut self) -> AttributeValue {
+macro_rules! binary_set_attr {
+    ($collection:ty => $from_bytes:expr) => {
+        /// A Binary Set type, represented by the BS AttributeValue type
+        #[allow(clippy::implicit_hasher)]
+        impl Attribute for $collection {
+            fn into_attr(self) -> AttributeValue {
+                AttributeValue {
+                    bs: Some(self.into_iter().map(Bytes::from).collect()),
+                    ..AttributeValue::default()
+                }
+            }
+            fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+                let err = AttributeError::wrong_type("BS", &value);
+                value
+                    .bs
+                    .ok_or(err)
+                    .map(|value| value.into_iter().map($from_bytes).collect())
+            }
+        }
+    };
+}
+
+binary_set_attr!(HashSet<Vec<u8>> => |bs: Bytes| bs.as_ref().to_vec());
+binary_set_attr!(BTreeSet<Vec<u8>> => |bs: Bytes| bs.as_ref().to_vec());
+binary_set_attr!(HashSet<Bytes> => Bytes::from);
+binary_set_attr!(BTreeSet<Bytes> => Bytes::from);
+
+// a Boolean type, represented by the BOOL AttributeValue type
+impl Attribute for bool {
+    fn into_attr(self) -> AttributeValue {
         AttributeValue {
-            bs: Some(self.drain().map(Bytes::from).collect()),
+            bool: Some(self),
             ..AttributeValue::default()
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value
-            .bs
-            .ok_or(AttributeError::InvalidType)
-            .map(|mut value| value.drain(..).map(|bs| bs.as_ref().to_vec()).collect())
+        let err = AttributeError::wrong_type("BOOL", &value);
+        value.bool.ok_or(err)
     }
 }
 
-// a Boolean type, represented by the BOOL AttributeValue type
-impl Attribute for bool {
+/// A zero sized marker type, represented by the NULL AttributeValue type
+///
+/// Any value is read as `PhantomData`. Fields of derived types with this type are
+/// neither written nor read
+impl<T> Attribute for PhantomData<T> {
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
-            bool: Some(self),
+            null: Some(true),
             ..AttributeValue::default()
         }
     }
-    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value.bool.ok_or(AttributeError::InvalidType)
+    fn from_attr(_: AttributeValue) -> Result<Self, AttributeError> {
+        Ok(PhantomData)
     }
 }
 
 // a Binary type, represented by the B AttributeValue type
 impl Attribute for bytes::Bytes {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("B");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             b: Some(self),
@@ -876,12 +2176,15 @@ impl Attribute for bytes::Bytes {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value.b.ok_or(AttributeError::InvalidType)
+        let err = AttributeError::wrong_type("B", &value);
+        value.b.ok_or(err)
     }
 }
 
 // a Binary type, represented by the B AttributeValue type
 impl Attribute for Vec<u8> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = Some("B");
+
     fn into_attr(self) -> AttributeValue {
         AttributeValue {
             b: Some(self.into()),
@@ -889,10 +2192,8 @@ impl Attribute for Vec<u8> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value
-            .b
-            .ok_or(AttributeError::InvalidType)
-            .map(|bs| bs.as_ref().to_vec())
+        let err = AttributeError::wrong_type("B", &value);
+        value.b.ok_or(err).map(|bs| bs.as_ref().to_vec())
     }
 }
 
@@ -904,6 +2205,9 @@ impl Attribute for Vec<u8> {
 /// to implement a wrapper type that represents your desired variants
 /// and implement `Attribute` for `YourType`. An `Vec<YourType>` implementation
 /// will already be provided
+///
+/// Vectors of types deriving `Attributes` or `Item` are represented as an L of M values,
+/// one map per element. Empty vectors are represented as an empty L
 impl<A: Attribute> Attribute for Vec<A> {
     fn into_attr(mut self) -> AttributeValue {
         AttributeValue {
@@ -912,16 +2216,70 @@ impl<A: Attribute> Attribute for Vec<A> {
         }
     }
     fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-        value
-            .l
-            .ok_or(AttributeError::InvalidType)?
-            .into_iter()
-            .map(Attribute::from_attr)
-            .collect()
+        let err = AttributeError::wrong_type("L", &value);
+        let values = value.l.ok_or(err)?;
+        depth::nested(|| values.into_iter().map(Attribute::from_attr).collect())
+    }
+}
+
+/// A `SmallVec` type, represented by the L AttributeValue type
+#[cfg(feature = "smallvec")]
+impl<A> Attribute for SmallVec<A>
+where
+    A: Array,
+    A::Item: Attribute,
+{
+    fn into_attr(self) -> AttributeValue {
+        AttributeValue {
+            l: Some(self.into_iter().map(Attribute::into_attr).collect()),
+            ..AttributeValue::default()
+        }
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        let err = AttributeError::wrong_type("L", &value);
+        let values = value.l.ok_or(err)?;
+        depth::nested(|| values.into_iter().map(Attribute::from_attr).collect())
     }
 }
 
+macro_rules! tuple_attr {
+    ($len:expr => $($type:ident $value:ident),+) => {
+        /// A positional tuple type, represented by the L AttributeValue type
+        ///
+        /// A list whose length differs from the tuple's arity is an `InvalidFormat`
+        impl<$($type: Attribute),+> Attribute for ($($type,)+) {
+            fn into_attr(self) -> AttributeValue {
+                let ($($value,)+) = self;
+                AttributeValue {
+                    l: Some(vec![$($value.into_attr()),+]),
+                    ..AttributeValue::default()
+                }
+            }
+            fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+                let err = AttributeError::wrong_type("L", &value);
+                let [$($value),+] = <[AttributeValue; $len]>::try_from(value.l.ok_or(err)?)
+                    .map_err(|_| AttributeError::InvalidFormat)?;
+                Ok(($($type::from_attr($value)?,)+))
+            }
+        }
+    };
+}
+
+// implement Attribute for tuples
+tuple_attr!(1 => A a);
+tuple_attr!(2 => A a, B b);
+tuple_attr!(3 => A a, B b, C c);
+tuple_attr!(4 => A a, B b, C c, D d);
+tuple_attr!(5 => A a, B b, C c, D d, E e);
+tuple_attr!(6 => A a, B b, C c, D d, E e, F f);
+
+/// An optional value, represented as `T` when `Some` and by the NULL AttributeValue type when `None`
+///
+/// Nested options collapse: `Some(None)` is represented by the same `NULL` as `None`, and so reads back as `None`.
+/// `#[derive(Attributes)]` and `#[derive(Item)]` reject fields of type `Option<Option<T>>` for this reason
 impl<T: Attribute> Attribute for Option<T> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = T::ATTRIBUTE_TYPE;
+
     fn into_attr(self) -> AttributeValue {
         match self {
             Some(value) => value.into_attr(),
@@ -939,9 +2297,43 @@ impl<T: Attribute> Attribute for Option<T> {
     }
 }
 
+/// An `Arc<T>`, represented in the same way as `T`
+///
+/// The inner value is cloned when converting an `Arc` which is not uniquely owned
+impl<T: Attribute + Clone> Attribute for Arc<T> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = T::ATTRIBUTE_TYPE;
+
+    fn into_attr(self) -> AttributeValue {
+        Arc::try_unwrap(self)
+            .unwrap_or_else(|shared| (*shared).clone())
+            .into_attr()
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        T::from_attr(value).map(Arc::new)
+    }
+}
+
+/// An `Rc<T>`, represented in the same way as `T`
+///
+/// The inner value is cloned when converting an `Rc` which is not uniquely owned
+impl<T: Attribute + Clone> Attribute for Rc<T> {
+    const ATTRIBUTE_TYPE: Option<&'static str> = T::ATTRIBUTE_TYPE;
+
+    fn into_attr(self) -> AttributeValue {
+        Rc::try_unwrap(self)
+            .unwrap_or_else(|shared| (*shared).clone())
+            .into_attr()
+    }
+    fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+        T::from_attr(value).map(Rc::new)
+    }
+}
+
 macro_rules! numeric_attr {
     ($type:ty) => {
         impl Attribute for $type {
+            const ATTRIBUTE_TYPE: Option<&'static str> = Some("N");
+
             fn into_attr(self) -> AttributeValue {
                 AttributeValue {
                     n: Some(self.to_string()),
@@ -949,12 +2341,15 @@ macro_rules! numeric_attr {
                 }
             }
             fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+                let err = AttributeError::wrong_type("N", &value);
                 value
                     .n
-                    .ok_or(AttributeError::InvalidType)
+                    .ok_or(err)
                     .and_then(|num| num.parse().map_err(|_| AttributeError::InvalidFormat))
             }
         }
+
+        impl encoding::Scalar for $type {}
     };
 }
 
@@ -969,7 +2364,8 @@ macro_rules! numeric_set_attr {
                 }
             }
             fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
-                let mut nums = value.ns.ok_or(AttributeError::InvalidType)?;
+                let err = AttributeError::wrong_type("NS", &value);
+                let mut nums = value.ns.ok_or(err)?;
                 let mut results: Vec<Result<$type, AttributeError>> = nums
                     .drain(..)
                     .map(|ns| ns.parse().map_err(|_| AttributeError::InvalidFormat))
@@ -990,6 +2386,14 @@ numeric_attr!(i64);
 numeric_attr!(f32);
 numeric_attr!(f64);
 
+// non-zero integers parse as their integer type, rejecting a stored `0` as an `InvalidFormat`
+numeric_attr!(NonZeroU16);
+numeric_attr!(NonZeroI16);
+numeric_attr!(NonZeroU32);
+numeric_attr!(NonZeroI32);
+numeric_attr!(NonZeroU64);
+numeric_attr!(NonZeroI64);
+
 // implement Attribute for numeric collections
 numeric_set_attr!(u16 => HashSet<u16>);
 numeric_set_attr!(u16 => BTreeSet<u16>);
@@ -1013,6 +2417,106 @@ numeric_set_attr!(u64 => BTreeSet<u64>);
 //numeric_set_attr!(f64 => HashSet<f64>);
 //numeric_set_attr!(f64 => BTreeSet<f64>);
 
+macro_rules! boxed_attr {
+    ($(#[$meta:meta])* impl<$($param:ident: $bound:path),*> $type:ty) => {
+        /// A `Box<T>`, represented in the same way as `T`
+        $(#[$meta])*
+        impl<$($param: $bound),*> Attribute for Box<$type> {
+            const ATTRIBUTE_TYPE: Option<&'static str> = <$type>::ATTRIBUTE_TYPE;
+
+            fn into_attr(self) -> AttributeValue {
+                (*self).into_attr()
+            }
+            fn from_attr(value: AttributeValue) -> Result<Self, AttributeError> {
+                <$type>::from_attr(value).map(Box::new)
+            }
+        }
+    };
+    ($(#[$meta:meta])* $type:ty) => {
+        boxed_attr!($(#[$meta])* impl<> $type);
+    };
+}
+
+// implement Scalar for the other types represented by S or N
+impl encoding::Scalar for String {}
+impl<'a> encoding::Scalar for Cow<'a, str> {}
+impl encoding::Scalar for Duration {}
+#[cfg(feature = "uuid")]
+impl encoding::Scalar for Uuid {}
+#[cfg(feature = "ulid")]
+impl encoding::Scalar for Ulid {}
+#[cfg(feature = "url")]
+impl encoding::Scalar for Url {}
+#[cfg(feature = "rust_decimal")]
+impl encoding::Scalar for Decimal {}
+#[cfg(feature = "chrono")]
+impl encoding::Scalar for DateTime<Utc> {}
+#[cfg(feature = "chrono")]
+impl encoding::Scalar for DateTime<Local> {}
+#[cfg(feature = "chrono")]
+impl encoding::Scalar for DateTime<FixedOffset> {}
+#[cfg(feature = "chrono")]
+impl encoding::Scalar for SystemTime {}
+#[cfg(feature = "chrono")]
+impl encoding::Scalar for NaiveTime {}
+#[cfg(feature = "time")]
+impl encoding::Scalar for OffsetDateTime {}
+#[cfg(feature = "time")]
+impl encoding::Scalar for Date {}
+#[cfg(feature = "jiff")]
+impl encoding::Scalar for Timestamp {}
+#[cfg(feature = "jiff")]
+impl encoding::Scalar for Zoned {}
+
+// implement Attribute for boxed values, which can't be done for all `T: Attribute`
+// without overlapping the blanket implementation for map-like types
+boxed_attr!(String);
+boxed_attr!(bool);
+boxed_attr!(bytes::Bytes);
+boxed_attr!(Vec<u8>);
+boxed_attr!(Duration);
+boxed_attr!(u16);
+boxed_attr!(i16);
+boxed_attr!(u32);
+boxed_attr!(i32);
+boxed_attr!(u64);
+boxed_attr!(i64);
+boxed_attr!(f32);
+boxed_attr!(f64);
+boxed_attr!(NonZeroU16);
+boxed_attr!(NonZeroI16);
+boxed_attr!(NonZeroU32);
+boxed_attr!(NonZeroI32);
+boxed_attr!(NonZeroU64);
+boxed_attr!(NonZeroI64);
+boxed_attr!(impl<A: Attribute> Vec<A>);
+boxed_attr!(impl<T: Attribute> Option<T>);
+boxed_attr!(
+    #[cfg(feature = "uuid")]
+    Uuid
+);
+boxed_attr!(
+    #[cfg(feature = "ulid")]
+    Ulid
+);
+boxed_attr!(
+    #[cfg(feature = "url")]
+    Url
+);
+boxed_attr!(
+    #[cfg(feature = "rust_decimal")]
+    Decimal
+);
+boxed_attr!(#[cfg(feature = "chrono")] DateTime<Utc>);
+boxed_attr!(
+    #[cfg(feature = "time")]
+    OffsetDateTime
+);
+boxed_attr!(
+    #[cfg(feature = "jiff")]
+    Timestamp
+);
+
 #[macro_export]
 /// Creates a `HashMap<String, AttributeValue>` from a list of key-value pairs
 ///
@@ -1022,8 +2526,8 @@ numeric_set_attr!(u64 => BTreeSet<u64>);
 ///
 /// This syntax for this macro is the same as [maplit](https://crates.io/crates/maplit).
 ///
-/// A avoid using `&str` slices for values when creating a mapping for a `String` `AttributeValue`.
-/// Instead use a `String`.
+/// Values may be any type which implements `Attribute`, as well as `&str` slices, which are
+/// represented by the `S` AttributeValue type like `String`s.
 ///
 /// ## Example
 ///
@@ -1038,11 +2542,12 @@ numeric_set_attr!(u64 => BTreeSet<u64>);
 ///   ),
 ///   expression_attribute_values: Some(
 ///     attr_map! {
-///        ":partitionkeyval" => "rust".to_string()
+///        ":partitionkeyval" => "rust"
 ///      }
 ///    ),
 ///    ..QueryInput::default()
 /// };
+/// ```
 macro_rules! attr_map {
     (@single $($x:tt)*) => (());
     (@count $($rest:expr),*) => (<[()]>::len(&[$($crate::attr_map!(@single $rest)),*]));
@@ -1050,19 +2555,168 @@ macro_rules! attr_map {
     ($($key:expr => $value:expr),*) => {
         {
             let _cap = $crate::attr_map!(@count $($key),*);
-            let mut _map: ::std::collections::HashMap<String, ::dynomite::dynamodb::AttributeValue> =
+            let mut _map: ::std::collections::HashMap<String, $crate::dynamodb::AttributeValue> =
               ::std::collections::HashMap::with_capacity(_cap);
-              {
-                  use ::dynomite::Attribute;
             $(
-                let _ = _map.insert($key.into(), $value.into_attr());
+                let _ = _map.insert($key.into(), $crate::AttrMapValue::into_attr_value($value));
             )*
-              }
             _map
         }
     };
 }
 
+#[macro_export]
+/// Implements `Attribute` for types which implement `Display` and `FromStr`,
+/// represented by the `S` AttributeValue type
+///
+/// Values are written with `to_string` and read with `parse`, failing with
+/// `AttributeError::InvalidFormat` when they can't be parsed. This saves writing
+/// the same impl for each string-like domain type.
+///
+/// ## Example
+///
+/// ```
+/// use dynomite::{impl_attribute_via_string, Attribute};
+/// use std::{fmt, str::FromStr};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Sku(String);
+///
+/// impl fmt::Display for Sku {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "SKU-{}", self.0)
+///     }
+/// }
+///
+/// impl FromStr for Sku {
+///     type Err = ();
+///     fn from_str(s: &str) -> Result<Self, ()> {
+///         s.strip_prefix("SKU-").map(|sku| Sku(sku.into())).ok_or(())
+///     }
+/// }
+///
+/// impl_attribute_via_string!(Sku);
+///
+/// let value = Sku("123".into()).into_attr();
+/// assert_eq!(value.s.as_deref(), Some("SKU-123"));
+/// assert_eq!(Ok(Sku("123".into())), Sku::from_attr(value));
+/// ```
+macro_rules! impl_attribute_via_string {
+    ($($type:ty),+ $(,)?) => {
+        $(
+            impl $crate::Attribute for $type {
+                const ATTRIBUTE_TYPE: ::std::option::Option<&'static str> =
+                    ::std::option::Option::Some("S");
+
+                fn into_attr(self) -> $crate::dynamodb::AttributeValue {
+                    $crate::dynamodb::AttributeValue {
+                        s: ::std::option::Option::Some(::std::string::ToString::to_string(&self)),
+                        ..::std::default::Default::default()
+                    }
+                }
+                fn from_attr(
+                    value: $crate::dynamodb::AttributeValue
+                ) -> ::std::result::Result<Self, $crate::AttributeError> {
+                    let err = $crate::AttributeError::wrong_type("S", &value);
+                    value.s.ok_or(err).and_then(|s| {
+                        ::std::str::FromStr::from_str(&s)
+                            .map_err(|_| $crate::AttributeError::InvalidFormat)
+                    })
+                }
+            }
+
+            impl $crate::encoding::Scalar for $type {}
+        )+
+    };
+}
+
+/// A value accepted by [`attr_map!`](macro.attr_map.html), any `Attribute` as well as `&str`
+///
+/// `&str` can't implement `Attribute` because values can't be borrowed out of an owned `AttributeValue`
+#[doc(hidden)]
+pub trait AttrMapValue {
+    fn into_attr_value(self) -> AttributeValue;
+}
+
+impl<A: Attribute> AttrMapValue for A {
+    fn into_attr_value(self) -> AttributeValue {
+        self.into_attr()
+    }
+}
+
+impl AttrMapValue for &str {
+    fn into_attr_value(self) -> AttributeValue {
+        self.to_string().into_attr()
+    }
+}
+
+/// Collects `Attributes` from key-value pairs, converting each value with `into_attr`
+///
+/// This is the runtime counterpart of [`attr_map!`](macro.attr_map.html), useful for
+/// building `Attributes` from dynamic data, such as parsed CSV rows.
+///
+/// ## Example
+///
+/// ```
+/// use dynomite::{collect_attrs, Attribute};
+///
+/// let attrs = collect_attrs(vec![("a", 1), ("b", 2)]);
+/// assert_eq!(attrs["b"], 2.into_attr());
+/// ```
+pub fn collect_attrs<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Attributes
+where
+    K: Into<String>,
+    V: Attribute,
+{
+    pairs
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into_attr()))
+        .collect()
+}
+
+/// Overlays the attributes of `patch` onto `base`, replacing any attributes of the same name
+///
+/// ## Example
+///
+/// ```
+/// use dynomite::{attr_map, merge};
+///
+/// let mut base = attr_map! { "title" => "draft", "pages" => 1 };
+/// merge(&mut base, attr_map! { "title" => "final" });
+/// assert_eq!(base, attr_map! { "title" => "final", "pages" => 1 });
+/// ```
+pub fn merge(
+    base: &mut Attributes,
+    patch: Attributes,
+) {
+    base.extend(patch);
+}
+
+/// Returns the attributes of `new` which were added or changed since `old`, e.g. to write only the
+/// attributes of an item which changed
+///
+/// Values are compared as they are represented, so numbers such as `1` and `1.0` are considered changed.
+/// Attributes of `old` which were removed from `new` are not included
+///
+/// ## Example
+///
+/// ```
+/// use dynomite::{attr_map, diff};
+///
+/// let old = attr_map! { "title" => "draft", "pages" => 1 };
+/// let new = attr_map! { "title" => "final", "pages" => 1, "isbn" => "123" };
+/// assert_eq!(diff(&old, &new), attr_map! { "title" => "final", "isbn" => "123" });
+/// ```
+pub fn diff(
+    old: &Attributes,
+    new: &Attributes,
+) -> Attributes {
+    new.iter()
+        .filter(|(name, value)| old.get(*name) != Some(*value))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
 // Re-export #[derive(Item)]
 // work around for 2018 edition issue with needing to
 // import but the use dynomite::Item and dynomite_derive::Item
@@ -1075,23 +2729,236 @@ extern crate dynomite_derive;
 #[doc(hidden)]
 pub use dynomite_derive::*;
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use maplit::{btreemap, btreeset, hashmap};
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::{btreemap, btreeset, hashmap, hashset};
+
+    #[test]
+    fn uuid_attr() {
+        let value = Uuid::new_v4();
+        assert_eq!(Ok(value), Uuid::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    fn uuid_invalid_attr() {
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "BOOL"
+            }),
+            Uuid::from_attr(AttributeValue {
+                bool: Some(true),
+                ..AttributeValue::default()
+            })
+        );
+    }
+
+    #[test]
+    fn tuple_pair_attr() {
+        let value = (42_u32, "answer".to_string());
+        assert_eq!(
+            value.clone().into_attr().l,
+            Some(vec![42_u32.into_attr(), "answer".to_string().into_attr()])
+        );
+        assert_eq!(
+            Ok(value.clone()),
+            <(u32, String)>::from_attr(value.into_attr())
+        );
+    }
+
+    #[test]
+    fn tuple_triple_attr() {
+        let value = (true, false, true);
+        assert_eq!(
+            Ok(value),
+            <(bool, bool, bool)>::from_attr(value.into_attr())
+        );
+    }
+
+    #[test]
+    fn tuple_wrong_length_attr() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            <(bool, bool, bool)>::from_attr((true, false).into_attr())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "smallvec")]
+    fn smallvec_attr() {
+        let value: SmallVec<[u32; 2]> = smallvec::smallvec![1, 2, 3];
+        assert_eq!(value.clone().into_attr(), vec![1_u32, 2, 3].into_attr());
+        assert_eq!(Ok(value.clone()), SmallVec::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time_offset_date_time_attr() {
+        let value = OffsetDateTime::parse("2024-06-19T15:22:45.5-04:00", &Rfc3339).unwrap();
+        assert_eq!(
+            value.into_attr().s.as_deref(),
+            Some("2024-06-19T15:22:45.5-04:00")
+        );
+        assert_eq!(Ok(value), OffsetDateTime::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time_date_attr() {
+        let value = Date::from_calendar_date(2024, time::Month::June, 9).unwrap();
+        assert_eq!(value.into_attr().s.as_deref(), Some("2024-06-09"));
+        assert_eq!(Ok(value), Date::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time_offset_date_time_with_offset_seconds_attr() {
+        let value = OffsetDateTime::parse("2024-06-19T15:22:45.5Z", &Rfc3339)
+            .unwrap()
+            .to_offset(UtcOffset::from_hms(1, 2, 3).unwrap());
+        assert_eq!(
+            value.into_attr().s.as_deref(),
+            Some("2024-06-19T15:22:45.5Z")
+        );
+        assert_eq!(Ok(value), OffsetDateTime::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time_negative_year_attr() {
+        let date = Date::from_calendar_date(-1, time::Month::June, 9).unwrap();
+        assert_eq!(date.into_attr().s.as_deref(), Some("-0001-06-09"));
+        assert_eq!(Ok(date), Date::from_attr(date.into_attr()));
+
+        let value = date
+            .with_hms(15, 22, 45)
+            .unwrap()
+            .assume_offset(UtcOffset::from_hms(1, 2, 3).unwrap());
+        assert_eq!(
+            value.into_attr().s.as_deref(),
+            Some("-0001-06-09T15:22:45.0+01:02:03")
+        );
+        assert_eq!(Ok(value), OffsetDateTime::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time_invalid_attr() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            OffsetDateTime::from_attr("2024-06-19".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Date::from_attr("2024-02-30".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "jiff")]
+    fn jiff_timestamp_attr() {
+        let value: Timestamp = "2024-06-19T15:22:45.123Z".parse().unwrap();
+        assert_eq!(
+            value.into_attr().s.as_deref(),
+            Some("2024-06-19T15:22:45.123Z")
+        );
+        assert_eq!(Ok(value), Timestamp::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "jiff")]
+    fn jiff_zoned_attr() {
+        let value: Zoned = "2024-06-19T15:22:45-04:00[-04:00]".parse().unwrap();
+        assert_eq!(
+            value.clone().into_attr().s.as_deref(),
+            Some("2024-06-19T15:22:45-04:00[-04:00]")
+        );
+        assert_eq!(Ok(value.clone()), Zoned::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "jiff")]
+    fn jiff_invalid_attr() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Timestamp::from_attr("yesterday".to_string().into_attr())
+        );
+        // a zoned timestamp requires a time zone annotation
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Zoned::from_attr("2024-06-19T15:22:45-04:00".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "N"
+            }),
+            Timestamp::from_attr(1.into_attr())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ulid")]
+    fn ulid_attr() {
+        let value = Ulid::new();
+        assert_eq!(value.into_attr().s, Some(value.to_string()));
+        assert_eq!(Ok(value), Ulid::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "ulid")]
+    fn ulid_invalid_attr() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Ulid::from_attr(AttributeValue {
+                s: Some("not-a-ulid".into()),
+                ..AttributeValue::default()
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "url")]
+    fn url_attr() {
+        let value = Url::parse("https://example.com/hooks?id=1").unwrap();
+        assert_eq!(
+            value.clone().into_attr().s.as_deref(),
+            Some("https://example.com/hooks?id=1")
+        );
+        assert_eq!(Ok(value.clone()), Url::from_attr(value.into_attr()));
+    }
 
     #[test]
-    fn uuid_attr() {
-        let value = Uuid::new_v4();
-        assert_eq!(Ok(value), Uuid::from_attr(value.into_attr()));
+    #[cfg(feature = "url")]
+    fn url_invalid_attr() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Url::from_attr("not a url".to_string().into_attr())
+        );
     }
 
     #[test]
-    fn uuid_invalid_attr() {
+    #[cfg(feature = "rust_decimal")]
+    fn decimal_attr() {
+        let value: Decimal = "12345.6789".parse().unwrap();
+        assert_eq!(value.into_attr().n, Some("12345.6789".to_string()));
+        assert_eq!(Ok(value), Decimal::from_attr(value.into_attr()));
+        // unlike f64
+        let float = 0.1_f64 + 0.2;
+        assert_ne!(float.into_attr().n, Some("0.3".to_string()));
+        let decimal: Decimal =
+            "0.1".parse::<Decimal>().unwrap() + "0.2".parse::<Decimal>().unwrap();
+        assert_eq!(decimal.into_attr().n, Some("0.3".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "rust_decimal")]
+    fn decimal_invalid_attr() {
         assert_eq!(
-            Err(AttributeError::InvalidType),
-            Uuid::from_attr(AttributeValue {
-                bool: Some(true),
+            Err(AttributeError::InvalidFormat),
+            Decimal::from_attr(AttributeValue {
+                n: Some("twelve".into()),
                 ..AttributeValue::default()
             })
         );
@@ -1108,7 +2975,10 @@ mod test {
     #[cfg(feature = "chrono")]
     fn chrono_datetime_invalid_utc_attr() {
         assert_eq!(
-            Err(AttributeError::InvalidType),
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "BOOL"
+            }),
             DateTime::<Utc>::from_attr(AttributeValue {
                 bool: Some(true),
                 ..AttributeValue::default()
@@ -1127,7 +2997,10 @@ mod test {
     #[cfg(feature = "chrono")]
     fn chrono_datetime_invalid_local_attr() {
         assert_eq!(
-            Err(AttributeError::InvalidType),
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "BOOL"
+            }),
             DateTime::<Local>::from_attr(AttributeValue {
                 bool: Some(true),
                 ..AttributeValue::default()
@@ -1152,7 +3025,10 @@ mod test {
     #[cfg(feature = "chrono")]
     fn chrono_datetime_invalid_fixedoffset_attr() {
         assert_eq!(
-            Err(AttributeError::InvalidType),
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "BOOL"
+            }),
             DateTime::<FixedOffset>::from_attr(AttributeValue {
                 bool: Some(true),
                 ..AttributeValue::default()
@@ -1173,7 +3049,10 @@ mod test {
     fn system_time_invalid_attr() {
         use std::time::SystemTime;
         assert_eq!(
-            Err(AttributeError::InvalidType),
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "BOOL"
+            }),
             SystemTime::from_attr(AttributeValue {
                 bool: Some(true),
                 ..AttributeValue::default()
@@ -1181,6 +3060,126 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn chrono_naive_time_attr() {
+        let value = NaiveTime::from_hms_opt(23, 16, 9).unwrap();
+        assert_eq!(value.into_attr().s, Some("23:16:09".to_string()));
+        assert_eq!(Ok(value), NaiveTime::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn chrono_naive_time_subsec_attr() {
+        let value = NaiveTime::from_hms_milli_opt(8, 5, 0, 250).unwrap();
+        assert_eq!(value.into_attr().s, Some("08:05:00.250".to_string()));
+        assert_eq!(Ok(value), NaiveTime::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn chrono_naive_time_invalid_attr() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            NaiveTime::from_attr("25:61".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    fn duration_attr() {
+        let value = Duration::new(90, 500_000_000);
+        assert_eq!(value.into_attr().n, Some("90.5".to_string()));
+        assert_eq!(Ok(value), Duration::from_attr(value.into_attr()));
+        assert_eq!(
+            Ok(Duration::from_secs(3)),
+            Duration::from_attr(Duration::from_secs(3).into_attr())
+        );
+        assert_eq!(
+            Ok(Duration::from_nanos(1)),
+            Duration::from_attr(Duration::from_nanos(1).into_attr())
+        );
+    }
+
+    #[test]
+    fn duration_invalid_attr() {
+        for n in &["-1", "1.0000000001", "1.-5", "one"] {
+            assert_eq!(
+                Err(AttributeError::InvalidFormat),
+                Duration::from_attr(AttributeValue {
+                    n: Some(n.to_string()),
+                    ..AttributeValue::default()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn duration_vec_attr() {
+        let value = vec![Duration::from_millis(1500), Duration::from_secs(1)];
+        assert!(value.clone().into_attr().l.is_some());
+        assert_eq!(
+            Ok(value.clone()),
+            Vec::<Duration>::from_attr(value.into_attr())
+        );
+    }
+
+    #[test]
+    fn duration_set_attr() {
+        let value = btreeset! { Duration::from_millis(1500), Duration::from_secs(1) };
+        let attr = value.clone().into_attr();
+        assert_eq!(attr.ns, Some(vec!["1".to_string(), "1.5".to_string()]));
+        assert_eq!(Ok(value), BTreeSet::<Duration>::from_attr(attr));
+
+        let value = hashset! { Duration::from_millis(1500), Duration::from_secs(1) };
+        assert_eq!(
+            Ok(value.clone()),
+            HashSet::<Duration>::from_attr(value.into_attr())
+        );
+    }
+
+    #[test]
+    fn box_map_attr() {
+        let value = Box::new(hashmap! { "foo".to_string() => 1_u32 });
+        assert_eq!(value.clone().into_attr(), (*value).clone().into_attr());
+        assert_eq!(
+            Ok(value.clone()),
+            Box::<HashMap<String, u32>>::from_attr(value.into_attr())
+        );
+    }
+
+    #[test]
+    fn arc_attr() {
+        let value = Arc::new("test".to_string());
+        let shared = value.clone();
+        assert_eq!(
+            value.clone().into_attr(),
+            AttributeValue {
+                s: Some("test".into()),
+                ..AttributeValue::default()
+            }
+        );
+        assert_eq!(Ok(value), Arc::<String>::from_attr(shared.into_attr()));
+    }
+
+    #[test]
+    fn box_attr() {
+        let value = Box::new(1_u32);
+        assert_eq!(
+            value.clone().into_attr(),
+            AttributeValue {
+                n: Some("1".into()),
+                ..AttributeValue::default()
+            }
+        );
+        assert_eq!(Ok(value.clone()), Box::<u32>::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    fn rc_attr() {
+        let value = Rc::new(1_u32);
+        assert_eq!(Ok(value.clone()), Rc::<u32>::from_attr(value.into_attr()));
+    }
+
     #[test]
     fn option_some_attr() {
         let value = Some(1);
@@ -1193,10 +3192,31 @@ mod test {
         assert_eq!(Ok(value), Attribute::from_attr(value.into_attr()));
     }
 
+    #[test]
+    fn option_nested_attr() {
+        let none: Option<Option<u32>> = None;
+        assert_eq!(Ok(None), Option::<Option<u32>>::from_attr(none.into_attr()));
+        // the inner `None` is indistinguishable from the outer `None`
+        let some_none: Option<Option<u32>> = Some(None);
+        assert_eq!(some_none.into_attr().null, Some(true));
+        assert_eq!(
+            Ok(None),
+            Option::<Option<u32>>::from_attr(some_none.into_attr())
+        );
+        let some_some: Option<Option<u32>> = Some(Some(5));
+        assert_eq!(
+            Ok(some_some),
+            Option::<Option<u32>>::from_attr(some_some.into_attr())
+        );
+    }
+
     #[test]
     fn option_invalid_attr() {
         assert_eq!(
-            Err(AttributeError::InvalidType),
+            Err(AttributeError::WrongType {
+                expected: "N",
+                found: "BOOL"
+            }),
             Option::<u32>::from_attr(AttributeValue {
                 bool: Some(true),
                 ..AttributeValue::default()
@@ -1244,6 +3264,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn non_zero_attr() {
+        let value = NonZeroU32::new(42).unwrap();
+        assert_eq!(value.into_attr(), 42_u32.into_attr());
+        assert_eq!(Ok(value), NonZeroU32::from_attr(value.into_attr()));
+        let value = NonZeroI64::new(-42).unwrap();
+        assert_eq!(Ok(value), NonZeroI64::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    fn non_zero_rejects_zero() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            NonZeroU64::from_attr(0_u64.into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            NonZeroI16::from_attr(0_i16.into_attr())
+        );
+    }
+
     #[test]
     fn string_into_attr() {
         assert_eq!(
@@ -1276,6 +3317,77 @@ mod test {
         );
     }
 
+    #[test]
+    fn phantom_data_attr() {
+        let value = PhantomData::<String>.into_attr();
+        assert_eq!(value.null, Some(true));
+        assert_eq!(PhantomData::<String>::from_attr(value), Ok(PhantomData));
+    }
+
+    #[test]
+    fn cow_bytes_attr() {
+        let borrowed: Cow<[u8]> = Cow::Borrowed(b"foo");
+        let owned: Cow<[u8]> = Cow::Owned(b"bar".to_vec());
+        assert_eq!(borrowed.clone().into_attr().b.as_deref(), Some(&b"foo"[..]));
+        assert_eq!(owned.clone().into_attr().b.as_deref(), Some(&b"bar"[..]));
+        assert_eq!(Cow::from_attr(borrowed.clone().into_attr()), Ok(borrowed));
+        assert_eq!(Cow::from_attr(owned.clone().into_attr()), Ok(owned));
+        assert_eq!(
+            Cow::<[u8]>::from_attr("foo".to_string().into_attr()),
+            Err(AttributeError::WrongType {
+                expected: "B",
+                found: "S"
+            })
+        );
+    }
+
+    #[test]
+    fn string_wrong_type_names_both_types() {
+        let err = String::from_attr(1.into_attr()).unwrap_err();
+        assert_eq!(
+            err,
+            AttributeError::WrongType {
+                expected: "S",
+                found: "N"
+            }
+        );
+        assert_eq!(err.to_string(), "Wrong type, expected S but found N");
+    }
+
+    #[test]
+    fn empty_string_into_attr() {
+        assert_eq!(
+            serde_json::to_string(&String::new().into_attr()).unwrap(),
+            r#"{"S":""}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Cow::Borrowed("").into_attr()).unwrap(),
+            r#"{"S":""}"#
+        );
+    }
+
+    #[test]
+    fn empty_string_from_attr() {
+        assert_eq!(
+            Attribute::from_attr(serde_json::from_str::<AttributeValue>(r#"{"S":""}"#).unwrap()),
+            Ok(String::new())
+        );
+        assert_eq!(
+            Attribute::from_attr(serde_json::from_str::<AttributeValue>(r#"{"S":""}"#).unwrap()),
+            Ok(Cow::Borrowed(""))
+        );
+    }
+
+    #[test]
+    fn option_empty_string_is_not_null() {
+        let value = Some(String::new());
+        assert_eq!(
+            serde_json::to_string(&value.clone().into_attr()).unwrap(),
+            r#"{"S":""}"#
+        );
+        assert_eq!(Ok(value.clone()), Attribute::from_attr(value.into_attr()));
+    }
+
     #[test]
     fn byte_vec_into_attr() {
         assert_eq!(
@@ -1373,6 +3485,24 @@ mod test {
         );
     }
 
+    #[test]
+    fn binary_btreeset_attr() {
+        let value = btreeset! { b"foo".to_vec(), b"bar".to_vec() };
+        assert_eq!(
+            value.clone().into_attr().bs,
+            Some(vec![Bytes::from("bar"), Bytes::from("foo")])
+        );
+        assert_eq!(Ok(value.clone()), Attribute::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    fn binary_bytes_set_attr() {
+        let value = hashset! { Bytes::from("foo"), Bytes::from("bar") };
+        assert_eq!(Ok(value.clone()), Attribute::from_attr(value.into_attr()));
+        let value = btreeset! { Bytes::from("foo"), Bytes::from("bar") };
+        assert_eq!(Ok(value.clone()), Attribute::from_attr(value.into_attr()));
+    }
+
     #[test]
     fn string_vec_into_attr() {
         assert_eq!(
@@ -1430,4 +3560,296 @@ mod test {
             Ok(btreemap! { "foo".to_string() => 1 })
         );
     }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap_attr() {
+        let value: IndexMap<String, u32> = (0..10).map(|i| (format!("key{}", i), i)).collect();
+        let mut read = IndexMap::<String, u32>::from_attr(value.clone().into_attr()).unwrap();
+        assert_eq!(read, value);
+        read.sort_keys();
+        assert!(read.keys().eq(value.keys()));
+    }
+
+    #[test]
+    fn hashmap_non_string_keys_attr() {
+        let value = hashmap! { 1_u32 => "one".to_string(), 2 => "two".to_string() };
+        assert_eq!(
+            value.clone().into_attr().m,
+            Some(hashmap! {
+                "1".to_string() => "one".to_string().into_attr(),
+                "2".to_string() => "two".to_string().into_attr(),
+            })
+        );
+        assert_eq!(Ok(value.clone()), Attribute::from_attr(value.into_attr()));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn btreemap_non_string_keys_attr() {
+        let value = btreemap! { Uuid::new_v4() => 1_u64, Uuid::new_v4() => 2 };
+        assert_eq!(Ok(value.clone()), Attribute::from_attr(value.into_attr()));
+    }
+
+    #[test]
+    fn hashmap_invalid_key_attr() {
+        assert_eq!(
+            Err(AttributeError::Path {
+                path: vec!["foo".into()],
+                source: Box::new(AttributeError::InvalidFormat)
+            }),
+            HashMap::<u32, String>::from_attr(hashmap! { "foo".to_string() => 1 }.into_attr())
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Book {
+        id: String,
+        title: String,
+    }
+
+    impl IntoAttributes for Book {
+        fn into_attrs(
+            self,
+            sink: &mut Attributes,
+        ) {
+            sink.insert("id".into(), self.id.into_attr());
+            sink.insert("title".into(), self.title.into_attr());
+        }
+    }
+
+    impl FromAttributes for Book {
+        fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError> {
+            Ok(Book {
+                id: String::from_attr(
+                    attrs
+                        .remove("id")
+                        .ok_or_else(|| AttributeError::MissingField { name: "id".into() })?,
+                )?,
+                title: String::from_attr(attrs.remove("title").ok_or_else(|| {
+                    AttributeError::MissingField {
+                        name: "title".into(),
+                    }
+                })?)?,
+            })
+        }
+    }
+
+    impl Item for Book {
+        fn key(&self) -> Attributes {
+            hashmap! { "id".into() => self.id.clone().into_attr() }
+        }
+    }
+
+    fn book() -> Book {
+        Book {
+            id: "123".into(),
+            title: "rust".into(),
+        }
+    }
+
+    #[test]
+    fn attr_map_accepts_str_values() {
+        let attrs = attr_map! { ":v" => "rust", ":n" => 1 };
+        assert_eq!(attrs[":v"].s.as_deref(), Some("rust"));
+        assert_eq!(attrs[":n"], 1.into_attr());
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Color {
+        Red,
+        Green,
+    }
+
+    impl std::fmt::Display for Color {
+        fn fmt(
+            &self,
+            f: &mut std::fmt::Formatter<'_>,
+        ) -> std::fmt::Result {
+            f.write_str(match self {
+                Color::Red => "red",
+                Color::Green => "green",
+            })
+        }
+    }
+
+    impl FromStr for Color {
+        type Err = String;
+        fn from_str(s: &str) -> Result<Self, String> {
+            match s {
+                "red" => Ok(Color::Red),
+                "green" => Ok(Color::Green),
+                other => Err(format!("unknown color {}", other)),
+            }
+        }
+    }
+
+    impl_attribute_via_string!(Color);
+
+    #[test]
+    fn attribute_via_string() {
+        assert_eq!(Color::Green.into_attr(), "green".to_string().into_attr());
+        assert_eq!(Ok(Color::Red), Color::from_attr(Color::Red.into_attr()));
+    }
+
+    #[test]
+    fn attribute_via_string_invalid() {
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Color::from_attr("blue".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::WrongType {
+                expected: "S",
+                found: "N"
+            }),
+            Color::from_attr(1.into_attr())
+        );
+    }
+
+    /// A wrapper delegating to the `Display` and `FromStr` impls of the type it wraps
+    #[derive(Debug, PartialEq)]
+    struct Host(std::net::IpAddr);
+
+    impl std::fmt::Display for Host {
+        fn fmt(
+            &self,
+            f: &mut std::fmt::Formatter<'_>,
+        ) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl FromStr for Host {
+        type Err = std::net::AddrParseError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse().map(Host)
+        }
+    }
+
+    impl_attribute_via_string!(Host);
+
+    #[test]
+    fn attribute_via_string_wrapper() {
+        let host = Host("127.0.0.1".parse().unwrap());
+        assert_eq!(host.into_attr().s.as_deref(), Some("127.0.0.1"));
+        assert_eq!(
+            Ok(Host("::1".parse().unwrap())),
+            Host::from_attr("::1".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(AttributeError::InvalidFormat),
+            Host::from_attr("localhost".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    fn merge_overlays_patch() {
+        let mut base = attr_map! { "a" => 1, "b" => 2 };
+        merge(&mut base, attr_map! { "b" => 3, "c" => 4 });
+        assert_eq!(base, attr_map! { "a" => 1, "b" => 3, "c" => 4 });
+    }
+
+    #[test]
+    fn diff_includes_changed_and_added_attributes() {
+        let old = attr_map! { "unchanged" => 1, "changed" => 2, "removed" => 3 };
+        let new = attr_map! { "unchanged" => 1, "changed" => 20, "added" => 4 };
+        assert_eq!(
+            diff(&old, &new),
+            attr_map! { "changed" => 20, "added" => 4 }
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn collect_attrs_converts_values() {
+        assert_eq!(
+            collect_attrs(vec![("a", 1), ("b", 2)]),
+            hashmap! {
+                "a".to_string() => 1.into_attr(),
+                "b".to_string() => 2.into_attr(),
+            }
+        );
+        let row = vec![("name".to_string(), "rust".to_string())];
+        assert_eq!(
+            collect_attrs(row),
+            hashmap! { "name".to_string() => "rust".to_string().into_attr() }
+        );
+    }
+
+    #[test]
+    fn item_get_input() {
+        let input = book().get_input("books");
+        assert_eq!(input.table_name, "books");
+        assert_eq!(input.key, book().key());
+    }
+
+    #[test]
+    fn item_delete_input() {
+        let input = book().delete_input("books");
+        assert_eq!(input.table_name, "books");
+        assert_eq!(input.key, book().key());
+    }
+
+    #[test]
+    fn hashmap_of_items_attr() {
+        let value = hashmap! { "b1".to_string() => book() };
+        let attr = value.clone().into_attr();
+        assert_eq!(
+            attr.m.as_ref().and_then(|m| m["b1"].m.clone()),
+            book().into_attr().m
+        );
+        assert_eq!(Ok(value), HashMap::<String, Book>::from_attr(attr));
+    }
+
+    #[test]
+    fn hashmap_of_items_invalid_attr() {
+        let mut partial = Attributes::new();
+        book().into_attrs(&mut partial);
+        partial.remove("title");
+        let value = AttributeValue {
+            m: Some(hashmap! {
+                "b1".to_string() => book().into_attr(),
+                "b2".to_string() => partial.into_attr(),
+            }),
+            ..AttributeValue::default()
+        };
+        let err = HashMap::<String, Book>::from_attr(value).unwrap_err();
+        assert_eq!(
+            err,
+            AttributeError::Path {
+                path: vec!["b2".into()],
+                source: Box::new(AttributeError::MissingField {
+                    name: "title".into()
+                })
+            }
+        );
+        assert_eq!(err.to_string(), "b2: Missing field title");
+    }
+
+    #[test]
+    fn from_attrs_with_remainder_returns_unconsumed() {
+        let attrs = hashmap! {
+            "id".to_string() => "123".to_string().into_attr(),
+            "title".to_string() => "rust".to_string().into_attr(),
+            "extra".to_string() => 1.into_attr(),
+        };
+        let (value, remainder) =
+            Book::from_attrs_with_remainder(attrs).expect("book to deserialize");
+        assert_eq!(value, book());
+        assert_eq!(remainder, hashmap! { "extra".to_string() => 1.into_attr() });
+    }
+
+    #[test]
+    fn item_put_input() {
+        let input = book().put_input(String::from("books"));
+        assert_eq!(input.table_name, "books");
+        assert_eq!(
+            input.item,
+            hashmap! {
+                "id".to_string() => "123".to_string().into_attr(),
+                "title".to_string() => "rust".to_string().into_attr(),
+            }
+        );
+    }
 }
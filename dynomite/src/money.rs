@@ -0,0 +1,143 @@
+//! Fixed-point monetary amounts
+//!
+//! Floating point numbers can't represent most decimal fractions exactly, which makes them a poor fit
+//! for currency. [`Money`](struct.Money.html) instead stores an integer amount of a currency's minor units, e.g. cents.
+
+use crate::{Attribute, AttributeError, Attributes, FromAttributes, IntoAttributes};
+
+/// An amount of money in a currency's minor units, such as cents for `USD`
+///
+/// Represented by the M AttributeValue type as an `amount` number (`N`) and `currency` string (`S`).
+/// Currencies are validated as three letter, upper case, [ISO 4217](https://www.iso.org/iso-4217-currency-codes.html) codes
+/// when read.
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::{Attribute, Money};
+///
+/// let price = Money::new(1999, "USD");
+/// assert_eq!(Ok(price.clone()), Money::from_attr(price.into_attr()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Money {
+    /// The amount in minor units of the currency
+    pub amount: i64,
+    /// The ISO 4217 currency code, e.g. `USD`
+    pub currency: String,
+}
+
+impl Money {
+    /// Returns a new amount of `amount` minor units of `currency`
+    pub fn new(
+        amount: i64,
+        currency: impl Into<String>,
+    ) -> Self {
+        Money {
+            amount,
+            currency: currency.into(),
+        }
+    }
+}
+
+impl IntoAttributes for Money {
+    fn into_attrs(
+        self,
+        sink: &mut Attributes,
+    ) {
+        sink.insert("amount".into(), self.amount.into_attr());
+        sink.insert("currency".into(), self.currency.into_attr());
+    }
+}
+
+impl FromAttributes for Money {
+    fn from_attrs(attrs: &mut Attributes) -> Result<Self, AttributeError> {
+        let amount = field(attrs, "amount")?;
+        let currency: String = field(attrs, "currency")?;
+        if currency.len() != 3 || !currency.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(AttributeError::InvalidFormat.in_field("currency"));
+        }
+        Ok(Money { amount, currency })
+    }
+}
+
+fn field<A: Attribute>(
+    attrs: &mut Attributes,
+    name: &str,
+) -> Result<A, AttributeError> {
+    let value = attrs
+        .remove(name)
+        .ok_or_else(|| AttributeError::MissingField {
+            name: name.to_string(),
+        })?;
+    A::from_attr(value).map_err(|err| err.in_field(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AttributeValue;
+    use maplit::hashmap;
+
+    #[test]
+    fn money_attr() {
+        for value in &[
+            Money::new(1999, "USD"),
+            Money::new(0, "EUR"),
+            Money::new(-250, "JPY"),
+        ] {
+            assert_eq!(
+                Ok(value.clone()),
+                Money::from_attr(value.clone().into_attr())
+            );
+        }
+    }
+
+    #[test]
+    fn money_into_attr() {
+        assert_eq!(
+            Money::new(-5, "GBP").into_attr().m,
+            Some(hashmap! {
+                "amount".to_string() => (-5_i64).into_attr(),
+                "currency".to_string() => "GBP".to_string().into_attr(),
+            })
+        );
+    }
+
+    #[test]
+    fn money_invalid_attr() {
+        let malformed = |attrs: Attributes| {
+            Money::from_attr(AttributeValue {
+                m: Some(attrs),
+                ..AttributeValue::default()
+            })
+        };
+        assert_eq!(
+            malformed(hashmap! { "amount".to_string() => 1.into_attr() }),
+            Err(AttributeError::MissingField {
+                name: "currency".into()
+            })
+        );
+        assert_eq!(
+            malformed(hashmap! {
+                "amount".to_string() => 1.5.into_attr(),
+                "currency".to_string() => "USD".to_string().into_attr(),
+            }),
+            Err(AttributeError::InvalidFormat.in_field("amount"))
+        );
+        assert_eq!(
+            malformed(hashmap! {
+                "amount".to_string() => 1.into_attr(),
+                "currency".to_string() => "dollars".to_string().into_attr(),
+            }),
+            Err(AttributeError::InvalidFormat.in_field("currency"))
+        );
+        assert_eq!(
+            Money::from_attr("1999 USD".to_string().into_attr()),
+            Err(AttributeError::WrongType {
+                expected: "M",
+                found: "S"
+            })
+        );
+    }
+}
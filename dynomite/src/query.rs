@@ -0,0 +1,472 @@
+//! Fluent builders for query and scan inputs
+//!
+//! [`QueryBuilder`](struct.QueryBuilder.html) and [`ScanBuilder`](struct.ScanBuilder.html) set the
+//! commonly used parameters of [`QueryInput`] and [`ScanInput`] with chainable methods, accepting
+//! key conditions and filters composed with the [`expression`](../expression/index.html) module and
+//! merging their placeholder maps into the built input.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{
+//!     expression::{attr, value},
+//!     query::{QueryBuilder, ReturnConsumedCapacity},
+//! };
+//!
+//! let input = QueryBuilder::new("books")
+//!     .index("by_status")
+//!     .key_condition(attr("status").eq(value(":status", "published".to_string())))
+//!     .filter(attr("pages").gt(value(":pages", 100)))
+//!     .consistent_read(true)
+//!     .return_consumed_capacity(ReturnConsumedCapacity::Total)
+//!     .limit(10)
+//!     .build();
+//!
+//! assert_eq!(input.index_name.as_deref(), Some("by_status"));
+//! assert_eq!(input.key_condition_expression.as_deref(), Some("status = :status"));
+//! assert_eq!(input.filter_expression.as_deref(), Some("pages > :pages"));
+//! ```
+//!
+//! [`QueryInput`]: https://docs.rs/rusoto_dynamodb/*/rusoto_dynamodb/struct.QueryInput.html
+//! [`ScanInput`]: https://docs.rs/rusoto_dynamodb/*/rusoto_dynamodb/struct.ScanInput.html
+
+use crate::{
+    dynamodb::{QueryInput, ScanInput},
+    expression::Expression,
+    Attributes,
+};
+use std::collections::HashMap;
+
+/// Levels of detail of the consumed capacity returned by an operation
+///
+/// See the `ReturnConsumedCapacity` parameter of [Query](https://docs.aws.amazon.com/amazondynamodb/latest/APIReference/API_Query.html#DDB-Query-request-ReturnConsumedCapacity)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReturnConsumedCapacity {
+    /// Return the capacity consumed by the table and each index
+    Indexes,
+    /// Return the total capacity consumed
+    Total,
+    /// Return no consumed capacity
+    None,
+}
+
+impl ReturnConsumedCapacity {
+    /// Returns the name DynamoDB uses for this setting, e.g. `TOTAL`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReturnConsumedCapacity::Indexes => "INDEXES",
+            ReturnConsumedCapacity::Total => "TOTAL",
+            ReturnConsumedCapacity::None => "NONE",
+        }
+    }
+}
+
+/// A builder for `QueryInput`s
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    input: QueryInput,
+    placeholders: Placeholders,
+}
+
+impl QueryBuilder {
+    /// Returns a builder for a query of the given table
+    pub fn new(table_name: impl Into<String>) -> Self {
+        QueryBuilder {
+            input: QueryInput {
+                table_name: table_name.into(),
+                ..QueryInput::default()
+            },
+            ..QueryBuilder::default()
+        }
+    }
+
+    /// Queries the named secondary index rather than the table
+    pub fn index(
+        mut self,
+        index_name: impl Into<String>,
+    ) -> Self {
+        self.input.index_name = Some(index_name.into());
+        self
+    }
+
+    /// Sets the condition items' keys must meet, typically a condition built with the `expression` module
+    pub fn key_condition(
+        mut self,
+        condition: impl Into<Expression>,
+    ) -> Self {
+        self.input.key_condition_expression = Some(self.placeholders.add(condition.into()));
+        self
+    }
+
+    /// Sets the condition items must meet to be returned, once they have been read
+    pub fn filter(
+        mut self,
+        condition: impl Into<Expression>,
+    ) -> Self {
+        self.input.filter_expression = Some(self.placeholders.add(condition.into()));
+        self
+    }
+
+    /// Sets whether items are read with strongly consistent reads
+    pub fn consistent_read(
+        mut self,
+        consistent_read: bool,
+    ) -> Self {
+        self.input.consistent_read = Some(consistent_read);
+        self
+    }
+
+    /// Sets the maximum number of items evaluated per page
+    pub fn limit(
+        mut self,
+        limit: i64,
+    ) -> Self {
+        self.input.limit = Some(limit);
+        self
+    }
+
+    /// Sets whether items are returned in ascending order of their sort keys, rather than descending
+    pub fn scan_index_forward(
+        mut self,
+        scan_index_forward: bool,
+    ) -> Self {
+        self.input.scan_index_forward = Some(scan_index_forward);
+        self
+    }
+
+    /// Sets the level of detail of the consumed capacity returned
+    pub fn return_consumed_capacity(
+        mut self,
+        return_consumed_capacity: ReturnConsumedCapacity,
+    ) -> Self {
+        self.input.return_consumed_capacity = Some(return_consumed_capacity.as_str().into());
+        self
+    }
+
+    /// Returns the built `QueryInput`
+    pub fn build(self) -> QueryInput {
+        let QueryBuilder {
+            mut input,
+            placeholders,
+        } = self;
+        input.expression_attribute_names = placeholders.names;
+        input.expression_attribute_values = placeholders.values;
+        input
+    }
+}
+
+impl From<QueryBuilder> for QueryInput {
+    fn from(builder: QueryBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// A builder for `ScanInput`s
+#[derive(Debug, Clone, Default)]
+pub struct ScanBuilder {
+    input: ScanInput,
+    placeholders: Placeholders,
+}
+
+impl ScanBuilder {
+    /// Returns a builder for a scan of the given table
+    pub fn new(table_name: impl Into<String>) -> Self {
+        ScanBuilder {
+            input: ScanInput {
+                table_name: table_name.into(),
+                ..ScanInput::default()
+            },
+            ..ScanBuilder::default()
+        }
+    }
+
+    /// Scans the named secondary index rather than the table
+    pub fn index(
+        mut self,
+        index_name: impl Into<String>,
+    ) -> Self {
+        self.input.index_name = Some(index_name.into());
+        self
+    }
+
+    /// Sets the condition items must meet to be returned, once they have been read
+    pub fn filter(
+        mut self,
+        condition: impl Into<Expression>,
+    ) -> Self {
+        self.input.filter_expression = Some(self.placeholders.add(condition.into()));
+        self
+    }
+
+    /// Sets whether items are read with strongly consistent reads
+    pub fn consistent_read(
+        mut self,
+        consistent_read: bool,
+    ) -> Self {
+        self.input.consistent_read = Some(consistent_read);
+        self
+    }
+
+    /// Sets the maximum number of items evaluated per page
+    pub fn limit(
+        mut self,
+        limit: i64,
+    ) -> Self {
+        self.input.limit = Some(limit);
+        self
+    }
+
+    /// Sets the level of detail of the consumed capacity returned
+    pub fn return_consumed_capacity(
+        mut self,
+        return_consumed_capacity: ReturnConsumedCapacity,
+    ) -> Self {
+        self.input.return_consumed_capacity = Some(return_consumed_capacity.as_str().into());
+        self
+    }
+
+    /// Returns the built `ScanInput`
+    pub fn build(self) -> ScanInput {
+        let ScanBuilder {
+            mut input,
+            placeholders,
+        } = self;
+        input.expression_attribute_names = placeholders.names;
+        input.expression_attribute_values = placeholders.values;
+        input
+    }
+}
+
+impl From<ScanBuilder> for ScanInput {
+    fn from(builder: ScanBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// The placeholders of each expression of an input, which share the same maps
+#[derive(Debug, Clone, Default)]
+struct Placeholders {
+    names: Option<HashMap<String, String>>,
+    values: Option<Attributes>,
+}
+
+impl Placeholders {
+    /// Merges the expression's placeholders, returning its text
+    ///
+    /// Placeholders already merged from another expression for something else, such as a
+    /// `:status` value used by both a key condition and a filter, or a `#name` of a different
+    /// attribute, are renamed with a numeric suffix so that neither is lost
+    fn add(
+        &mut self,
+        expression: Expression,
+    ) -> String {
+        let Expression {
+            expression,
+            names,
+            values,
+        } = expression;
+        let mut renames = HashMap::new();
+        if let Some(names) = names {
+            renames.extend(merge_placeholders(
+                self.names.get_or_insert_with(HashMap::new),
+                names,
+            ));
+        }
+        if let Some(values) = values {
+            renames.extend(merge_placeholders(
+                self.values.get_or_insert_with(HashMap::new),
+                values,
+            ));
+        }
+        if renames.is_empty() {
+            expression
+        } else {
+            rename_placeholders(&expression, &renames)
+        }
+    }
+}
+
+/// Merges placeholders into those already merged, returning the new names of placeholders
+/// which were already merged with a different value
+fn merge_placeholders<V: PartialEq>(
+    merged: &mut HashMap<String, V>,
+    placeholders: HashMap<String, V>,
+) -> HashMap<String, String> {
+    let mut renames = HashMap::new();
+    for (placeholder, value) in &placeholders {
+        if matches!(merged.get(placeholder), Some(merged) if merged != value) {
+            let unique = (1..)
+                .map(|n| format!("{}_{}", placeholder, n))
+                .find(|unique| !merged.contains_key(unique) && !placeholders.contains_key(unique))
+                .expect("unbounded suffixes");
+            renames.insert(placeholder.clone(), unique);
+        }
+    }
+    for (placeholder, value) in placeholders {
+        let placeholder = renames.get(&placeholder).cloned().unwrap_or(placeholder);
+        merged.insert(placeholder, value);
+    }
+    renames
+}
+
+/// Replaces each `:value` and `#name` placeholder in an expression's text with its new name,
+/// in a single pass so that a replacement is never itself replaced
+fn rename_placeholders(
+    expression: &str,
+    renames: &HashMap<String, String>,
+) -> String {
+    let is_placeholder_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut renamed = String::with_capacity(expression.len());
+    let mut rest = expression;
+    while let Some(start) = rest.find([':', '#']) {
+        renamed.push_str(&rest[..start]);
+        let len = rest[start + 1..]
+            .find(|c: char| !is_placeholder_char(c))
+            .unwrap_or(rest.len() - start - 1);
+        let placeholder = &rest[start..start + 1 + len];
+        renamed.push_str(renames.get(placeholder).map_or(placeholder, String::as_str));
+        rest = &rest[start + 1 + len..];
+    }
+    renamed.push_str(rest);
+    renamed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        expression::{attr, attribute_exists, value},
+        Attribute,
+    };
+    use maplit::hashmap;
+
+    #[test]
+    fn query_defaults_to_table_name() {
+        assert_eq!(
+            QueryBuilder::new("books").build(),
+            QueryInput {
+                table_name: "books".into(),
+                ..QueryInput::default()
+            }
+        );
+    }
+
+    #[test]
+    fn query_merges_placeholders() {
+        let input = QueryBuilder::new("books")
+            .index("by_status")
+            .key_condition(attr("#status").eq(value(":status", "published".to_string())))
+            .filter(attr("pages").between(value(":min", 100), value(":max", 200)))
+            .scan_index_forward(false)
+            .consistent_read(false)
+            .limit(5)
+            .build();
+        assert_eq!(
+            input,
+            QueryInput {
+                table_name: "books".into(),
+                index_name: Some("by_status".into()),
+                key_condition_expression: Some("#status = :status".into()),
+                filter_expression: Some("pages BETWEEN :min AND :max".into()),
+                expression_attribute_names: Some(hashmap! {
+                    "#status".to_string() => "status".to_string(),
+                }),
+                expression_attribute_values: Some(hashmap! {
+                    ":status".to_string() => "published".to_string().into_attr(),
+                    ":min".to_string() => 100.into_attr(),
+                    ":max".to_string() => 200.into_attr(),
+                }),
+                scan_index_forward: Some(false),
+                consistent_read: Some(false),
+                limit: Some(5),
+                ..QueryInput::default()
+            }
+        );
+    }
+
+    #[test]
+    fn query_renames_clashing_value_placeholders() {
+        let input = QueryBuilder::new("books")
+            .key_condition(attr("status").eq(value(":status", "published".to_string())))
+            .filter(
+                attr("status")
+                    .eq(value(":status", "draft".to_string()))
+                    .or(attr("pages").gt(value(":status_1", 1))),
+            )
+            .build();
+        assert_eq!(
+            input.key_condition_expression.as_deref(),
+            Some("status = :status")
+        );
+        assert_eq!(
+            input.filter_expression.as_deref(),
+            Some("status = :status_2 OR pages > :status_1")
+        );
+        assert_eq!(
+            input.expression_attribute_values,
+            Some(hashmap! {
+                ":status".to_string() => "published".to_string().into_attr(),
+                ":status_2".to_string() => "draft".to_string().into_attr(),
+                ":status_1".to_string() => 1.into_attr(),
+            })
+        );
+    }
+
+    #[test]
+    fn query_renames_clashing_name_placeholders() {
+        let input = QueryBuilder::new("books")
+            .key_condition(attr("#n").eq(value(":n", "rust".to_string())))
+            .filter(Expression {
+                expression: "#n > :pages".into(),
+                names: Some(hashmap! { "#n".to_string() => "pages".to_string() }),
+                values: Some(hashmap! { ":pages".to_string() => 100.into_attr() }),
+            })
+            .build();
+        assert_eq!(input.key_condition_expression.as_deref(), Some("#n = :n"));
+        assert_eq!(input.filter_expression.as_deref(), Some("#n_1 > :pages"));
+        assert_eq!(
+            input.expression_attribute_names,
+            Some(hashmap! {
+                "#n".to_string() => "n".to_string(),
+                "#n_1".to_string() => "pages".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn query_shares_equal_value_placeholders() {
+        let input = QueryBuilder::new("books")
+            .key_condition(attr("status").eq(value(":status", "published".to_string())))
+            .filter(attr("previous_status").eq(value(":status", "published".to_string())))
+            .build();
+        assert_eq!(
+            input.filter_expression.as_deref(),
+            Some("previous_status = :status")
+        );
+        assert_eq!(
+            input.expression_attribute_values,
+            Some(hashmap! {
+                ":status".to_string() => "published".to_string().into_attr(),
+            })
+        );
+    }
+
+    #[test]
+    fn scan_with_filter() {
+        let input = ScanBuilder::new("books")
+            .filter(attribute_exists("isbn"))
+            .consistent_read(true)
+            .return_consumed_capacity(ReturnConsumedCapacity::Indexes)
+            .build();
+        assert_eq!(
+            input,
+            ScanInput {
+                table_name: "books".into(),
+                filter_expression: Some("attribute_exists(isbn)".into()),
+                consistent_read: Some(true),
+                return_consumed_capacity: Some("INDEXES".into()),
+                ..ScanInput::default()
+            }
+        );
+    }
+}
@@ -16,16 +16,49 @@
 //!  // appropriate
 //!  let tables = client.list_tables(Default::default());
 //! ```
+//!
+//! Retrying clients compose with [`DynamoDbExt`](../trait.DynamoDbExt.html), in which case
+//! each page request made by its auto-paginating streams is retried independently
+//!
+//! ```rust,no_run
+//!  use dynomite::{Retries, DynamoDbExt, retry::Policy};
+//!  use dynomite::dynamodb::DynamoDbClient;
+//!
+//!  let items = DynamoDbClient::new(Default::default())
+//!     .with_retries(Policy::default())
+//!     .scan_pages(Default::default());
+//! ```
+//!
+//! With the `tracing` feature enabled, each retried operation runs within a `DEBUG` level
+//! [`tracing`](https://docs.rs/tracing) span named after the operation, e.g. `get_item`,
+//! which records the number of `attempts` made and the `outcome`, either `ok` or `error`,
+//! once the operation completes.
 
 use crate::dynamodb::*;
-use again::{Condition, RetryPolicy};
+use again::Condition;
+#[cfg(feature = "tracing")]
+use again::Task;
+#[cfg(not(feature = "tracing"))]
 use log::debug;
 use rusoto_core::RusotoError;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// The retry policy of the `again` crate, which retrying operations accept as well as a [`Policy`](enum.Policy.html)
+///
+/// This is re-exported so that it can be named without depending on `again` directly, e.g. to configure a
+/// maximum delay between retries, which a `Policy` doesn't cover
+pub use again::RetryPolicy;
 
 /// Pre-configured retry policies for fallible operations
 ///
 /// A `Default` impl of retrying 5 times with an exponential backoff of 100 milliseconds
+///
+/// Pauses between retries are randomized with jitter, spreading out the retries of concurrent
+/// requests. Use [`without_jitter`](#method.without_jitter) for pauses of exactly the configured duration
 #[derive(Clone, PartialEq, Debug)]
 pub enum Policy {
     /// Limited number of times to retry
@@ -42,6 +75,14 @@ impl Default for Policy {
     }
 }
 
+impl Policy {
+    /// Returns this policy with pauses between retries of exactly the configured duration,
+    /// e.g. for reproducible latencies when testing
+    pub fn without_jitter(self) -> WithoutJitter {
+        WithoutJitter(self)
+    }
+}
+
 impl From<Policy> for RetryPolicy {
     fn from(policy: Policy) -> RetryPolicy {
         match policy {
@@ -58,6 +99,18 @@ impl From<Policy> for RetryPolicy {
     }
 }
 
+/// A `Policy` whose pauses between retries are not randomized with jitter
+///
+/// See [`Policy::without_jitter`](enum.Policy.html#method.without_jitter)
+#[derive(Clone, PartialEq, Debug)]
+pub struct WithoutJitter(pub Policy);
+
+impl From<WithoutJitter> for RetryPolicy {
+    fn from(WithoutJitter(policy): WithoutJitter) -> RetryPolicy {
+        RetryPolicy::from(policy).with_jitter(false)
+    }
+}
+
 /// Predicate trait that determines if an impl
 /// type is retryable
 trait Retry {
@@ -75,6 +128,7 @@ where
         &mut self,
         error: &RusotoError<R>,
     ) -> bool {
+        #[cfg(not(feature = "tracing"))]
         debug!("retrying operation {}", self.0);
         if let Some(value) = self.0.checked_add(1) {
             self.0 = value;
@@ -86,6 +140,30 @@ where
     }
 }
 
+/// Retries an operation per a client's policy. With the `tracing` feature enabled,
+/// the operation runs within a span of the given name
+macro_rules! retried {
+    ($client:expr, $operation:literal, $task:expr) => {{
+        #[cfg(feature = "tracing")]
+        {
+            $client
+                .traced(
+                    tracing::debug_span!(
+                        $operation,
+                        attempts = tracing::field::Empty,
+                        outcome = tracing::field::Empty
+                    ),
+                    $task,
+                )
+                .await
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            $client.inner.policy.retry_if($task, Counter(0)).await
+        }
+    }};
+}
+
 // wrapper so we only pay for one arc
 struct Inner<D> {
     client: D,
@@ -107,9 +185,10 @@ where
 {
     /// Consumes a `DynamoDb` impl and produces
     /// a `DynamoDb` which retries its operations when appropriate
+    /// per the given policy, typically a [`Policy`](enum.Policy.html)
     fn with_retries(
         self,
-        policy: Policy,
+        policy: impl Into<RetryPolicy>,
     ) -> RetryingDynamoDb<D>;
 }
 
@@ -119,7 +198,7 @@ where
 {
     fn with_retries(
         self,
-        policy: Policy,
+        policy: impl Into<RetryPolicy>,
     ) -> RetryingDynamoDb<D> {
         RetryingDynamoDb::new(self, policy)
     }
@@ -132,7 +211,7 @@ where
     /// Return a new instance with a configured retry policy
     pub fn new(
         client: D,
-        policy: Policy,
+        policy: impl Into<RetryPolicy>,
     ) -> Self {
         Self {
             inner: Arc::new(Inner {
@@ -143,6 +222,41 @@ where
     }
 }
 
+#[cfg(feature = "tracing")]
+impl<D> RetryingDynamoDb<D> {
+    /// Retries `task` per this client's policy within `span`, recording the
+    /// number of attempts made and the outcome as the span's fields
+    async fn traced<T, R>(
+        &self,
+        span: tracing::Span,
+        task: T,
+    ) -> Result<T::Item, RusotoError<R>>
+    where
+        T: Task<Error = RusotoError<R>>,
+        R: Retry + std::fmt::Debug,
+    {
+        use tracing::Instrument;
+
+        let mut task = task;
+        let mut attempts: u64 = 0;
+        let result = self
+            .inner
+            .policy
+            .retry_if(
+                || {
+                    attempts += 1;
+                    task.call()
+                },
+                Counter(0),
+            )
+            .instrument(span.clone())
+            .await;
+        span.record("attempts", attempts);
+        span.record("outcome", if result.is_ok() { "ok" } else { "error" });
+        result
+    }
+}
+
 #[async_trait::async_trait]
 impl<D> DynamoDb for RetryingDynamoDb<D>
 where
@@ -152,153 +266,99 @@ where
         &self,
         input: BatchGetItemInput,
     ) -> Result<BatchGetItemOutput, RusotoError<BatchGetItemError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.batch_get_item(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "batch_get_item", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.batch_get_item(input).await }
+        })
     }
 
     async fn batch_write_item(
         &self,
         input: BatchWriteItemInput,
     ) -> Result<BatchWriteItemOutput, RusotoError<BatchWriteItemError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.batch_write_item(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "batch_write_item", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.batch_write_item(input).await }
+        })
     }
 
     async fn create_backup(
         &self,
         input: CreateBackupInput,
     ) -> Result<CreateBackupOutput, RusotoError<CreateBackupError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.create_backup(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "create_backup", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.create_backup(input).await }
+        })
     }
 
     async fn create_global_table(
         &self,
         input: CreateGlobalTableInput,
     ) -> Result<CreateGlobalTableOutput, RusotoError<CreateGlobalTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.create_global_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "create_global_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.create_global_table(input).await }
+        })
     }
 
     async fn create_table(
         &self,
         input: CreateTableInput,
     ) -> Result<CreateTableOutput, RusotoError<CreateTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.create_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "create_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.create_table(input).await }
+        })
     }
 
     async fn delete_backup(
         &self,
         input: DeleteBackupInput,
     ) -> Result<DeleteBackupOutput, RusotoError<DeleteBackupError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.delete_backup(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "delete_backup", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.delete_backup(input).await }
+        })
     }
 
     async fn delete_item(
         &self,
         input: DeleteItemInput,
     ) -> Result<DeleteItemOutput, RusotoError<DeleteItemError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.delete_item(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "delete_item", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.delete_item(input).await }
+        })
     }
 
     async fn delete_table(
         &self,
         input: DeleteTableInput,
     ) -> Result<DeleteTableOutput, RusotoError<DeleteTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.delete_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "delete_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.delete_table(input).await }
+        })
     }
 
     async fn describe_backup(
         &self,
         input: DescribeBackupInput,
     ) -> Result<DescribeBackupOutput, RusotoError<DescribeBackupError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.describe_backup(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_backup", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.describe_backup(input).await }
+        })
     }
 
     async fn describe_export(
@@ -312,17 +372,11 @@ where
         &self,
         input: DescribeContinuousBackupsInput,
     ) -> Result<DescribeContinuousBackupsOutput, RusotoError<DescribeContinuousBackupsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.describe_continuous_backups(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_continuous_backups", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.describe_continuous_backups(input).await }
+        })
     }
 
     async fn describe_contributor_insights(
@@ -337,17 +391,11 @@ where
         &self,
         input: DescribeGlobalTableInput,
     ) -> Result<DescribeGlobalTableOutput, RusotoError<DescribeGlobalTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.describe_global_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_global_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.describe_global_table(input).await }
+        })
     }
 
     async fn describe_global_table_settings(
@@ -355,49 +403,31 @@ where
         input: DescribeGlobalTableSettingsInput,
     ) -> Result<DescribeGlobalTableSettingsOutput, RusotoError<DescribeGlobalTableSettingsError>>
     {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.describe_global_table_settings(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_global_table_settings", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.describe_global_table_settings(input).await }
+        })
     }
 
     async fn describe_limits(
         &self
     ) -> Result<DescribeLimitsOutput, RusotoError<DescribeLimitsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    async move { client.describe_limits().await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_limits", move || {
+            let client = self.inner.clone().client.clone();
+            async move { client.describe_limits().await }
+        })
     }
 
     async fn describe_table(
         &self,
         input: DescribeTableInput,
     ) -> Result<DescribeTableOutput, RusotoError<DescribeTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.describe_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.describe_table(input).await }
+        })
     }
 
     async fn describe_table_replica_auto_scaling(
@@ -417,51 +447,33 @@ where
         &self,
         input: DescribeTimeToLiveInput,
     ) -> Result<DescribeTimeToLiveOutput, RusotoError<DescribeTimeToLiveError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.describe_time_to_live(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "describe_time_to_live", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.describe_time_to_live(input).await }
+        })
     }
 
     async fn get_item(
         &self,
         input: GetItemInput,
     ) -> Result<GetItemOutput, RusotoError<GetItemError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.get_item(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "get_item", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.get_item(input).await }
+        })
     }
 
     async fn list_backups(
         &self,
         input: ListBackupsInput,
     ) -> Result<ListBackupsOutput, RusotoError<ListBackupsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.list_backups(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "list_backups", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.list_backups(input).await }
+        })
     }
 
     async fn list_exports(
@@ -482,187 +494,121 @@ where
         &self,
         input: ListGlobalTablesInput,
     ) -> Result<ListGlobalTablesOutput, RusotoError<ListGlobalTablesError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.list_global_tables(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "list_global_tables", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.list_global_tables(input).await }
+        })
     }
 
     async fn list_tables(
         &self,
         input: ListTablesInput,
     ) -> Result<ListTablesOutput, RusotoError<ListTablesError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.list_tables(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "list_tables", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.list_tables(input).await }
+        })
     }
 
     async fn list_tags_of_resource(
         &self,
         input: ListTagsOfResourceInput,
     ) -> Result<ListTagsOfResourceOutput, RusotoError<ListTagsOfResourceError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.list_tags_of_resource(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "list_tags_of_resource", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.list_tags_of_resource(input).await }
+        })
     }
 
     async fn put_item(
         &self,
         input: PutItemInput,
     ) -> Result<PutItemOutput, RusotoError<PutItemError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.put_item(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "put_item", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.put_item(input).await }
+        })
     }
 
     async fn query(
         &self,
         input: QueryInput,
     ) -> Result<QueryOutput, RusotoError<QueryError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.query(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "query", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.query(input).await }
+        })
     }
 
     async fn restore_table_from_backup(
         &self,
         input: RestoreTableFromBackupInput,
     ) -> Result<RestoreTableFromBackupOutput, RusotoError<RestoreTableFromBackupError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.restore_table_from_backup(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "restore_table_from_backup", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.restore_table_from_backup(input).await }
+        })
     }
 
     async fn restore_table_to_point_in_time(
         &self,
         input: RestoreTableToPointInTimeInput,
     ) -> Result<RestoreTableToPointInTimeOutput, RusotoError<RestoreTableToPointInTimeError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.restore_table_to_point_in_time(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "restore_table_to_point_in_time", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.restore_table_to_point_in_time(input).await }
+        })
     }
 
     async fn scan(
         &self,
         input: ScanInput,
     ) -> Result<ScanOutput, RusotoError<ScanError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.scan(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "scan", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.scan(input).await }
+        })
     }
 
     async fn tag_resource(
         &self,
         input: TagResourceInput,
     ) -> Result<(), RusotoError<TagResourceError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.tag_resource(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "tag_resource", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.tag_resource(input).await }
+        })
     }
 
     async fn untag_resource(
         &self,
         input: UntagResourceInput,
     ) -> Result<(), RusotoError<UntagResourceError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.untag_resource(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "untag_resource", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.untag_resource(input).await }
+        })
     }
 
     async fn update_continuous_backups(
         &self,
         input: UpdateContinuousBackupsInput,
     ) -> Result<UpdateContinuousBackupsOutput, RusotoError<UpdateContinuousBackupsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.update_continuous_backups(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "update_continuous_backups", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.update_continuous_backups(input).await }
+        })
     }
 
     async fn update_contributor_insights(
@@ -681,68 +627,44 @@ where
         &self,
         input: UpdateGlobalTableInput,
     ) -> Result<UpdateGlobalTableOutput, RusotoError<UpdateGlobalTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.update_global_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "update_global_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.update_global_table(input).await }
+        })
     }
 
     async fn update_global_table_settings(
         &self,
         input: UpdateGlobalTableSettingsInput,
     ) -> Result<UpdateGlobalTableSettingsOutput, RusotoError<UpdateGlobalTableSettingsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.update_global_table_settings(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "update_global_table_settings", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.update_global_table_settings(input).await }
+        })
     }
 
     async fn update_item(
         &self,
         input: UpdateItemInput,
     ) -> Result<UpdateItemOutput, RusotoError<UpdateItemError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.update_item(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "update_item", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.update_item(input).await }
+        })
     }
 
     async fn update_table(
         &self,
         input: UpdateTableInput,
     ) -> Result<UpdateTableOutput, RusotoError<UpdateTableError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.update_table(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "update_table", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.update_table(input).await }
+        })
     }
 
     async fn update_table_replica_auto_scaling(
@@ -760,17 +682,11 @@ where
         &self,
         input: UpdateTimeToLiveInput,
     ) -> Result<UpdateTimeToLiveOutput, RusotoError<UpdateTimeToLiveError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.update_time_to_live(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "update_time_to_live", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.update_time_to_live(input).await }
+        })
     }
 
     async fn describe_endpoints(
@@ -784,34 +700,22 @@ where
         &self,
         input: TransactGetItemsInput,
     ) -> Result<TransactGetItemsOutput, RusotoError<TransactGetItemsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.transact_get_items(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "transact_get_items", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.transact_get_items(input).await }
+        })
     }
 
     async fn transact_write_items(
         &self,
         input: TransactWriteItemsInput,
     ) -> Result<TransactWriteItemsOutput, RusotoError<TransactWriteItemsError>> {
-        self.inner
-            .policy
-            .retry_if(
-                move || {
-                    let client = self.inner.clone().client.clone();
-                    let input = input.clone();
-                    async move { client.transact_write_items(input).await }
-                },
-                Counter(0),
-            )
-            .await
+        retried!(self, "transact_write_items", move || {
+            let client = self.inner.clone().client.clone();
+            let input = input.clone();
+            async move { client.transact_write_items(input).await }
+        })
     }
 
     async fn batch_execute_statement(
@@ -882,6 +786,174 @@ where
     }
 }
 
+/// Resubmits the `unprocessed_items` of a batch write's `output` until none remain,
+/// pausing between requests and giving up after the number of retries of the given `policy`
+///
+/// Requests which fail with a retryable error are retried in the same way.
+/// The output of the final request is returned, whose `unprocessed_items` are those
+/// which could not be written should the policy's retries be exhausted.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use dynomite::{
+///     dynamodb::{BatchWriteItemInput, DynamoDb, DynamoDbClient},
+///     retry::{drain_unprocessed_writes, Policy},
+/// };
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = DynamoDbClient::new(Default::default());
+/// let output = client.batch_write_item(BatchWriteItemInput::default()).await?;
+/// let output = drain_unprocessed_writes(&client, output, Policy::default()).await?;
+/// assert!(output.unprocessed_items.unwrap_or_default().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn drain_unprocessed_writes<D>(
+    client: &D,
+    output: BatchWriteItemOutput,
+    policy: impl Into<RetryPolicy>,
+) -> Result<BatchWriteItemOutput, RusotoError<BatchWriteItemError>>
+where
+    D: DynamoDb,
+{
+    if !has_unprocessed_items(&output) {
+        return Ok(output);
+    }
+    let last = Arc::new(Mutex::new(output));
+    let result = policy
+        .into()
+        .retry_if(
+            || {
+                let last = last.clone();
+                async move {
+                    let request_items = lock(&last).unprocessed_items.clone().unwrap_or_default();
+                    let output = client
+                        .batch_write_item(BatchWriteItemInput {
+                            request_items,
+                            ..BatchWriteItemInput::default()
+                        })
+                        .await
+                        .map_err(Drain::Failed)?;
+                    let unprocessed = has_unprocessed_items(&output);
+                    *lock(&last) = output;
+                    if unprocessed {
+                        Err(Drain::Unprocessed)
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+            Counter(0),
+        )
+        .await;
+    match result {
+        Err(Drain::Failed(err)) => Err(err),
+        Ok(()) | Err(Drain::Unprocessed) => Ok(std::mem::take(&mut *lock(&last))),
+    }
+}
+
+fn has_unprocessed_items(output: &BatchWriteItemOutput) -> bool {
+    output
+        .unprocessed_items
+        .as_ref()
+        .is_some_and(|items| items.values().any(|requests| !requests.is_empty()))
+}
+
+/// Resubmits the `unprocessed_keys` of a batch get's `output` until none remain,
+/// pausing between requests and giving up after the number of retries of the given `policy`
+///
+/// Requests which fail with a retryable error are retried in the same way.
+/// The `responses` of the returned output include the items of `output` and of every
+/// request made, and its `unprocessed_keys` are those which could not be read should
+/// the policy's retries be exhausted.
+pub async fn drain_unprocessed_keys<D>(
+    client: &D,
+    output: BatchGetItemOutput,
+    policy: impl Into<RetryPolicy>,
+) -> Result<BatchGetItemOutput, RusotoError<BatchGetItemError>>
+where
+    D: DynamoDb,
+{
+    if !has_unprocessed_keys(&output) {
+        return Ok(output);
+    }
+    let last = Arc::new(Mutex::new(output));
+    let result = policy
+        .into()
+        .retry_if(
+            || {
+                let last = last.clone();
+                async move {
+                    let request_items = lock(&last).unprocessed_keys.clone().unwrap_or_default();
+                    let output = client
+                        .batch_get_item(BatchGetItemInput {
+                            request_items,
+                            ..BatchGetItemInput::default()
+                        })
+                        .await
+                        .map_err(Drain::Failed)?;
+                    let unprocessed = has_unprocessed_keys(&output);
+                    let mut drained = lock(&last);
+                    let responses = drained.responses.get_or_insert_with(HashMap::new);
+                    for (table_name, items) in output.responses.unwrap_or_default() {
+                        responses.entry(table_name).or_default().extend(items);
+                    }
+                    drained.unprocessed_keys = output.unprocessed_keys;
+                    if unprocessed {
+                        Err(Drain::Unprocessed)
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+            Counter(0),
+        )
+        .await;
+    match result {
+        Err(Drain::Failed(err)) => Err(err),
+        Ok(()) | Err(Drain::Unprocessed) => Ok(std::mem::take(&mut *lock(&last))),
+    }
+}
+
+fn has_unprocessed_keys(output: &BatchGetItemOutput) -> bool {
+    output
+        .unprocessed_keys
+        .as_ref()
+        .is_some_and(|keys| keys.values().any(|keys| !keys.keys.is_empty()))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // the lock is never held across an await or a panic, so poisoning isn't expected
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Reasons a request draining unprocessed items or keys may need to be retried
+#[derive(Debug)]
+enum Drain<E> {
+    /// Some items or keys remain unprocessed
+    Unprocessed,
+    /// The request failed
+    Failed(RusotoError<E>),
+}
+
+impl<E> Condition<Drain<E>> for Counter
+where
+    E: Retry,
+{
+    fn is_retryable(
+        &mut self,
+        error: &Drain<E>,
+    ) -> bool {
+        match error {
+            Drain::Unprocessed => true,
+            Drain::Failed(err) => Condition::<RusotoError<_>>::is_retryable(self, err),
+        }
+    }
+}
+
 /// retry impl for Service error types
 macro_rules! retry {
     ($e:ty, $($p: pat)+) => {
@@ -1074,6 +1146,10 @@ retry!(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{testing::MockDynamoDb, Attribute};
+    use futures::executor::block_on;
+    use std::collections::HashMap;
+
     #[test]
     fn policy_has_default() {
         assert_eq!(
@@ -1087,4 +1163,367 @@ mod tests {
         fn test(_: impl Into<RetryPolicy>) {}
         test(Policy::default())
     }
+
+    #[test]
+    fn policy_without_jitter_pauses_for_configured_duration() {
+        let pause = Duration::from_millis(20);
+        let client = MockDynamoDb::default();
+        for _ in 0..2 {
+            client.get_item.respond(Err(RusotoError::Service(
+                GetItemError::InternalServerError("oops".into()),
+            )));
+        }
+        let started = std::time::Instant::now();
+        let result = block_on(
+            client
+                .clone()
+                .with_retries(Policy::Pause(2, pause).without_jitter())
+                .get_item(GetItemInput::default()),
+        );
+        assert!(result.is_ok());
+        // jitter would otherwise scale each pause down by a random factor
+        assert!(started.elapsed() >= pause * 2);
+    }
+
+    #[test]
+    fn retrying_client_retries_paginated_requests() {
+        use crate::DynamoDbExt;
+        use futures::TryStreamExt;
+
+        let client = MockDynamoDb::default();
+        let throttled = || {
+            Err(RusotoError::Service(
+                ScanError::ProvisionedThroughputExceeded("slow down".into()),
+            ))
+        };
+        let mut key = HashMap::new();
+        key.insert("id".to_string(), "1".to_string().into_attr());
+        client
+            .scan
+            .respond(throttled())
+            .respond(Ok(ScanOutput {
+                items: Some(vec![key.clone()]),
+                last_evaluated_key: Some(key.clone()),
+                ..ScanOutput::default()
+            }))
+            .respond(throttled())
+            .respond(Ok(ScanOutput {
+                items: Some(vec![key]),
+                ..ScanOutput::default()
+            }));
+        let items = block_on(
+            client
+                .clone()
+                .with_retries(Policy::Pause(1, Duration::from_millis(1)))
+                .scan_pages(ScanInput::default())
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("throttled pages to be retried");
+        assert_eq!(items.len(), 2);
+        assert_eq!(client.scan.inputs().len(), 4);
+    }
+
+    #[test]
+    fn retrying_client_retries_retryable_errors() {
+        let client = MockDynamoDb::default();
+        client
+            .get_item
+            .respond(Err(RusotoError::Service(
+                GetItemError::ProvisionedThroughputExceeded("slow down".into()),
+            )))
+            .respond(Err(RusotoError::Service(
+                GetItemError::InternalServerError("oops".into()),
+            )));
+        let result = block_on(
+            client
+                .clone()
+                .with_retries(Policy::Pause(2, Duration::from_millis(1)))
+                .get_item(GetItemInput::default()),
+        );
+        assert!(result.is_ok());
+        assert_eq!(client.get_item.inputs().len(), 3);
+    }
+
+    #[test]
+    fn retrying_client_gives_up_on_other_errors() {
+        let client = MockDynamoDb::default();
+        client
+            .get_item
+            .respond(Err(RusotoError::Service(GetItemError::ResourceNotFound(
+                "no such table".into(),
+            ))));
+        let result = block_on(
+            client
+                .clone()
+                .with_retries(Policy::Pause(2, Duration::from_millis(1)))
+                .get_item(GetItemInput::default()),
+        );
+        assert!(matches!(
+            result,
+            Err(RusotoError::Service(GetItemError::ResourceNotFound(_)))
+        ));
+        assert_eq!(client.get_item.inputs().len(), 1);
+    }
+
+    fn unprocessed(ids: &[&str]) -> Option<HashMap<String, Vec<WriteRequest>>> {
+        Some(maplit::hashmap! {
+            "books".to_string() => ids
+                .iter()
+                .map(|id| WriteRequest {
+                    delete_request: Some(DeleteRequest {
+                        key: crate::attr_map! { "id" => id.to_string() },
+                    }),
+                    ..WriteRequest::default()
+                })
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn drain_unprocessed_writes_resubmits_until_drained() {
+        let client = MockDynamoDb::default();
+        client
+            .batch_write_item
+            .respond(Ok(BatchWriteItemOutput {
+                unprocessed_items: unprocessed(&["b"]),
+                ..BatchWriteItemOutput::default()
+            }))
+            .respond(Ok(BatchWriteItemOutput {
+                unprocessed_items: unprocessed(&["b"]),
+                ..BatchWriteItemOutput::default()
+            }));
+        let output = BatchWriteItemOutput {
+            unprocessed_items: unprocessed(&["a", "b"]),
+            ..BatchWriteItemOutput::default()
+        };
+        let output = block_on(drain_unprocessed_writes(
+            &client,
+            output,
+            Policy::Pause(3, Duration::from_millis(1)),
+        ))
+        .expect("unprocessed items to be drained");
+        assert_eq!(output, BatchWriteItemOutput::default());
+        let requested = client
+            .batch_write_item
+            .inputs()
+            .into_iter()
+            .map(|input| Some(input.request_items))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requested,
+            vec![
+                unprocessed(&["a", "b"]),
+                unprocessed(&["b"]),
+                unprocessed(&["b"])
+            ]
+        );
+    }
+
+    #[test]
+    fn drain_unprocessed_writes_gives_up_after_retries() {
+        let client = MockDynamoDb::default();
+        for _ in 0..3 {
+            client.batch_write_item.respond(Ok(BatchWriteItemOutput {
+                unprocessed_items: unprocessed(&["a"]),
+                ..BatchWriteItemOutput::default()
+            }));
+        }
+        let output = BatchWriteItemOutput {
+            unprocessed_items: unprocessed(&["a"]),
+            ..BatchWriteItemOutput::default()
+        };
+        let output = block_on(drain_unprocessed_writes(
+            &client,
+            output,
+            Policy::Pause(1, Duration::from_millis(1)),
+        ))
+        .unwrap();
+        assert_eq!(output.unprocessed_items, unprocessed(&["a"]));
+        assert_eq!(client.batch_write_item.inputs().len(), 2);
+    }
+
+    #[test]
+    fn drain_unprocessed_keys_collects_responses() {
+        let key = |id: &str| crate::attr_map! { "id" => id.to_string() };
+        let batch = |ids: &[&str]| {
+            maplit::hashmap! {
+                "books".to_string() => ids.iter().map(|id| key(id)).collect::<Vec<_>>(),
+            }
+        };
+        let unprocessed = |ids: &[&str]| {
+            Some(maplit::hashmap! {
+                "books".to_string() => KeysAndAttributes {
+                    keys: ids.iter().map(|id| key(id)).collect(),
+                    ..KeysAndAttributes::default()
+                },
+            })
+        };
+        let client = MockDynamoDb::default();
+        client
+            .batch_get_item
+            .respond(Ok(BatchGetItemOutput {
+                responses: Some(batch(&["b"])),
+                unprocessed_keys: unprocessed(&["c"]),
+                ..BatchGetItemOutput::default()
+            }))
+            .respond(Ok(BatchGetItemOutput {
+                responses: Some(batch(&["c"])),
+                ..BatchGetItemOutput::default()
+            }));
+        let output = BatchGetItemOutput {
+            responses: Some(batch(&["a"])),
+            unprocessed_keys: unprocessed(&["b", "c"]),
+            ..BatchGetItemOutput::default()
+        };
+        let output = block_on(drain_unprocessed_keys(
+            &client,
+            output,
+            Policy::Pause(3, Duration::from_millis(1)),
+        ))
+        .expect("unprocessed keys to be drained");
+        assert_eq!(output.responses, Some(batch(&["a", "b", "c"])));
+        assert_eq!(output.unprocessed_keys, None);
+        assert_eq!(
+            client
+                .batch_get_item
+                .inputs()
+                .into_iter()
+                .map(|input| Some(input.request_items))
+                .collect::<Vec<_>>(),
+            vec![unprocessed(&["b", "c"]), unprocessed(&["c"])]
+        );
+    }
+
+    #[test]
+    fn drain_unprocessed_writes_without_unprocessed_items() {
+        let client = MockDynamoDb::default();
+        let output = block_on(drain_unprocessed_writes(
+            &client,
+            BatchWriteItemOutput::default(),
+            Policy::default(),
+        ))
+        .unwrap();
+        assert_eq!(output, BatchWriteItemOutput::default());
+        assert!(client.batch_write_item.inputs().is_empty());
+    }
+
+    #[cfg(feature = "tracing")]
+    mod tracing_spans {
+        use super::*;
+        use std::{
+            collections::BTreeMap,
+            fmt::Debug,
+            sync::{Arc, Mutex},
+        };
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Id, Metadata, Subscriber,
+        };
+
+        /// The names and recorded fields of the spans created
+        type Spans = Arc<Mutex<Vec<(&'static str, BTreeMap<&'static str, String>)>>>;
+
+        /// A subscriber which collects spans, identified by their index + 1
+        #[derive(Default)]
+        struct Collector(Spans);
+
+        struct Fields<'a>(&'a mut BTreeMap<&'static str, String>);
+
+        impl Visit for Fields<'_> {
+            fn record_str(
+                &mut self,
+                field: &Field,
+                value: &str,
+            ) {
+                self.0.insert(field.name(), value.into());
+            }
+
+            fn record_debug(
+                &mut self,
+                field: &Field,
+                value: &dyn Debug,
+            ) {
+                self.0.insert(field.name(), format!("{:?}", value));
+            }
+        }
+
+        impl Subscriber for Collector {
+            fn enabled(
+                &self,
+                _: &Metadata<'_>,
+            ) -> bool {
+                true
+            }
+
+            fn new_span(
+                &self,
+                attrs: &span::Attributes<'_>,
+            ) -> Id {
+                let mut spans = self.0.lock().unwrap();
+                let mut fields = BTreeMap::new();
+                attrs.record(&mut Fields(&mut fields));
+                spans.push((attrs.metadata().name(), fields));
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(
+                &self,
+                id: &Id,
+                values: &span::Record<'_>,
+            ) {
+                let mut spans = self.0.lock().unwrap();
+                let (_, fields) = &mut spans[id.into_u64() as usize - 1];
+                values.record(&mut Fields(fields));
+            }
+
+            fn record_follows_from(
+                &self,
+                _: &Id,
+                _: &Id,
+            ) {
+            }
+
+            fn event(
+                &self,
+                _: &Event<'_>,
+            ) {
+            }
+
+            fn enter(
+                &self,
+                _: &Id,
+            ) {
+            }
+
+            fn exit(
+                &self,
+                _: &Id,
+            ) {
+            }
+        }
+
+        #[test]
+        fn retried_operations_record_attempts_and_outcome() {
+            let client = MockDynamoDb::default();
+            client.get_item.respond(Err(RusotoError::Service(
+                GetItemError::InternalServerError("oops".into()),
+            )));
+            let spans = Spans::default();
+            let result = tracing::subscriber::with_default(Collector(spans.clone()), || {
+                block_on(
+                    client
+                        .clone()
+                        .with_retries(Policy::Pause(2, Duration::from_millis(1)))
+                        .get_item(GetItemInput::default()),
+                )
+            });
+            assert!(result.is_ok());
+            let spans = spans.lock().unwrap();
+            assert_eq!(spans.len(), 1);
+            let (name, fields) = &spans[0];
+            assert_eq!(*name, "get_item");
+            assert_eq!(fields.get("attempts").map(String::as_str), Some("2"));
+            assert_eq!(fields.get("outcome").map(String::as_str), Some("ok"));
+        }
+    }
 }
@@ -0,0 +1,143 @@
+//! Estimation of the size DynamoDB counts for items against its 400KB item size limit
+//!
+//! Sizes are estimated from the [rules DynamoDB documents](https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/CapacityUnitCalculations.html)
+//! for calculating item sizes, which makes it possible to catch oversized items before
+//! sending them rather than as an API error.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{attr_map, size};
+//!
+//! let attrs = attr_map! { "id" => "abc".to_string() };
+//! // 2 bytes for the name, 3 for the value
+//! assert_eq!(size::item_size(&attrs), 5);
+//! ```
+
+use crate::{AttributeError, AttributeValue, Attributes, IntoAttributes};
+
+/// The maximum size of a DynamoDB item, including attribute names, in bytes
+pub const MAX_ITEM_SIZE: usize = 400 * 1024;
+
+/// Returns the estimated size of an item, the sum of the lengths of its attribute
+/// names and the sizes of their values
+pub fn item_size(attrs: &Attributes) -> usize {
+    attrs
+        .iter()
+        .map(|(name, value)| name.len() + value_size(value))
+        .sum()
+}
+
+/// Returns the estimated size of an attribute value, excluding its name
+pub fn value_size(value: &AttributeValue) -> usize {
+    if let Some(s) = &value.s {
+        s.len()
+    } else if let Some(n) = &value.n {
+        number_size(n)
+    } else if let Some(b) = &value.b {
+        b.len()
+    } else if value.bool.is_some() || value.null.is_some() {
+        1
+    } else if let Some(ss) = &value.ss {
+        ss.iter().map(String::len).sum()
+    } else if let Some(ns) = &value.ns {
+        ns.iter().map(|n| number_size(n)).sum()
+    } else if let Some(bs) = &value.bs {
+        bs.iter().map(|b| b.len()).sum()
+    } else if let Some(l) = &value.l {
+        // 3 bytes for the list itself and 1 for each element
+        3 + l.iter().map(|value| 1 + value_size(value)).sum::<usize>()
+    } else if let Some(m) = &value.m {
+        // 3 bytes for the map itself and 1 for each entry
+        3 + m
+            .iter()
+            .map(|(name, value)| 1 + name.len() + value_size(value))
+            .sum::<usize>()
+    } else {
+        0
+    }
+}
+
+/// Numbers take 1 byte per 2 significant digits, plus 1 byte
+fn number_size(n: &str) -> usize {
+    let digits = n
+        .split(&['e', 'E'][..])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    let significant = digits.trim_start_matches('0').trim_end_matches('0').len();
+    significant.div_ceil(2) + 1
+}
+
+/// Converts `item` into `Attributes`, failing with `AttributeError::ItemTooLarge`
+/// when its estimated size exceeds DynamoDB's 400KB item size limit
+///
+/// # Examples
+///
+/// ```
+/// use dynomite::{to_attrs_checked, Attributes};
+///
+/// #[derive(Attributes)]
+/// struct Upload {
+///     id: String,
+///     data: Vec<u8>,
+/// }
+///
+/// let upload = Upload {
+///     id: "abc".into(),
+///     data: vec![0; 1024 * 1024],
+/// };
+/// assert!(to_attrs_checked(upload).is_err());
+/// ```
+pub fn to_attrs_checked<T: IntoAttributes>(item: T) -> Result<Attributes, AttributeError> {
+    let mut attrs = Attributes::new();
+    item.into_attrs(&mut attrs);
+    match item_size(&attrs) {
+        size if size > MAX_ITEM_SIZE => Err(AttributeError::ItemTooLarge { size }),
+        _ => Ok(attrs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attribute;
+    use bytes::Bytes;
+    use maplit::hashmap;
+
+    #[test]
+    fn value_sizes() {
+        assert_eq!(value_size(&"abc".to_string().into_attr()), 3);
+        assert_eq!(value_size(&true.into_attr()), 1);
+        assert_eq!(value_size(&Bytes::from("abcd").into_attr()), 4);
+        assert_eq!(value_size(&1234.into_attr()), 3);
+        assert_eq!(value_size(&0.00012.into_attr()), 2);
+        assert_eq!(value_size(&vec![1, 2].into_attr()), 3 + 2 * 3);
+        assert_eq!(
+            value_size(&hashmap! { "a".to_string() => 1 }.into_attr()),
+            3 + 1 + 1 + 2
+        );
+    }
+
+    #[test]
+    fn to_attrs_checked_accepts_items_within_limit() {
+        let data = Bytes::from(vec![0; 1024]);
+        assert_eq!(
+            to_attrs_checked(hashmap! { "data".to_string() => data.clone() }),
+            Ok(hashmap! { "data".to_string() => data.into_attr() })
+        );
+    }
+
+    #[test]
+    fn to_attrs_checked_rejects_oversized_items() {
+        let item = hashmap! { "data".to_string() => Bytes::from(vec![0; MAX_ITEM_SIZE]) };
+        assert_eq!(
+            to_attrs_checked(item),
+            Err(AttributeError::ItemTooLarge {
+                size: MAX_ITEM_SIZE + 4
+            })
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Helpers for asserting on attribute values in tests
+//!
+//! [`AttributeValue`](../dynamodb/struct.AttributeValue.html) is a struct with a field for every DynamoDB type,
+//! so its derived `Debug` output, and so any `assert_eq!` failure involving [`Attributes`](../type.Attributes.html),
+//! is mostly made up of `None`s. Wrapping values in [`Av`](struct.Av.html) renders only the fields that are
+//! set, which pairs well with crates like [`pretty_assertions`](https://crates.io/crates/pretty_assertions).
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{
+//!     test::{av_map, Av},
+//!     Attribute, Attributes,
+//! };
+//!
+//! #[derive(Attributes)]
+//! struct Book {
+//!     title: String,
+//!     pages: u32,
+//! }
+//!
+//! let attrs: Attributes = Book {
+//!     title: "rust".into(),
+//!     pages: 42,
+//! }
+//! .into();
+//! assert_eq!(
+//!     format!("{:?}", av_map(attrs)),
+//!     r#"{"pages": N("42"), "title": S("rust")}"#
+//! );
+//! assert_eq!(Av(42.into_attr()), Av(42.into_attr()));
+//! ```
+use crate::{AttributeValue, Attributes};
+use bytes::Bytes;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// An `AttributeValue` with a concise `Debug` representation
+///
+/// Only the fields which are set are rendered, using their DynamoDB type names, e.g. `S("foo")`,
+/// `N("42")` or `NULL`. Equality likewise only considers the fields which are set, treating
+/// `NULL: false` as unset and comparing `SS`, `NS` and `BS` sets without regard to order.
+#[derive(Clone)]
+pub struct Av(pub AttributeValue);
+
+/// Wraps each value of a map of attributes in an [`Av`](struct.Av.html), ordered by name
+pub fn av_map(attrs: Attributes) -> BTreeMap<String, Av> {
+    attrs
+        .into_iter()
+        .map(|(name, value)| (name, Av(value)))
+        .collect()
+}
+
+impl From<AttributeValue> for Av {
+    fn from(value: AttributeValue) -> Self {
+        Av(value)
+    }
+}
+
+/// The fields of an `AttributeValue` which are relevant for comparison and display
+#[derive(PartialEq)]
+struct Repr<'a> {
+    s: Option<&'a String>,
+    n: Option<&'a String>,
+    b: Option<&'a Bytes>,
+    bool: Option<bool>,
+    null: bool,
+    ss: Option<BTreeSet<&'a String>>,
+    ns: Option<BTreeSet<&'a String>>,
+    bs: Option<BTreeSet<&'a Bytes>>,
+    l: Option<Vec<Av>>,
+    m: Option<BTreeMap<&'a String, Av>>,
+}
+
+impl Av {
+    fn repr(&self) -> Repr<'_> {
+        let AttributeValue {
+            b,
+            bool,
+            bs,
+            l,
+            m,
+            n,
+            ns,
+            null,
+            s,
+            ss,
+        } = &self.0;
+        Repr {
+            s: s.as_ref(),
+            n: n.as_ref(),
+            b: b.as_ref(),
+            bool: *bool,
+            null: null.unwrap_or_default(),
+            ss: ss.as_ref().map(|ss| ss.iter().collect()),
+            ns: ns.as_ref().map(|ns| ns.iter().collect()),
+            bs: bs.as_ref().map(|bs| bs.iter().collect()),
+            l: l.as_ref().map(|l| l.iter().cloned().map(Av).collect()),
+            m: m.as_ref().map(|m| {
+                m.iter()
+                    .map(|(name, value)| (name, Av(value.clone())))
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl PartialEq for Av {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.repr() == other.repr()
+    }
+}
+
+impl fmt::Debug for Av {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let Repr {
+            s,
+            n,
+            b,
+            bool,
+            null,
+            ss,
+            ns,
+            bs,
+            l,
+            m,
+        } = self.repr();
+        let mut types: Vec<(&str, Option<&dyn fmt::Debug>)> = Vec::new();
+        if let Some(s) = &s {
+            types.push(("S", Some(s)));
+        }
+        if let Some(n) = &n {
+            types.push(("N", Some(n)));
+        }
+        if let Some(b) = &b {
+            types.push(("B", Some(b)));
+        }
+        if let Some(bool) = &bool {
+            types.push(("BOOL", Some(bool)));
+        }
+        if null {
+            types.push(("NULL", None));
+        }
+        if let Some(ss) = &ss {
+            types.push(("SS", Some(ss)));
+        }
+        if let Some(ns) = &ns {
+            types.push(("NS", Some(ns)));
+        }
+        if let Some(bs) = &bs {
+            types.push(("BS", Some(bs)));
+        }
+        if let Some(l) = &l {
+            types.push(("L", Some(l)));
+        }
+        if let Some(m) = &m {
+            types.push(("M", Some(m)));
+        }
+        if types.is_empty() {
+            return f.write_str("Empty");
+        }
+        for (index, (name, value)) in types.into_iter().enumerate() {
+            if index > 0 {
+                f.write_str(" | ")?;
+            }
+            match value {
+                Some(value) => f.debug_tuple(name).field(value).finish()?,
+                None => f.write_str(name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attribute;
+    use maplit::hashmap;
+
+    #[test]
+    fn debug_renders_only_set_fields() {
+        let attrs = hashmap! {
+            "name".to_string() => "foo".to_string().into_attr(),
+            "count".to_string() => 1.into_attr(),
+            "missing".to_string() => None::<String>.into_attr(),
+            "tags".to_string() => vec![true].into_attr(),
+            "empty".to_string() => AttributeValue::default(),
+        };
+        assert_eq!(
+            format!("{:?}", av_map(attrs)),
+            r#"{"count": N("1"), "empty": Empty, "missing": NULL, "name": S("foo"), "tags": L([BOOL(true)])}"#
+        );
+    }
+
+    #[test]
+    fn eq_ignores_unset_fields_and_set_order() {
+        let null = AttributeValue {
+            null: Some(false),
+            s: Some("foo".into()),
+            ..AttributeValue::default()
+        };
+        assert_eq!(Av(null), Av("foo".to_string().into_attr()));
+        let ss = |values: &[&str]| AttributeValue {
+            ss: Some(values.iter().map(|value| value.to_string()).collect()),
+            ..AttributeValue::default()
+        };
+        assert_eq!(Av(ss(&["a", "b"])), Av(ss(&["b", "a"])));
+        assert_ne!(Av(ss(&["a", "b"])), Av(ss(&["a"])));
+    }
+
+    #[test]
+    fn failure_diff_is_readable() {
+        let left = av_map(hashmap! { "name".to_string() => "foo".to_string().into_attr() });
+        let right = av_map(hashmap! { "name".to_string() => "bar".to_string().into_attr() });
+        let message = std::panic::catch_unwind(|| assert_eq!(left, right))
+            .expect_err("values differ")
+            .downcast::<String>()
+            .expect("formatted panic message");
+        assert!(message.contains(r#"{"name": S("foo")}"#));
+        assert!(message.contains(r#"{"name": S("bar")}"#));
+        assert!(!message.contains("None"));
+    }
+}
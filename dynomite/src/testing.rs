@@ -0,0 +1,231 @@
+//! A scriptable `DynamoDb` for unit testing code which depends on a DynamoDB client
+//!
+//! Enabled with the `testing` feature, [`MockDynamoDb`](struct.MockDynamoDb.html) implements
+//! every `DynamoDb` operation by returning the next result queued for that operation, or the operation's
+//! default output when none are queued, and records the input of each request it receives.
+//!
+//! For tests which rely on items actually being stored, the [`memory`](memory/index.html) module
+//! provides an in-memory table.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{
+//!     dynamodb::{DynamoDb, GetItemError, GetItemInput, GetItemOutput},
+//!     testing::MockDynamoDb,
+//! };
+//! use rusoto_core::RusotoError;
+//!
+//! # futures::executor::block_on(async {
+//! let client = MockDynamoDb::default();
+//! client
+//!     .get_item
+//!     .respond(Err(RusotoError::Service(GetItemError::ResourceNotFound(
+//!         "no such table".into(),
+//!     ))));
+//! let input = GetItemInput {
+//!     table_name: "books".into(),
+//!     ..GetItemInput::default()
+//! };
+//! assert!(client.get_item(input.clone()).await.is_err());
+//! // with nothing left queued, requests succeed with a default output
+//! assert_eq!(
+//!     client.get_item(input.clone()).await.ok(),
+//!     Some(GetItemOutput::default())
+//! );
+//! assert_eq!(client.get_item.inputs(), vec![input.clone(), input]);
+//! # });
+//! ```
+
+/// Invokes `$callback!` with the signature of every `DynamoDb` operation, as `method(Input) -> Output, Error;`,
+/// or `method() -> Output, Error;` for operations without an input
+macro_rules! dynamodb_operations {
+    ($callback:ident) => {
+        $callback! {
+            batch_execute_statement(BatchExecuteStatementInput) -> BatchExecuteStatementOutput, BatchExecuteStatementError;
+            batch_get_item(BatchGetItemInput) -> BatchGetItemOutput, BatchGetItemError;
+            batch_write_item(BatchWriteItemInput) -> BatchWriteItemOutput, BatchWriteItemError;
+            create_backup(CreateBackupInput) -> CreateBackupOutput, CreateBackupError;
+            create_global_table(CreateGlobalTableInput) -> CreateGlobalTableOutput, CreateGlobalTableError;
+            create_table(CreateTableInput) -> CreateTableOutput, CreateTableError;
+            delete_backup(DeleteBackupInput) -> DeleteBackupOutput, DeleteBackupError;
+            delete_item(DeleteItemInput) -> DeleteItemOutput, DeleteItemError;
+            delete_table(DeleteTableInput) -> DeleteTableOutput, DeleteTableError;
+            describe_backup(DescribeBackupInput) -> DescribeBackupOutput, DescribeBackupError;
+            describe_continuous_backups(DescribeContinuousBackupsInput) -> DescribeContinuousBackupsOutput, DescribeContinuousBackupsError;
+            describe_contributor_insights(DescribeContributorInsightsInput) -> DescribeContributorInsightsOutput, DescribeContributorInsightsError;
+            describe_endpoints() -> DescribeEndpointsResponse, DescribeEndpointsError;
+            describe_export(DescribeExportInput) -> DescribeExportOutput, DescribeExportError;
+            describe_global_table(DescribeGlobalTableInput) -> DescribeGlobalTableOutput, DescribeGlobalTableError;
+            describe_global_table_settings(DescribeGlobalTableSettingsInput) -> DescribeGlobalTableSettingsOutput, DescribeGlobalTableSettingsError;
+            describe_kinesis_streaming_destination(DescribeKinesisStreamingDestinationInput) -> DescribeKinesisStreamingDestinationOutput, DescribeKinesisStreamingDestinationError;
+            describe_limits() -> DescribeLimitsOutput, DescribeLimitsError;
+            describe_table(DescribeTableInput) -> DescribeTableOutput, DescribeTableError;
+            describe_table_replica_auto_scaling(DescribeTableReplicaAutoScalingInput) -> DescribeTableReplicaAutoScalingOutput, DescribeTableReplicaAutoScalingError;
+            describe_time_to_live(DescribeTimeToLiveInput) -> DescribeTimeToLiveOutput, DescribeTimeToLiveError;
+            disable_kinesis_streaming_destination(KinesisStreamingDestinationInput) -> KinesisStreamingDestinationOutput, DisableKinesisStreamingDestinationError;
+            enable_kinesis_streaming_destination(KinesisStreamingDestinationInput) -> KinesisStreamingDestinationOutput, EnableKinesisStreamingDestinationError;
+            execute_statement(ExecuteStatementInput) -> ExecuteStatementOutput, ExecuteStatementError;
+            execute_transaction(ExecuteTransactionInput) -> ExecuteTransactionOutput, ExecuteTransactionError;
+            export_table_to_point_in_time(ExportTableToPointInTimeInput) -> ExportTableToPointInTimeOutput, ExportTableToPointInTimeError;
+            get_item(GetItemInput) -> GetItemOutput, GetItemError;
+            list_backups(ListBackupsInput) -> ListBackupsOutput, ListBackupsError;
+            list_contributor_insights(ListContributorInsightsInput) -> ListContributorInsightsOutput, ListContributorInsightsError;
+            list_exports(ListExportsInput) -> ListExportsOutput, ListExportsError;
+            list_global_tables(ListGlobalTablesInput) -> ListGlobalTablesOutput, ListGlobalTablesError;
+            list_tables(ListTablesInput) -> ListTablesOutput, ListTablesError;
+            list_tags_of_resource(ListTagsOfResourceInput) -> ListTagsOfResourceOutput, ListTagsOfResourceError;
+            put_item(PutItemInput) -> PutItemOutput, PutItemError;
+            query(QueryInput) -> QueryOutput, QueryError;
+            restore_table_from_backup(RestoreTableFromBackupInput) -> RestoreTableFromBackupOutput, RestoreTableFromBackupError;
+            restore_table_to_point_in_time(RestoreTableToPointInTimeInput) -> RestoreTableToPointInTimeOutput, RestoreTableToPointInTimeError;
+            scan(ScanInput) -> ScanOutput, ScanError;
+            tag_resource(TagResourceInput) -> (), TagResourceError;
+            transact_get_items(TransactGetItemsInput) -> TransactGetItemsOutput, TransactGetItemsError;
+            transact_write_items(TransactWriteItemsInput) -> TransactWriteItemsOutput, TransactWriteItemsError;
+            untag_resource(UntagResourceInput) -> (), UntagResourceError;
+            update_continuous_backups(UpdateContinuousBackupsInput) -> UpdateContinuousBackupsOutput, UpdateContinuousBackupsError;
+            update_contributor_insights(UpdateContributorInsightsInput) -> UpdateContributorInsightsOutput, UpdateContributorInsightsError;
+            update_global_table(UpdateGlobalTableInput) -> UpdateGlobalTableOutput, UpdateGlobalTableError;
+            update_global_table_settings(UpdateGlobalTableSettingsInput) -> UpdateGlobalTableSettingsOutput, UpdateGlobalTableSettingsError;
+            update_item(UpdateItemInput) -> UpdateItemOutput, UpdateItemError;
+            update_table(UpdateTableInput) -> UpdateTableOutput, UpdateTableError;
+            update_table_replica_auto_scaling(UpdateTableReplicaAutoScalingInput) -> UpdateTableReplicaAutoScalingOutput, UpdateTableReplicaAutoScalingError;
+            update_time_to_live(UpdateTimeToLiveInput) -> UpdateTimeToLiveOutput, UpdateTimeToLiveError;
+        }
+    };
+}
+
+pub mod memory;
+
+use crate::dynamodb::*;
+use rusoto_core::RusotoError;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// The scripted results of, and inputs received by, a single `DynamoDb` operation
+///
+/// Clones share the same script, so results may be queued and inputs inspected
+/// through any clone of a `MockDynamoDb`
+pub struct Script<I, O, E> {
+    state: Arc<Mutex<State<I, O, E>>>,
+}
+
+struct State<I, O, E> {
+    results: VecDeque<Result<O, RusotoError<E>>>,
+    inputs: Vec<I>,
+}
+
+impl<I, O, E> Script<I, O, E> {
+    /// Queues a result to be returned by a future request, in the order queued
+    pub fn respond(
+        &self,
+        result: Result<O, RusotoError<E>>,
+    ) -> &Self {
+        self.lock().results.push_back(result);
+        self
+    }
+
+    /// Returns the inputs of the requests received so far, in the order received
+    pub fn inputs(&self) -> Vec<I>
+    where
+        I: Clone,
+    {
+        self.lock().inputs.clone()
+    }
+
+    /// Records a request's `input`, returning the next queued result or a default output
+    fn call(
+        &self,
+        input: I,
+    ) -> Result<O, RusotoError<E>>
+    where
+        O: Default,
+    {
+        let mut state = self.lock();
+        state.inputs.push(input);
+        state
+            .results
+            .pop_front()
+            .unwrap_or_else(|| Ok(O::default()))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State<I, O, E>> {
+        // a panicking test may poison the lock, which shouldn't obscure the original failure elsewhere
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<I, O, E> Clone for Script<I, O, E> {
+    fn clone(&self) -> Self {
+        Script {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<I, O, E> Default for Script<I, O, E> {
+    fn default() -> Self {
+        Script {
+            state: Arc::new(Mutex::new(State {
+                results: VecDeque::new(),
+                inputs: Vec::new(),
+            })),
+        }
+    }
+}
+
+/// The input type of an operation, `()` for operations without one
+macro_rules! input_type {
+    () => {
+        ()
+    };
+    ($input:ty) => {
+        $input
+    };
+}
+
+/// The input value of an operation, `()` for operations without one
+macro_rules! input_value {
+    () => {
+        ()
+    };
+    ($value:ident: $input:ident) => {
+        $value
+    };
+}
+
+macro_rules! mock_dynamodb {
+    ($($method:ident($($input:ident)?) -> $output:ty, $error:ty;)*) => {
+        /// A `DynamoDb` whose operations return scripted results
+        ///
+        /// Each operation has a public [`Script`](struct.Script.html) field of the same name, through
+        /// which results are queued and received inputs inspected. Operations without an input,
+        /// like `describe_limits`, record `()`
+        #[derive(Clone, Default)]
+        pub struct MockDynamoDb {
+            $(
+                #[doc = concat!("The script of `", stringify!($method), "` requests")]
+                pub $method: Script<input_type!($($input)?), $output, $error>,
+            )*
+        }
+
+        #[async_trait::async_trait]
+        impl DynamoDb for MockDynamoDb {
+            $(
+                async fn $method(
+                    &self,
+                    $(input: $input)?
+                ) -> Result<$output, RusotoError<$error>> {
+                    self.$method.call(input_value!($(input: $input)?))
+                }
+            )*
+        }
+    };
+}
+
+dynamodb_operations!(mock_dynamodb);
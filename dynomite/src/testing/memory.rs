@@ -0,0 +1,930 @@
+//! An in-memory `DynamoDb` for integration testing without a DynamoDB server
+//!
+//! [`InMemoryDynamoDb`](struct.InMemoryDynamoDb.html) stores the items of a single table, keyed by a partition
+//! key and an optional sort key, and supports the `put_item`, `get_item`, `delete_item`, `query` and `scan`
+//! operations. Other operations fail with a `RusotoError::Validation` error.
+//!
+//! Only a subset of DynamoDB's request parameters is supported
+//!
+//! * `key_condition_expression` for `query` must test the partition key with `=`, optionally followed by
+//!   `AND` and one test of the sort key with `=`, `<`, `<=`, `>`, `>=`, `BETWEEN :a AND :b` or `begins_with(sk, :prefix)`.
+//!   Names and values may be given directly or through `expression_attribute_names` and `expression_attribute_values`
+//! * `limit`, `exclusive_start_key` and, for `query`, `scan_index_forward` are honored
+//! * `return_values` of `ALL_OLD` is honored for `put_item` and `delete_item`
+//!
+//! Requests using any other expression, such as a `condition_expression`, `filter_expression` or `projection_expression`,
+//! or an `index_name`, fail with a `RusotoError::Validation` error rather than being silently ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{
+//!     attr_map,
+//!     dynamodb::{DynamoDb, GetItemInput, PutItemInput},
+//!     testing::memory::InMemoryDynamoDb,
+//! };
+//!
+//! # futures::executor::block_on(async {
+//! let client = InMemoryDynamoDb::new("books", "id");
+//! let book = attr_map! { "id" => "rust".to_string(), "pages" => 42 };
+//! client
+//!     .put_item(PutItemInput {
+//!         table_name: "books".into(),
+//!         item: book.clone(),
+//!         ..PutItemInput::default()
+//!     })
+//!     .await
+//!     .expect("item to be put");
+//! let found = client
+//!     .get_item(GetItemInput {
+//!         table_name: "books".into(),
+//!         key: attr_map! { "id" => "rust".to_string() },
+//!         ..GetItemInput::default()
+//!     })
+//!     .await
+//!     .expect("item to be got");
+//! assert_eq!(found.item, Some(book));
+//! # });
+//! ```
+
+use crate::{dynamodb::*, Attributes};
+use rusoto_core::RusotoError;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// A `DynamoDb` storing the items of a single table in memory
+///
+/// Clones share the same items
+#[derive(Clone)]
+pub struct InMemoryDynamoDb {
+    table: Arc<Table>,
+}
+
+struct Table {
+    name: String,
+    partition_key: String,
+    sort_key: Option<String>,
+    /// items by their serialized partition and sort key values
+    items: Mutex<BTreeMap<(String, String), Attributes>>,
+}
+
+impl InMemoryDynamoDb {
+    /// Returns a new, empty, table named `table_name` keyed by the `partition_key` attribute
+    pub fn new(
+        table_name: impl Into<String>,
+        partition_key: impl Into<String>,
+    ) -> Self {
+        InMemoryDynamoDb {
+            table: Arc::new(Table {
+                name: table_name.into(),
+                partition_key: partition_key.into(),
+                sort_key: None,
+                items: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Keys this table's items by the `sort_key` attribute in addition to their partition key
+    ///
+    /// This is intended to be called before any items are stored
+    pub fn with_sort_key(
+        self,
+        sort_key: impl Into<String>,
+    ) -> Self {
+        InMemoryDynamoDb {
+            table: Arc::new(Table {
+                name: self.table.name.clone(),
+                partition_key: self.table.partition_key.clone(),
+                sort_key: Some(sort_key.into()),
+                items: Mutex::default(),
+            }),
+        }
+    }
+
+    fn items(&self) -> MutexGuard<'_, BTreeMap<(String, String), Attributes>> {
+        self.table
+            .items
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn check_table<E>(
+        &self,
+        table_name: &str,
+    ) -> Result<(), RusotoError<E>> {
+        if table_name == self.table.name {
+            Ok(())
+        } else {
+            Err(RusotoError::Validation(format!(
+                "Requested resource not found: Table: {} not found",
+                table_name
+            )))
+        }
+    }
+
+    /// Returns the serialized partition and sort key values of an item or key
+    fn key_of<E>(
+        &self,
+        attrs: &Attributes,
+    ) -> Result<(String, String), RusotoError<E>> {
+        let value = |name: &str| {
+            attrs.get(name).and_then(serialize).ok_or_else(|| {
+                RusotoError::Validation(format!(
+                    "Missing the key {} in the item, or it is not a string, number or binary",
+                    name
+                ))
+            })
+        };
+        Ok((
+            value(&self.table.partition_key)?,
+            match &self.table.sort_key {
+                Some(sort_key) => value(sort_key)?,
+                None => String::new(),
+            },
+        ))
+    }
+
+    /// Returns only the key attributes of an item
+    fn key_attrs(
+        &self,
+        item: &Attributes,
+    ) -> Attributes {
+        std::iter::once(&self.table.partition_key)
+            .chain(self.table.sort_key.as_ref())
+            .filter_map(|name| item.get(name).map(|value| (name.clone(), value.clone())))
+            .collect()
+    }
+
+    /// Returns a page of `items`, which are in the given `order`, of up to `limit` items following
+    /// `exclusive_start_key`. Like DynamoDB, the key needn't be that of a stored item
+    fn page(
+        &self,
+        items: Vec<Attributes>,
+        exclusive_start_key: Option<Attributes>,
+        limit: Option<i64>,
+        order: impl Fn(&Attributes, &Attributes) -> Ordering,
+    ) -> (Vec<Attributes>, Option<Attributes>) {
+        let start = exclusive_start_key
+            .map(|start| {
+                items
+                    .iter()
+                    .position(|item| order(item, &start) == Ordering::Greater)
+                    .unwrap_or(items.len())
+            })
+            .unwrap_or_default();
+        let mut items = items.into_iter().skip(start).collect::<Vec<_>>();
+        match limit {
+            Some(limit) if limit >= 0 && (limit as usize) < items.len() => {
+                items.truncate(limit as usize);
+                let last_evaluated_key = items.last().map(|item| self.key_attrs(item));
+                (items, last_evaluated_key)
+            }
+            _ => (items, None),
+        }
+    }
+}
+
+/// Serializes a key value such that equal values serialize equally
+fn serialize(value: &AttributeValue) -> Option<String> {
+    if let Some(s) = &value.s {
+        Some(format!("S:{}", s))
+    } else if let Some(n) = &value.n {
+        Number::parse(n).map(|n| format!("N:{}", n))
+    } else {
+        value.b.as_ref().map(|b| format!("B:{}", base64::encode(b)))
+    }
+}
+
+/// Compares key values of the same type
+fn compare(
+    a: &AttributeValue,
+    b: &AttributeValue,
+) -> Option<Ordering> {
+    match (a, b) {
+        (AttributeValue { s: Some(a), .. }, AttributeValue { s: Some(b), .. }) => Some(a.cmp(b)),
+        (AttributeValue { n: Some(a), .. }, AttributeValue { n: Some(b), .. }) => {
+            Some(Number::parse(a)?.cmp(&Number::parse(b)?))
+        }
+        (AttributeValue { b: Some(a), .. }, AttributeValue { b: Some(b), .. }) => {
+            Some(a.as_ref().cmp(b.as_ref()))
+        }
+        _ => None,
+    }
+}
+
+/// A number's significant digits, without leading or trailing zeros, and the power of ten following its
+/// first digit, such that numbers of equal value are equal however they're written. Unlike `f64`s, this
+/// distinguishes numbers of up to the 38 digits DynamoDB supports
+#[derive(Debug, PartialEq, Eq)]
+struct Number {
+    negative: bool,
+    digits: String,
+    exponent: i64,
+}
+
+impl Number {
+    fn parse(n: &str) -> Option<Self> {
+        let n = n.trim();
+        let (negative, n) = match n.strip_prefix('-') {
+            Some(n) => (true, n),
+            None => (false, n.strip_prefix('+').unwrap_or(n)),
+        };
+        let (mantissa, exponent) = match n.find(['e', 'E']) {
+            Some(at) => (&n[..at], n[at + 1..].parse::<i64>().ok()?),
+            None => (n, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let digits = format!("{}{}", integer, fraction);
+        let significant = digits.trim_start_matches('0');
+        let exponent = exponent + integer.len() as i64 - (digits.len() - significant.len()) as i64;
+        let digits = significant.trim_end_matches('0').to_string();
+        Some(if digits.is_empty() {
+            Number {
+                negative: false,
+                digits,
+                exponent: 0,
+            }
+        } else {
+            Number {
+                negative,
+                digits,
+                exponent,
+            }
+        })
+    }
+
+    fn sign(&self) -> i8 {
+        match (self.digits.is_empty(), self.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        }
+    }
+}
+
+impl Ord for Number {
+    fn cmp(
+        &self,
+        other: &Self,
+    ) -> Ordering {
+        self.sign().cmp(&other.sign()).then_with(|| {
+            let magnitude = self
+                .exponent
+                .cmp(&other.exponent)
+                .then_with(|| self.digits.cmp(&other.digits));
+            if self.negative {
+                magnitude.reverse()
+            } else {
+                magnitude
+            }
+        })
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(
+        &self,
+        other: &Self,
+    ) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}0.{}e{}", sign, self.digits, self.exponent)
+    }
+}
+
+/// Fails requests using parameters this table doesn't support
+fn unsupported<E>(params: &[(&str, bool)]) -> Result<(), RusotoError<E>> {
+    match params.iter().find(|(_, used)| *used) {
+        Some((param, _)) => Err(RusotoError::Validation(format!(
+            "{} is not supported by InMemoryDynamoDb",
+            param
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// A test of a key attribute's value
+#[allow(clippy::large_enum_variant)]
+enum KeyCondition {
+    Compare(String, Ordering, bool, AttributeValue),
+    Between(String, AttributeValue, AttributeValue),
+    BeginsWith(String, AttributeValue),
+}
+
+impl KeyCondition {
+    fn name(&self) -> &str {
+        match self {
+            KeyCondition::Compare(name, ..)
+            | KeyCondition::Between(name, ..)
+            | KeyCondition::BeginsWith(name, ..) => name,
+        }
+    }
+
+    fn matches(
+        &self,
+        item: &Attributes,
+    ) -> bool {
+        let value = match item.get(self.name()) {
+            Some(value) => value,
+            _ => return false,
+        };
+        match self {
+            KeyCondition::Compare(_, ordering, or_equal, operand) => matches!(
+                compare(value, operand),
+                Some(found) if found == *ordering || (*or_equal && found == Ordering::Equal)
+            ),
+            KeyCondition::Between(_, low, high) => {
+                matches!(
+                    compare(value, low),
+                    Some(Ordering::Equal) | Some(Ordering::Greater)
+                ) && matches!(
+                    compare(value, high),
+                    Some(Ordering::Less) | Some(Ordering::Equal)
+                )
+            }
+            KeyCondition::BeginsWith(_, prefix) => match (value, prefix) {
+                (
+                    AttributeValue { s: Some(s), .. },
+                    AttributeValue {
+                        s: Some(prefix), ..
+                    },
+                ) => s.starts_with(prefix.as_str()),
+                (
+                    AttributeValue { b: Some(b), .. },
+                    AttributeValue {
+                        b: Some(prefix), ..
+                    },
+                ) => b.starts_with(prefix),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Parses the supported subset of key condition expressions
+struct KeyConditionParser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+    names: &'a HashMap<String, String>,
+    values: &'a Attributes,
+}
+
+impl<'a> KeyConditionParser<'a> {
+    fn parse(
+        expression: &'a str,
+        names: &'a HashMap<String, String>,
+        values: &'a Attributes,
+    ) -> Result<Vec<KeyCondition>, String> {
+        let mut tokens = Vec::new();
+        let mut rest = expression.trim_start();
+        while !rest.is_empty() {
+            let len = if rest.starts_with("<=") || rest.starts_with(">=") {
+                2
+            } else if rest.starts_with(|c| "(),=<>".contains(c)) {
+                1
+            } else {
+                rest.find(|c: char| c.is_whitespace() || "(),=<>".contains(c))
+                    .unwrap_or(rest.len())
+            };
+            tokens.push(&rest[..len]);
+            rest = rest[len..].trim_start();
+        }
+
+        let mut parser = KeyConditionParser {
+            tokens,
+            position: 0,
+            names,
+            values,
+        };
+        let mut conditions = vec![parser.condition()?];
+        while parser.position < parser.tokens.len() {
+            if !parser.next()?.eq_ignore_ascii_case("AND") {
+                return Err(format!(
+                    "Unsupported key condition expression {}",
+                    expression
+                ));
+            }
+            conditions.push(parser.condition()?);
+        }
+        Ok(conditions)
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| "Unexpected end of key condition expression".to_string())?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(
+        &mut self,
+        expected: &str,
+    ) -> Result<(), String> {
+        match self.next()? {
+            token if token.eq_ignore_ascii_case(expected) => Ok(()),
+            token => Err(format!("Expected {} but found {}", expected, token)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let token = self.next()?;
+        if token.starts_with('#') {
+            self.names
+                .get(token)
+                .cloned()
+                .ok_or_else(|| format!("Undefined expression attribute name {}", token))
+        } else {
+            Ok(token.to_string())
+        }
+    }
+
+    fn value(&mut self) -> Result<AttributeValue, String> {
+        let token = self.next()?;
+        self.values
+            .get(token)
+            .cloned()
+            .ok_or_else(|| format!("Undefined expression attribute value {}", token))
+    }
+
+    fn condition(&mut self) -> Result<KeyCondition, String> {
+        if matches!(
+            self.tokens.get(self.position),
+            Some(token) if token.eq_ignore_ascii_case("begins_with")
+        ) {
+            self.position += 1;
+            self.expect("(")?;
+            let name = self.name()?;
+            self.expect(",")?;
+            let prefix = self.value()?;
+            self.expect(")")?;
+            return Ok(KeyCondition::BeginsWith(name, prefix));
+        }
+        let name = self.name()?;
+        let condition = match self.next()? {
+            "=" => KeyCondition::Compare(name, Ordering::Equal, true, self.value()?),
+            "<" => KeyCondition::Compare(name, Ordering::Less, false, self.value()?),
+            "<=" => KeyCondition::Compare(name, Ordering::Less, true, self.value()?),
+            ">" => KeyCondition::Compare(name, Ordering::Greater, false, self.value()?),
+            ">=" => KeyCondition::Compare(name, Ordering::Greater, true, self.value()?),
+            between if between.eq_ignore_ascii_case("BETWEEN") => {
+                let low = self.value()?;
+                self.expect("AND")?;
+                KeyCondition::Between(name, low, self.value()?)
+            }
+            operator => return Err(format!("Unsupported key condition operator {}", operator)),
+        };
+        Ok(condition)
+    }
+}
+
+/// The operations this table supports, which its `DynamoDb` operations of the same names serve
+impl InMemoryDynamoDb {
+    async fn serve_put_item(
+        &self,
+        input: PutItemInput,
+    ) -> Result<PutItemOutput, RusotoError<PutItemError>> {
+        self.check_table(&input.table_name)?;
+        unsupported(&[
+            ("condition_expression", input.condition_expression.is_some()),
+            ("expected", input.expected.is_some()),
+        ])?;
+        let key = self.key_of(&input.item)?;
+        let all_old = input.return_values.as_deref() == Some("ALL_OLD");
+        let old = self.items().insert(key, input.item);
+        Ok(PutItemOutput {
+            attributes: old.filter(|_| all_old),
+            ..PutItemOutput::default()
+        })
+    }
+
+    async fn serve_get_item(
+        &self,
+        input: GetItemInput,
+    ) -> Result<GetItemOutput, RusotoError<GetItemError>> {
+        self.check_table(&input.table_name)?;
+        unsupported(&[
+            (
+                "projection_expression",
+                input.projection_expression.is_some(),
+            ),
+            ("attributes_to_get", input.attributes_to_get.is_some()),
+        ])?;
+        let key = self.key_of(&input.key)?;
+        Ok(GetItemOutput {
+            item: self.items().get(&key).cloned(),
+            ..GetItemOutput::default()
+        })
+    }
+
+    async fn serve_delete_item(
+        &self,
+        input: DeleteItemInput,
+    ) -> Result<DeleteItemOutput, RusotoError<DeleteItemError>> {
+        self.check_table(&input.table_name)?;
+        unsupported(&[
+            ("condition_expression", input.condition_expression.is_some()),
+            ("expected", input.expected.is_some()),
+        ])?;
+        let key = self.key_of(&input.key)?;
+        let old = self.items().remove(&key);
+        Ok(DeleteItemOutput {
+            attributes: old.filter(|_| input.return_values.as_deref() == Some("ALL_OLD")),
+            ..DeleteItemOutput::default()
+        })
+    }
+
+    async fn serve_query(
+        &self,
+        input: QueryInput,
+    ) -> Result<QueryOutput, RusotoError<QueryError>> {
+        self.check_table(&input.table_name)?;
+        unsupported(&[
+            ("index_name", input.index_name.is_some()),
+            ("filter_expression", input.filter_expression.is_some()),
+            (
+                "projection_expression",
+                input.projection_expression.is_some(),
+            ),
+            ("key_conditions", input.key_conditions.is_some()),
+            ("query_filter", input.query_filter.is_some()),
+        ])?;
+        let expression = input.key_condition_expression.as_deref().ok_or_else(|| {
+            RusotoError::Validation("A key_condition_expression is required".into())
+        })?;
+        let conditions = KeyConditionParser::parse(
+            expression,
+            &input.expression_attribute_names.clone().unwrap_or_default(),
+            &input
+                .expression_attribute_values
+                .clone()
+                .unwrap_or_default(),
+        )
+        .map_err(RusotoError::Validation)?;
+        match conditions.as_slice() {
+            [KeyCondition::Compare(name, Ordering::Equal, true, _), rest @ ..]
+                if *name == self.table.partition_key
+                    && rest.iter().all(|condition| {
+                        Some(condition.name()) == self.table.sort_key.as_deref()
+                    })
+                    && rest.len() <= 1 => {}
+            _ => {
+                return Err(RusotoError::Validation(format!(
+                    "Unsupported key condition expression {}",
+                    expression
+                )))
+            }
+        }
+
+        let mut items = self
+            .items()
+            .values()
+            .filter(|item| conditions.iter().all(|condition| condition.matches(item)))
+            .cloned()
+            .collect::<Vec<_>>();
+        let forward = input.scan_index_forward != Some(false);
+        let order = |a: &Attributes, b: &Attributes| {
+            let order = self
+                .table
+                .sort_key
+                .as_ref()
+                .and_then(|sort_key| a.get(sort_key).zip(b.get(sort_key)))
+                .and_then(|(a, b)| compare(a, b))
+                .unwrap_or(Ordering::Equal);
+            if forward {
+                order
+            } else {
+                order.reverse()
+            }
+        };
+        items.sort_by(order);
+        let scanned_count = items.len();
+        let (items, last_evaluated_key) =
+            self.page(items, input.exclusive_start_key, input.limit, order);
+        Ok(QueryOutput {
+            count: Some(items.len() as i64),
+            scanned_count: Some(scanned_count as i64),
+            items: Some(items),
+            last_evaluated_key,
+            ..QueryOutput::default()
+        })
+    }
+
+    async fn serve_scan(
+        &self,
+        input: ScanInput,
+    ) -> Result<ScanOutput, RusotoError<ScanError>> {
+        self.check_table(&input.table_name)?;
+        unsupported(&[
+            ("index_name", input.index_name.is_some()),
+            ("filter_expression", input.filter_expression.is_some()),
+            (
+                "projection_expression",
+                input.projection_expression.is_some(),
+            ),
+            ("scan_filter", input.scan_filter.is_some()),
+            ("segment", input.segment.is_some()),
+        ])?;
+        // items are ordered by their serialized keys
+        let items = self.items().values().cloned().collect::<Vec<_>>();
+        let scanned_count = items.len();
+        let (items, last_evaluated_key) =
+            self.page(items, input.exclusive_start_key, input.limit, |a, b| {
+                self.key_of::<ScanError>(a)
+                    .ok()
+                    .cmp(&self.key_of::<ScanError>(b).ok())
+            });
+        Ok(ScanOutput {
+            count: Some(items.len() as i64),
+            scanned_count: Some(scanned_count as i64),
+            items: Some(items),
+            last_evaluated_key,
+            ..ScanOutput::default()
+        })
+    }
+}
+
+macro_rules! in_memory_dynamodb {
+    ($($method:ident($($input:ident)?) -> $output:ty, $error:ty;)*) => {
+        #[async_trait::async_trait]
+        impl DynamoDb for InMemoryDynamoDb {
+            $(
+                async fn $method(
+                    &self,
+                    $(input: $input)?
+                ) -> Result<$output, RusotoError<$error>> {
+                    in_memory_operation!(self, $method($(input: $input)?))
+                }
+            )*
+        }
+    };
+}
+
+/// Serves the operations this table supports, failing all others with a validation error
+macro_rules! in_memory_operation {
+    ($self:ident, put_item($input:ident: $ty:ident)) => {
+        $self.serve_put_item($input).await
+    };
+    ($self:ident, get_item($input:ident: $ty:ident)) => {
+        $self.serve_get_item($input).await
+    };
+    ($self:ident, delete_item($input:ident: $ty:ident)) => {
+        $self.serve_delete_item($input).await
+    };
+    ($self:ident, query($input:ident: $ty:ident)) => {
+        $self.serve_query($input).await
+    };
+    ($self:ident, scan($input:ident: $ty:ident)) => {
+        $self.serve_scan($input).await
+    };
+    ($self:ident, $method:ident($($input:ident: $ty:ident)?)) => {{
+        $(let _ = $input;)?
+        Err(RusotoError::Validation(format!(
+            "{} is not supported by InMemoryDynamoDb",
+            stringify!($method)
+        )))
+    }};
+}
+
+dynamodb_operations!(in_memory_dynamodb);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Attribute, DynamoDbExt};
+    use futures::{executor::block_on, TryStreamExt};
+    use maplit::hashmap;
+
+    fn order(
+        customer: &str,
+        id: &str,
+    ) -> Attributes {
+        hashmap! {
+            "customer".to_string() => customer.to_string().into_attr(),
+            "id".to_string() => id.to_string().into_attr(),
+        }
+    }
+
+    fn orders() -> InMemoryDynamoDb {
+        let client = InMemoryDynamoDb::new("orders", "customer").with_sort_key("id");
+        for (customer, id) in &[
+            ("alice", "2021#2"),
+            ("alice", "2020#1"),
+            ("alice", "2021#1"),
+            ("bob", "2021#3"),
+        ] {
+            block_on(client.put_item(PutItemInput {
+                table_name: "orders".into(),
+                item: order(customer, id),
+                ..PutItemInput::default()
+            }))
+            .expect("item to be put");
+        }
+        client
+    }
+
+    #[test]
+    fn items_round_trip() {
+        let client = orders();
+        let mut item = order("carol", "2021#1");
+        item.insert("total".into(), 42.into_attr());
+        let put = |item: Attributes| {
+            block_on(client.put_item(PutItemInput {
+                table_name: "orders".into(),
+                item,
+                return_values: Some("ALL_OLD".into()),
+                ..PutItemInput::default()
+            }))
+            .expect("item to be put")
+            .attributes
+        };
+        let get = || {
+            block_on(client.get_item(GetItemInput {
+                table_name: "orders".into(),
+                key: order("carol", "2021#1"),
+                ..GetItemInput::default()
+            }))
+            .expect("item to be got")
+            .item
+        };
+
+        assert_eq!(put(item.clone()), None);
+        assert_eq!(get(), Some(item.clone()));
+        assert_eq!(put(order("carol", "2021#1")), Some(item));
+        block_on(client.delete_item(DeleteItemInput {
+            table_name: "orders".into(),
+            key: order("carol", "2021#1"),
+            ..DeleteItemInput::default()
+        }))
+        .expect("item to be deleted");
+        assert_eq!(get(), None);
+    }
+
+    #[test]
+    fn query_by_sort_key_prefix() {
+        let items = block_on(
+            orders()
+                .query_pages(QueryInput {
+                    table_name: "orders".into(),
+                    key_condition_expression: Some(
+                        "#customer = :customer AND begins_with(id, :year)".into(),
+                    ),
+                    expression_attribute_names: Some(hashmap! {
+                        "#customer".to_string() => "customer".to_string()
+                    }),
+                    expression_attribute_values: Some(hashmap! {
+                        ":customer".to_string() => "alice".to_string().into_attr(),
+                        ":year".to_string() => "2021#".to_string().into_attr(),
+                    }),
+                    limit: Some(1),
+                    ..QueryInput::default()
+                })
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("items to be queried");
+        assert_eq!(
+            items,
+            vec![order("alice", "2021#1"), order("alice", "2021#2")]
+        );
+    }
+
+    #[test]
+    fn query_by_sort_key_range() {
+        let output = block_on(orders().query(QueryInput {
+            table_name: "orders".into(),
+            key_condition_expression: Some(
+                "customer = :customer AND id BETWEEN :from AND :to".into(),
+            ),
+            expression_attribute_values: Some(hashmap! {
+                ":customer".to_string() => "alice".to_string().into_attr(),
+                ":from".to_string() => "2020#1".to_string().into_attr(),
+                ":to".to_string() => "2021#1".to_string().into_attr(),
+            }),
+            scan_index_forward: Some(false),
+            ..QueryInput::default()
+        }))
+        .expect("items to be queried");
+        assert_eq!(
+            output.items,
+            Some(vec![order("alice", "2021#1"), order("alice", "2020#1")])
+        );
+    }
+
+    #[test]
+    fn query_rejects_unsupported_expressions() {
+        let result = block_on(orders().query(QueryInput {
+            table_name: "orders".into(),
+            key_condition_expression: Some("id = :id".into()),
+            expression_attribute_values: Some(hashmap! {
+                ":id".to_string() => "2021#1".to_string().into_attr(),
+            }),
+            ..QueryInput::default()
+        }));
+        assert!(matches!(result, Err(RusotoError::Validation(_))));
+    }
+
+    #[test]
+    fn numbers_are_keyed_by_value() {
+        let client = InMemoryDynamoDb::new("users", "id");
+        // adjacent ids beyond 2^53, which f64s can't tell apart
+        for id in &["9007199254740992", "9007199254740993"] {
+            block_on(client.put_item(PutItemInput {
+                table_name: "users".into(),
+                item: hashmap! {
+                    "id".to_string() => AttributeValue {
+                        n: Some(id.to_string()),
+                        ..AttributeValue::default()
+                    },
+                },
+                ..PutItemInput::default()
+            }))
+            .expect("item to be put");
+        }
+        assert_eq!(client.items().len(), 2);
+
+        let number = |n: &str| Number::parse(n).expect("a number");
+        assert_eq!(number("1.50"), number("001.5e0"));
+        assert_eq!(number("-0.0"), number("0"));
+        assert_eq!(number("120"), number("1.2E2"));
+        assert!(number("9007199254740993") > number("9007199254740992"));
+        assert!(number("-10") < number("-9.5"));
+        assert!(number("0.01") < number("0.1"));
+        assert_eq!(Number::parse("1.2.3"), None);
+    }
+
+    #[test]
+    fn pages_start_after_keys_which_are_not_stored() {
+        let client = orders();
+        let output = block_on(client.query(QueryInput {
+            table_name: "orders".into(),
+            key_condition_expression: Some("customer = :customer".into()),
+            expression_attribute_values: Some(hashmap! {
+                ":customer".to_string() => "alice".to_string().into_attr(),
+            }),
+            exclusive_start_key: Some(order("alice", "2020#2")),
+            ..QueryInput::default()
+        }))
+        .expect("items to be queried");
+        assert_eq!(
+            output.items,
+            Some(vec![order("alice", "2021#1"), order("alice", "2021#2")])
+        );
+
+        let output = block_on(client.scan(ScanInput {
+            table_name: "orders".into(),
+            exclusive_start_key: Some(order("alice", "2021#3")),
+            ..ScanInput::default()
+        }))
+        .expect("items to be scanned");
+        assert_eq!(output.items, Some(vec![order("bob", "2021#3")]));
+    }
+
+    #[test]
+    fn unsupported_operations_fail() {
+        let result = block_on(orders().update_item(UpdateItemInput {
+            table_name: "orders".into(),
+            ..UpdateItemInput::default()
+        }));
+        assert!(matches!(
+            result,
+            Err(RusotoError::Validation(message)) if message == "update_item is not supported by InMemoryDynamoDb"
+        ));
+    }
+
+    #[test]
+    fn scan_yields_all_items() {
+        let items = block_on(
+            orders()
+                .scan_pages(ScanInput {
+                    table_name: "orders".into(),
+                    limit: Some(3),
+                    ..ScanInput::default()
+                })
+                .try_collect::<Vec<_>>(),
+        )
+        .expect("items to be scanned");
+        assert_eq!(items.len(), 4);
+    }
+}
@@ -0,0 +1,251 @@
+//! A builder for transactional writes
+//!
+//! DynamoDB applies all of the actions of a [`TransactWriteItems`](https://docs.aws.amazon.com/amazondynamodb/latest/APIReference/API_TransactWriteItems.html)
+//! request atomically, but accepts at most [`MAX_ACTIONS`](constant.MAX_ACTIONS.html) of them.
+//! Transactions can't be split without losing their atomicity, so [`TransactWrite::build`](struct.TransactWrite.html#method.build)
+//! fails with [`TooManyActions`](struct.TooManyActions.html) rather than sending a request DynamoDB would reject.
+//!
+//! # Examples
+//!
+//! ```
+//! use dynomite::{
+//!     attr_map,
+//!     expression::attribute_exists,
+//!     transact::TransactWrite,
+//! };
+//!
+//! let input = TransactWrite::new()
+//!     .condition_check("accounts", attr_map! { "id" => "a" }, attribute_exists("id"))
+//!     .put("orders", attr_map! { "id" => "1", "account" => "a" })
+//!     .delete("carts", attr_map! { "account" => "a" })
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(input.transact_items.len(), 3);
+//! ```
+
+use crate::{
+    dynamodb::{ConditionCheck, Delete, Put, TransactWriteItem, TransactWriteItemsInput, Update},
+    expression::Expression,
+    Attributes, IntoAttributes,
+};
+use std::{error::Error as StdError, fmt};
+
+/// The maximum number of actions DynamoDB accepts in a single transaction
+pub const MAX_ACTIONS: usize = 100;
+
+/// Returned when building a transaction with more than [`MAX_ACTIONS`](constant.MAX_ACTIONS.html) actions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooManyActions {
+    /// The number of actions in the transaction
+    pub count: usize,
+}
+
+impl fmt::Display for TooManyActions {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "Transaction of {} actions exceeds the limit of {} actions. Transactions can't be split without losing their atomicity",
+            self.count, MAX_ACTIONS
+        )
+    }
+}
+
+impl StdError for TooManyActions {}
+
+/// A builder for `TransactWriteItemsInput`s
+#[derive(Debug, Clone, Default)]
+pub struct TransactWrite {
+    actions: Vec<TransactWriteItem>,
+    client_request_token: Option<String>,
+}
+
+impl TransactWrite {
+    /// Returns a builder for a transaction without any actions
+    pub fn new() -> Self {
+        TransactWrite::default()
+    }
+
+    /// Adds an action which writes `item` to the given table
+    pub fn put(
+        self,
+        table_name: impl Into<String>,
+        item: impl IntoAttributes,
+    ) -> Self {
+        let mut attrs = Attributes::new();
+        item.into_attrs(&mut attrs);
+        self.action(TransactWriteItem {
+            put: Some(Put {
+                table_name: table_name.into(),
+                item: attrs,
+                ..Put::default()
+            }),
+            ..TransactWriteItem::default()
+        })
+    }
+
+    /// Adds an action which deletes the item with the given key from the given table
+    pub fn delete(
+        self,
+        table_name: impl Into<String>,
+        key: impl Into<Attributes>,
+    ) -> Self {
+        self.action(TransactWriteItem {
+            delete: Some(Delete {
+                table_name: table_name.into(),
+                key: key.into(),
+                ..Delete::default()
+            }),
+            ..TransactWriteItem::default()
+        })
+    }
+
+    /// Adds an action which updates an item
+    pub fn update(
+        self,
+        update: Update,
+    ) -> Self {
+        self.action(TransactWriteItem {
+            update: Some(update),
+            ..TransactWriteItem::default()
+        })
+    }
+
+    /// Adds an action which fails the transaction unless the item with the given key meets `condition`
+    pub fn condition_check(
+        self,
+        table_name: impl Into<String>,
+        key: impl Into<Attributes>,
+        condition: impl Into<Expression>,
+    ) -> Self {
+        let condition = condition.into();
+        self.action(TransactWriteItem {
+            condition_check: Some(ConditionCheck {
+                table_name: table_name.into(),
+                key: key.into(),
+                condition_expression: condition.expression,
+                expression_attribute_names: condition.names,
+                expression_attribute_values: condition.values,
+                ..ConditionCheck::default()
+            }),
+            ..TransactWriteItem::default()
+        })
+    }
+
+    /// Adds an action as is
+    pub fn action(
+        mut self,
+        action: TransactWriteItem,
+    ) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Sets the token which makes retries of this transaction idempotent
+    pub fn client_request_token(
+        mut self,
+        token: impl Into<String>,
+    ) -> Self {
+        self.client_request_token = Some(token.into());
+        self
+    }
+
+    /// Returns the number of actions in this transaction
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns true when this transaction has no actions
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns the built `TransactWriteItemsInput`, failing when it has more than
+    /// [`MAX_ACTIONS`](constant.MAX_ACTIONS.html) actions
+    pub fn build(self) -> Result<TransactWriteItemsInput, TooManyActions> {
+        let TransactWrite {
+            actions,
+            client_request_token,
+        } = self;
+        if actions.len() > MAX_ACTIONS {
+            return Err(TooManyActions {
+                count: actions.len(),
+            });
+        }
+        Ok(TransactWriteItemsInput {
+            transact_items: actions,
+            client_request_token,
+            ..TransactWriteItemsInput::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attr_map, expression::attribute_not_exists};
+
+    #[test]
+    fn builds_actions_in_order() {
+        let input = TransactWrite::new()
+            .put("orders", attr_map! { "id" => "1" })
+            .condition_check(
+                "accounts",
+                attr_map! { "id" => "a" },
+                attribute_not_exists("closed"),
+            )
+            .client_request_token("token")
+            .build()
+            .unwrap();
+        assert_eq!(input.client_request_token.as_deref(), Some("token"));
+        assert_eq!(
+            input.transact_items,
+            vec![
+                TransactWriteItem {
+                    put: Some(Put {
+                        table_name: "orders".into(),
+                        item: attr_map! { "id" => "1" },
+                        ..Put::default()
+                    }),
+                    ..TransactWriteItem::default()
+                },
+                TransactWriteItem {
+                    condition_check: Some(ConditionCheck {
+                        table_name: "accounts".into(),
+                        key: attr_map! { "id" => "a" },
+                        condition_expression: "attribute_not_exists(closed)".into(),
+                        ..ConditionCheck::default()
+                    }),
+                    ..TransactWriteItem::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_transactions_at_the_limit() {
+        let transaction = (0..MAX_ACTIONS).fold(TransactWrite::new(), |transaction, id| {
+            transaction.delete("orders", attr_map! { "id" => id.to_string() })
+        });
+        assert_eq!(transaction.len(), MAX_ACTIONS);
+        assert_eq!(
+            transaction.build().map(|input| input.transact_items.len()),
+            Ok(MAX_ACTIONS)
+        );
+    }
+
+    #[test]
+    fn rejects_transactions_beyond_the_limit() {
+        let transaction = (0..=MAX_ACTIONS).fold(TransactWrite::new(), |transaction, id| {
+            transaction.put("orders", attr_map! { "id" => id.to_string() })
+        });
+        let err = transaction.build().unwrap_err();
+        assert_eq!(err, TooManyActions { count: 101 });
+        assert_eq!(
+            err.to_string(),
+            "Transaction of 101 actions exceeds the limit of 100 actions. Transactions can't be split without losing their atomicity"
+        );
+    }
+}
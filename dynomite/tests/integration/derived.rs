@@ -1,5 +1,7 @@
-use dynomite::{Attribute, Attributes, Item};
+use bytes::Bytes;
+use dynomite::{Attribute, AttributeValue, Attributes, Item};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Item, Default, PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Author {
@@ -15,6 +17,15 @@ pub enum Category {
     Foo,
 }
 
+#[derive(Attribute, PartialEq, Debug, Clone, Copy)]
+#[dynomite(numeric)]
+enum Priority {
+    Low,
+    Normal,
+    Urgent = 10,
+    Critical,
+}
+
 impl Default for Category {
     fn default() -> Self {
         Category::Foo
@@ -36,6 +47,153 @@ struct Recipe {
     servings: u64,
 }
 
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Membership {
+    #[dynomite(partition_key, prefix = "USER#")]
+    user: uuid::Uuid,
+    #[dynomite(sort_key, prefix = "ORG#")]
+    org: u32,
+    role: String,
+}
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+struct Marker;
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+#[dynomite(deny_unknown_fields)]
+struct StrictMarker;
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Ledger {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(version, rename = "rev")]
+    version: u32,
+    balance: i64,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+#[dynomite(key_derive(Hash, Eq))]
+struct Visit {
+    #[dynomite(partition_key)]
+    visitor: String,
+    #[dynomite(sort_key)]
+    day: u32,
+    #[dynomite(gsi(name = "by_page", partition_key))]
+    pages: u32,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+#[dynomite(sort_key(name = "sk", compute = "Statement::format_sort_key"))]
+struct Statement {
+    #[dynomite(partition_key)]
+    customer: String,
+    number: u32,
+    #[dynomite(flatten)]
+    rest: Attributes,
+}
+
+impl Statement {
+    fn format_sort_key(&self) -> String {
+        format!("STATEMENT#{:04}", self.number)
+    }
+}
+
+#[derive(Attribute, PartialEq, Debug, Clone)]
+struct ShelfId(String);
+
+#[derive(Attribute, PartialEq, Debug, Clone)]
+struct Thumbnail(Vec<u8>);
+
+#[derive(Attribute, PartialEq, Debug, Clone)]
+#[dynomite(transparent)]
+struct Email {
+    value: String,
+}
+
+#[derive(Attribute, PartialEq, Debug, Clone)]
+#[dynomite(compose = "ORDER#{year}#{seq}")]
+struct OrderSortKey {
+    year: u16,
+    seq: u32,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Purchase {
+    #[dynomite(partition_key)]
+    customer: String,
+    #[dynomite(sort_key, rename = "sk")]
+    sort_key: OrderSortKey,
+    total: u32,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Reading {
+    #[dynomite(partition_key)]
+    sensor: String,
+    #[dynomite(sort_key)]
+    at: dynomite::encoding::Be64,
+}
+
+type LedgerId = u64;
+
+#[derive(Attribute, PartialEq, Debug, Clone, Copy)]
+struct Sequence(u32);
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct LedgerEntry {
+    #[dynomite(partition_key)]
+    ledger: LedgerId,
+    #[dynomite(sort_key)]
+    sequence: Sequence,
+    #[dynomite(gsi(name = "by_priority", partition_key))]
+    priority: Priority,
+}
+
+#[derive(Attribute, PartialEq, Debug, Clone)]
+#[dynomite(compact_string, separator = ":")]
+enum Status {
+    Pending,
+    Failed(u32),
+    #[dynomite(rename = "note")]
+    Noted(String),
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Shelf {
+    #[dynomite(partition_key)]
+    id: ShelfId,
+    capacity: u32,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Order {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(gsi(name = "by_status", partition_key))]
+    status: String,
+    #[dynomite(rename = "createdAt", gsi(name = "by_status", sort_key))]
+    created_at: u64,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Shipment {
+    #[dynomite(partition_key)]
+    #[dynomite(gsi(name = "by-carrier", sort_key))]
+    id: String,
+    #[dynomite(gsi(name = "by-carrier", partition_key), rename = "Carrier")]
+    carrier: String,
+    weight: u64,
+}
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+struct Base64Encoded {
+    #[dynomite(base64)]
+    vec: Vec<u8>,
+    #[dynomite(base64, rename = "Bytes")]
+    bytes: Bytes,
+}
+
 #[derive(Item, PartialEq, Debug, Clone)]
 struct FlattenRoot {
     #[dynomite(partition_key)]
@@ -51,12 +209,181 @@ struct Flattened {
     flat_nested: FlattenedNested,
 }
 
+#[derive(Attributes, PartialEq, Debug, Clone)]
+struct Customer {
+    name: String,
+    #[dynomite(flatten)]
+    billing: Option<Billing>,
+}
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+struct Billing {
+    card: String,
+    #[dynomite(rename = "billingAddress")]
+    address: String,
+}
+
 #[derive(Attributes, PartialEq, Debug, Clone)]
 struct FlattenedNested {
     b: u64,
     c: bool,
 }
 
+#[derive(Attributes, Default, PartialEq, Debug, Clone)]
+#[dynomite(default)]
+struct Shipping {
+    carrier: String,
+    #[dynomite(rename = "trackingNumber")]
+    tracking_number: String,
+}
+
+#[derive(Attributes, Default, PartialEq, Debug, Clone)]
+#[dynomite(default)]
+struct Consignment {
+    note: String,
+    #[dynomite(flatten)]
+    shipping: Shipping,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct Verified;
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Certificate {
+    #[dynomite(partition_key)]
+    id: String,
+    state: std::marker::PhantomData<Verified>,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+#[dynomite(default)]
+struct Excerpt {
+    #[dynomite(partition_key)]
+    id: String,
+    title: String,
+    pages: u32,
+    tags: Vec<String>,
+    // field level defaults take precedence over the struct's
+    #[dynomite(default)]
+    rating: u32,
+    #[dynomite(default = "unshelved")]
+    shelf: Option<String>,
+}
+
+impl Default for Excerpt {
+    fn default() -> Self {
+        Excerpt {
+            id: String::new(),
+            title: "Untitled".into(),
+            pages: 100,
+            tags: vec![],
+            rating: 5,
+            shelf: None,
+        }
+    }
+}
+
+fn unshelved() -> Option<String> {
+    Some("unshelved".into())
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Parcel {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(flatten)]
+    shipping: Shipping,
+}
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+#[dynomite(presence)]
+struct Patch {
+    id: String,
+    #[dynomite(default)]
+    title: Option<String>,
+    #[dynomite(optional, rename = "Summary")]
+    summary: Option<String>,
+    #[dynomite(default)]
+    pages: u32,
+}
+
+mod revision {
+    use dynomite::Attributes;
+
+    #[derive(Attributes, PartialEq, Debug, Clone)]
+    #[dynomite(presence)]
+    pub struct Revision {
+        id: String,
+        #[dynomite(default)]
+        note: Option<String>,
+    }
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+struct Booking {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(flatten, prefix = "item_")]
+    slots: [Slot; 2],
+}
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+struct Slot {
+    #[dynomite(rename = "Start")]
+    start: u64,
+    label: String,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+#[dynomite(finalize = "Invoice::compute_total")]
+struct Invoice {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(flatten)]
+    lines: InvoiceLines,
+    #[dynomite(default)]
+    total: u64,
+}
+
+impl Invoice {
+    fn compute_total(&mut self) {
+        self.total = self.lines.price * self.lines.quantity;
+    }
+}
+
+#[derive(Attributes, PartialEq, Debug, Clone)]
+struct InvoiceLines {
+    price: u64,
+    quantity: u64,
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+#[dynomite(prepare = "Account::normalize")]
+struct Account {
+    #[dynomite(partition_key)]
+    id: String,
+    email: String,
+}
+
+impl Account {
+    fn normalize(&mut self) {
+        self.email = self.email.to_lowercase();
+    }
+}
+
+#[derive(Item, PartialEq, Debug, Clone)]
+#[dynomite(prepare = "Subscriber::normalize")]
+struct Subscriber {
+    #[dynomite(partition_key)]
+    email: String,
+}
+
+impl Subscriber {
+    fn normalize(&mut self) {
+        self.email = self.email.to_lowercase();
+    }
+}
+
 #[derive(Attributes)]
 struct RemainingPropsInMap {
     a: bool,
@@ -69,6 +396,44 @@ struct RemainingPropsInMap {
     remainder: Attributes,
 }
 
+#[derive(Attributes)]
+struct RemainingPropsInBTreeMap {
+    a: bool,
+    b: u32,
+
+    #[dynomite(flatten)]
+    original_c_collector: HasC,
+
+    #[dynomite(flatten)]
+    remainder: BTreeMap<String, AttributeValue>,
+}
+
+#[derive(Attributes, Debug, PartialEq)]
+struct LenientAccount {
+    #[dynomite(lenient)]
+    number: String,
+    #[dynomite(lenient)]
+    balance: u32,
+}
+
+#[derive(Attributes, Debug, PartialEq)]
+#[dynomite(deny_unknown_fields)]
+struct StrictProps {
+    a: bool,
+    #[dynomite(rename = "B")]
+    b: u32,
+}
+
+#[derive(Attributes)]
+#[dynomite(deny_unknown_fields)]
+struct StrictPropsWithRemainder {
+    a: bool,
+    #[dynomite(flatten)]
+    original_c_collector: HasC,
+    #[dynomite(flatten)]
+    remainder: Attributes,
+}
+
 #[derive(Attributes)]
 struct HasC {
     c: u32,
@@ -103,6 +468,25 @@ struct Bar {
     c: bool,
 }
 
+#[derive(Attributes, Clone, Debug, PartialEq)]
+#[dynomite(transparent)]
+struct TransparentFoo {
+    foo: Foo,
+}
+
+#[derive(Attributes, Clone, Debug, PartialEq)]
+#[dynomite(tag = "kind")]
+enum Event {
+    Created(Created),
+}
+
+#[derive(Attributes, Clone, Debug, PartialEq)]
+struct Created {
+    id: String,
+    #[dynomite(flatten)]
+    unknown: Attributes,
+}
+
 #[derive(Attributes, Clone, Debug, PartialEq)]
 #[dynomite(tag = "nested_kind")]
 enum Nested {
@@ -121,7 +505,13 @@ mod tests {
     use std::convert::TryFrom;
 
     use super::*;
-    use dynomite::{Attribute, Attributes, Item};
+    use dynomite::{
+        dynamodb::{
+            AttributeDefinition, CreateTableInput, GlobalSecondaryIndex, KeySchemaElement,
+            Projection, PutItemInput,
+        },
+        Attribute, Attributes, FromAttributes, Item,
+    };
 
     #[test]
     fn derived_key() {
@@ -132,6 +522,552 @@ mod tests {
         assert_eq!(value.key(), RecipeKey { id: "test".into() }.into());
     }
 
+    #[test]
+    fn derived_into_key_moves_key_fields() {
+        let recipe = Recipe {
+            id: "test".into(),
+            servings: 1,
+        };
+        assert_eq!(recipe.key(), recipe.clone().into_key());
+        let purchase = Purchase {
+            customer: "foo".into(),
+            sort_key: OrderSortKey { year: 2024, seq: 1 },
+            total: 3,
+        };
+        assert_eq!(purchase.key(), purchase.clone().into_key());
+        let statement = Statement {
+            customer: "foo".into(),
+            number: 7,
+            rest: Attributes::new(),
+        };
+        assert_eq!(statement.key(), statement.clone().into_key());
+        assert_eq!(statement.into_key().len(), 2);
+    }
+
+    #[test]
+    fn vec_of_derived_attributes_round_trips() {
+        let authors = vec![
+            Author {
+                name: "Jane".into(),
+            },
+            Author {
+                name: "John".into(),
+            },
+        ];
+        let value = authors.clone().into_attr();
+        let elements = value.l.clone().expect("expected an L value");
+        assert_eq!(elements.len(), 2);
+        assert_eq!(
+            elements[0].m,
+            Some(Attributes::from(authors[0].clone())),
+            "expected each element to be an M value"
+        );
+        assert_eq!(Vec::<Author>::from_attr(value), Ok(authors));
+    }
+
+    #[test]
+    fn empty_vec_of_derived_attributes_round_trips() {
+        let value = Vec::<Author>::new().into_attr();
+        assert_eq!(value.l, Some(vec![]));
+        assert_eq!(value.m, None);
+        assert_eq!(Vec::<Author>::from_attr(value), Ok(vec![]));
+    }
+
+    #[test]
+    fn derived_key_derives_hash_and_eq() {
+        let visit = Visit {
+            visitor: "rust".into(),
+            day: 1,
+            pages: 3,
+        };
+        let key = VisitKey {
+            visitor: "rust".into(),
+            day: 1,
+        };
+        let mut pages = std::collections::HashMap::new();
+        pages.insert(key.clone(), visit.pages);
+        pages.insert(
+            VisitKey {
+                visitor: "rust".into(),
+                day: 2,
+            },
+            5,
+        );
+        assert_eq!(pages.get(&key), Some(&3));
+        assert_eq!(visit.key(), key.into());
+
+        let mut index_keys = std::collections::HashSet::new();
+        index_keys.insert(VisitByPageKey { pages: 3 });
+        assert!(index_keys.contains(&VisitByPageKey { pages: 3 }));
+    }
+
+    #[test]
+    fn from_attrs_ref_leaves_attrs_intact() {
+        let value = Purchase {
+            customer: "foo".into(),
+            sort_key: OrderSortKey { year: 2024, seq: 1 },
+            total: 42,
+        };
+        let attrs: Attributes = value.clone().into();
+
+        let key = PurchaseKey::from_attrs_ref(&attrs).unwrap();
+        let purchase = Purchase::from_attrs_ref(&attrs).unwrap();
+        assert_eq!(key.customer, "foo");
+        assert_eq!(purchase, value);
+        assert_eq!(attrs, value.into());
+    }
+
+    #[test]
+    fn missing_partition_key() {
+        let mut attrs: Attributes = Purchase {
+            customer: "foo".into(),
+            sort_key: OrderSortKey { year: 2024, seq: 1 },
+            total: 42,
+        }
+        .into();
+        attrs.remove("customer");
+        assert_eq!(
+            Err(dynomite::AttributeError::MissingKey {
+                name: "customer".into()
+            }),
+            Purchase::try_from(attrs.clone())
+        );
+
+        attrs.remove("sk");
+        attrs.remove("total");
+        assert!(matches!(
+            PurchaseKey::try_from(attrs),
+            Err(dynomite::AttributeError::MissingKey { .. })
+        ));
+    }
+
+    #[test]
+    fn composite_sort_key() {
+        let value = Purchase {
+            customer: "foo".into(),
+            sort_key: OrderSortKey {
+                year: 2024,
+                seq: 123,
+            },
+            total: 42,
+        };
+        let key = PurchaseKey {
+            customer: "foo".into(),
+            sort_key: value.sort_key.clone(),
+        };
+        assert_eq!(value.key(), key.clone().into());
+        assert_eq!(value.key()["sk"].s.as_deref(), Some("ORDER#2024#123"));
+
+        let attrs: Attributes = value.clone().into();
+        assert!(!attrs.contains_key("year"));
+        assert_eq!(value, Purchase::try_from(attrs).unwrap());
+        assert_eq!(key, PurchaseKey::try_from(value.key()).unwrap());
+    }
+
+    #[test]
+    fn sort_key_is_optional_for_generic_items() {
+        fn sort_key_name<I: Item>(item: &I) -> Option<String> {
+            item.sort_key().map(|(name, _)| name)
+        }
+        let purchase = Purchase {
+            customer: "foo".into(),
+            sort_key: OrderSortKey { year: 2024, seq: 1 },
+            total: 42,
+        };
+        assert_eq!(sort_key_name(&purchase), Some("sk".to_string()));
+        assert_eq!(
+            purchase.sort_key().map(|(_, value)| value),
+            Some(purchase.key()["sk"].clone())
+        );
+        assert_eq!(sort_key_name(&Book::default()), None);
+    }
+
+    #[test]
+    fn computed_sort_key() {
+        let statement = Statement {
+            customer: "rust".into(),
+            number: 7,
+            rest: Attributes::new(),
+        };
+        let key = dynomite::attr_map! {
+            "customer" => "rust",
+            "sk" => "STATEMENT#0007",
+        };
+        assert_eq!(statement.key(), key);
+        assert_eq!(
+            statement.sort_key(),
+            Some(("sk".to_string(), "STATEMENT#0007".to_string().into_attr()))
+        );
+        let statement_key = StatementKey {
+            customer: "rust".into(),
+            sort_key: "STATEMENT#0007".into(),
+        };
+        assert_eq!(Attributes::from(statement_key), key);
+        let attrs: Attributes = statement.clone().into();
+        assert_eq!(attrs["sk"], "STATEMENT#0007".to_string().into_attr());
+        // the computed sort key is write only
+        assert_eq!(Statement::try_from(attrs), Ok(statement));
+    }
+
+    #[test]
+    fn composite_sort_key_malformed() {
+        assert_eq!(
+            Err(dynomite::AttributeError::InvalidFormat),
+            OrderSortKey::from_attr("INVOICE#2024#123".to_string().into_attr())
+        );
+        assert_eq!(
+            Err(dynomite::AttributeError::Path {
+                path: vec!["seq".into()],
+                source: Box::new(dynomite::AttributeError::InvalidFormat)
+            }),
+            OrderSortKey::from_attr("ORDER#2024#abc".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    fn compact_string_enum() {
+        assert_eq!(Status::Pending.into_attr().s.as_deref(), Some("Pending"));
+        assert_eq!(
+            Status::Failed(42).into_attr().s.as_deref(),
+            Some("Failed:42")
+        );
+        assert_eq!(
+            Status::Noted("a:b".into()).into_attr().s.as_deref(),
+            Some("note:a:b")
+        );
+        for value in &[
+            Status::Pending,
+            Status::Failed(42),
+            Status::Noted("a:b".into()),
+        ] {
+            assert_eq!(
+                Ok(value.clone()),
+                Status::from_attr(value.clone().into_attr())
+            );
+        }
+    }
+
+    #[test]
+    fn compact_string_enum_malformed() {
+        for s in &["Failed", "Failed:x", "Pending:1", "Unknown"] {
+            assert_eq!(
+                Err(dynomite::AttributeError::InvalidFormat),
+                Status::from_attr(s.to_string().into_attr())
+            );
+        }
+    }
+
+    #[test]
+    fn newtype_partition_key() {
+        let value = Shelf {
+            id: ShelfId("a1".into()),
+            capacity: 20,
+        };
+        let key = ShelfKey {
+            id: ShelfId("a1".into()),
+        };
+        assert_eq!(value.key(), key.clone().into());
+        assert_eq!(
+            value.key().get("id").and_then(|id| id.s.clone()),
+            Some("a1".to_string())
+        );
+        assert_eq!(ShelfKey::try_from(value.key()), Ok(key));
+
+        let attrs: Attributes = value.clone().into();
+        assert_eq!(Shelf::try_from(attrs), Ok(value));
+    }
+
+    #[test]
+    fn binary_newtype() {
+        let value = Thumbnail(vec![0x89, 0x50, 0x4e, 0x47]).into_attr();
+        assert_eq!(value.b.as_deref(), Some(&[0x89, 0x50, 0x4e, 0x47][..]));
+        assert_eq!(value.s, None);
+        assert_eq!(
+            Thumbnail::from_attr(value),
+            Ok(Thumbnail(vec![0x89, 0x50, 0x4e, 0x47]))
+        );
+    }
+
+    #[test]
+    fn transparent_named_struct() {
+        let email = Email {
+            value: "jane@example.com".into(),
+        };
+        let value = email.clone().into_attr();
+        assert_eq!(value.s.as_deref(), Some("jane@example.com"));
+        assert_eq!(value.m, None);
+        assert_eq!(Email::from_attr(value), Ok(email));
+    }
+
+    #[test]
+    fn derived_global_secondary_indexes() {
+        assert_eq!(
+            Order::global_secondary_indexes(),
+            vec![GlobalSecondaryIndex {
+                index_name: "by_status".into(),
+                key_schema: vec![
+                    KeySchemaElement {
+                        attribute_name: "status".into(),
+                        key_type: "HASH".into(),
+                    },
+                    KeySchemaElement {
+                        attribute_name: "createdAt".into(),
+                        key_type: "RANGE".into(),
+                    },
+                ],
+                projection: Projection {
+                    projection_type: Some("ALL".into()),
+                    ..Projection::default()
+                },
+                ..GlobalSecondaryIndex::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn derived_create_table_input() {
+        let definition = |name: &str, attribute_type: &str| AttributeDefinition {
+            attribute_name: name.into(),
+            attribute_type: attribute_type.into(),
+        };
+        let key = |name: &str, key_type: &str| KeySchemaElement {
+            attribute_name: name.into(),
+            key_type: key_type.into(),
+        };
+        assert_eq!(
+            Order::create_table_input("orders"),
+            CreateTableInput {
+                table_name: "orders".into(),
+                key_schema: vec![key("id", "HASH")],
+                attribute_definitions: vec![
+                    definition("id", "S"),
+                    definition("status", "S"),
+                    definition("createdAt", "N"),
+                ],
+                billing_mode: Some("PAY_PER_REQUEST".into()),
+                global_secondary_indexes: Some(Order::global_secondary_indexes()),
+                ..CreateTableInput::default()
+            }
+        );
+        assert_eq!(
+            Purchase::create_table_input("purchases"),
+            CreateTableInput {
+                table_name: "purchases".into(),
+                key_schema: vec![key("customer", "HASH"), key("sk", "RANGE")],
+                attribute_definitions: vec![definition("customer", "S"), definition("sk", "S")],
+                billing_mode: Some("PAY_PER_REQUEST".into()),
+                ..CreateTableInput::default()
+            }
+        );
+        assert_eq!(
+            Reading::create_table_input("readings").attribute_definitions,
+            vec![definition("sensor", "S"), definition("at", "B")]
+        );
+        // key types are resolved through aliases, newtypes and derived enums
+        assert_eq!(
+            LedgerEntry::create_table_input("ledger").attribute_definitions,
+            vec![
+                definition("ledger", "N"),
+                definition("sequence", "N"),
+                definition("priority", "N"),
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_enum_round_trips_through_discriminants() {
+        for (priority, number) in [
+            (Priority::Low, "0"),
+            (Priority::Normal, "1"),
+            (Priority::Urgent, "10"),
+            (Priority::Critical, "11"),
+        ] {
+            let value = priority.into_attr();
+            assert_eq!(value.n.as_deref(), Some(number));
+            assert_eq!(Priority::from_attr(value), Ok(priority));
+        }
+        assert_eq!(
+            Priority::from_attr(2.into_attr()),
+            Err(dynomite::AttributeError::InvalidFormat)
+        );
+        assert_eq!(
+            Priority::from_attr("Low".to_string().into_attr()),
+            Err(dynomite::AttributeError::WrongType {
+                expected: "N",
+                found: "S"
+            })
+        );
+    }
+
+    #[test]
+    fn unit_struct_round_trips_as_empty_map() {
+        let attrs: Attributes = Marker.into();
+        assert!(attrs.is_empty());
+        assert_eq!(Marker::try_from(attrs), Ok(Marker));
+        assert_eq!(Marker.into_attr().m, Some(Attributes::new()));
+        assert_eq!(
+            Marker::from_attrs_with_remainder(dynomite::attr_map! { "ignored" => 1 })
+                .map(|(_, remainder)| remainder.len()),
+            Ok(1)
+        );
+        assert_eq!(StrictMarker::try_from(Attributes::new()), Ok(StrictMarker));
+        assert_eq!(
+            StrictMarker::try_from(dynomite::attr_map! { "unknown" => 1 }),
+            Err(dynomite::AttributeError::UnknownField {
+                name: "unknown".into()
+            })
+        );
+    }
+
+    #[test]
+    fn derived_put_if_not_exists_input() {
+        use maplit::hashmap;
+        let value = Recipe {
+            id: "test".into(),
+            servings: 1,
+        };
+        assert_eq!(
+            value.clone().put_if_not_exists_input("recipes"),
+            PutItemInput {
+                table_name: "recipes".into(),
+                item: value.into(),
+                condition_expression: Some("attribute_not_exists(#pk)".into()),
+                expression_attribute_names: Some(hashmap! {
+                    "#pk".to_string() => "RecipeId".to_string(),
+                }),
+                ..PutItemInput::default()
+            }
+        );
+    }
+
+    #[test]
+    fn derived_key_schema_matches_create_table_input() {
+        let input = Recipe::create_table_input("recipes");
+        assert_eq!(
+            Recipe::key_schema(),
+            Some((input.key_schema, input.attribute_definitions))
+        );
+    }
+
+    #[test]
+    fn derived_prefixed_keys() {
+        let user = uuid::Uuid::new_v4();
+        let membership = Membership {
+            user,
+            org: 42,
+            role: "admin".into(),
+        };
+        let attrs: Attributes = membership.clone().into();
+        assert_eq!(attrs["user"].s, Some(format!("USER#{}", user)));
+        assert_eq!(attrs["org"].s.as_deref(), Some("ORG#42"));
+        assert_eq!(membership.key(), {
+            let mut key = Attributes::new();
+            key.insert("user".into(), attrs["user"].clone());
+            key.insert("org".into(), attrs["org"].clone());
+            key
+        });
+        assert_eq!(
+            Membership::create_table_input("memberships").attribute_definitions,
+            vec![
+                AttributeDefinition {
+                    attribute_name: "user".into(),
+                    attribute_type: "S".into(),
+                },
+                AttributeDefinition {
+                    attribute_name: "org".into(),
+                    attribute_type: "S".into(),
+                },
+            ]
+        );
+        assert_eq!(Membership::from_attrs(&mut attrs.clone()), Ok(membership));
+
+        let mut unprefixed = attrs;
+        unprefixed.insert("org".into(), "42".to_string().into_attr());
+        assert_eq!(
+            Membership::from_attrs(&mut unprefixed),
+            Err(dynomite::AttributeError::InvalidFormat.in_field("org"))
+        );
+    }
+
+    #[test]
+    fn derived_put_input_increments_version() {
+        use maplit::hashmap;
+        let ledger = Ledger {
+            id: "test".into(),
+            version: 2,
+            balance: 10,
+        };
+        assert_eq!(
+            ledger.clone().put_input("ledgers"),
+            PutItemInput {
+                table_name: "ledgers".into(),
+                item: Ledger {
+                    version: 3,
+                    ..ledger.clone()
+                }
+                .into(),
+                condition_expression: Some("#version = :expected".into()),
+                expression_attribute_names: Some(hashmap! {
+                    "#version".to_string() => "rev".to_string(),
+                }),
+                expression_attribute_values: Some(hashmap! {
+                    ":expected".to_string() => 2.into_attr(),
+                }),
+                ..PutItemInput::default()
+            }
+        );
+        let input = Ledger {
+            version: 0,
+            ..ledger
+        }
+        .put_input("ledgers");
+        assert_eq!(input.item["rev"], 1.into_attr());
+        assert_eq!(
+            input.condition_expression.as_deref(),
+            Some("attribute_not_exists(#pk)")
+        );
+        assert_eq!(
+            input.expression_attribute_names,
+            Some(hashmap! { "#pk".to_string() => "id".to_string() })
+        );
+        assert_eq!(input.expression_attribute_values, None);
+    }
+
+    #[test]
+    fn derived_put_input_increments_version_generically() {
+        fn put_input<T: Item>(item: T) -> PutItemInput {
+            item.put_input("ledgers")
+        }
+        let input = put_input(Ledger {
+            id: "test".into(),
+            version: 2,
+            balance: 10,
+        });
+        assert_eq!(input.item["rev"], 3.into_attr());
+        assert_eq!(
+            input.condition_expression.as_deref(),
+            Some("#version = :expected")
+        );
+    }
+
+    #[test]
+    fn derived_global_secondary_index_key() {
+        let value = Shipment {
+            id: "test".into(),
+            carrier: "acme".into(),
+            weight: 5,
+        };
+        assert_eq!(value.key(), ShipmentKey { id: "test".into() }.into());
+        let index_key: Attributes = ShipmentByCarrierKey {
+            carrier: "acme".into(),
+            id: "test".into(),
+        }
+        .into();
+        let mut names = index_key.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["Carrier".to_string(), "id".to_string()]);
+    }
+
     #[test]
     fn to_and_from_book() {
         let value = Book {
@@ -151,6 +1087,72 @@ mod tests {
         assert_eq!(Foo::Bar, Foo::from_attr(Foo::Bar.into_attr()).unwrap());
     }
 
+    #[test]
+    fn derive_attr_other_variant() {
+        #[derive(Attribute, Debug, PartialEq)]
+        enum Role {
+            Admin,
+            #[dynomite(other)]
+            Unknown,
+        }
+        assert_eq!(
+            Role::from_attr("Moderator".to_string().into_attr()),
+            Ok(Role::Unknown)
+        );
+        assert_eq!(Role::from_attr(Role::Admin.into_attr()), Ok(Role::Admin));
+        assert_eq!(Role::Unknown.into_attr().s.as_deref(), Some("Unknown"));
+        assert!(Role::from_attr(1.into_attr()).is_err());
+    }
+
+    #[test]
+    fn derive_attr_map_key() {
+        use maplit::hashmap;
+        use std::collections::HashMap;
+
+        #[derive(Attribute, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[dynomite(map_key)]
+        enum Role {
+            #[dynomite(rename = "admin")]
+            Admin,
+            Regular,
+        }
+        let grants = hashmap! {
+            Role::Admin => vec!["write".to_string()],
+            Role::Regular => vec!["read".to_string()],
+        };
+        let value = grants.clone().into_attr();
+        assert_eq!(
+            value.m.as_ref().map(|m| {
+                let mut keys = m.keys().cloned().collect::<Vec<_>>();
+                keys.sort();
+                keys
+            }),
+            Some(vec!["Regular".to_string(), "admin".to_string()])
+        );
+        assert_eq!(HashMap::from_attr(value), Ok(grants));
+        assert_eq!(
+            HashMap::<Role, u32>::from_attr(hashmap! { "Moderator".to_string() => 1 }.into_attr()),
+            Err(dynomite::AttributeError::InvalidFormat.in_field("Moderator"))
+        );
+    }
+
+    #[test]
+    fn derive_attr_renamed_variant() {
+        #[derive(Attribute, Debug, PartialEq)]
+        enum Role {
+            #[dynomite(rename = "admin")]
+            Admin,
+            Regular,
+        }
+        assert_eq!(Role::Admin.into_attr().s.as_deref(), Some("admin"));
+        assert_eq!(Role::Regular.into_attr().s.as_deref(), Some("Regular"));
+        assert_eq!(Role::from_attr(Role::Admin.into_attr()), Ok(Role::Admin));
+        assert_eq!(
+            Role::from_attr("Admin".to_string().into_attr()),
+            Err(dynomite::AttributeError::InvalidFormat)
+        );
+    }
+
     #[test]
     fn field_rename() {
         let value = Recipe {
@@ -165,6 +1167,36 @@ mod tests {
         assert_eq!(value, Recipe::try_from(attrs).unwrap());
     }
 
+    #[test]
+    fn base64_round_trip() {
+        let value = Base64Encoded {
+            vec: b"foo".to_vec(),
+            bytes: Bytes::from("bar"),
+        };
+
+        let attrs: Attributes = value.clone().into();
+        assert_eq!(attrs["vec"].s.as_deref(), Some("Zm9v"));
+        assert_eq!(attrs["vec"].b, None);
+        assert_eq!(attrs["Bytes"].s.as_deref(), Some("YmFy"));
+
+        assert_eq!(value, Base64Encoded::try_from(attrs).unwrap());
+    }
+
+    #[test]
+    fn base64_malformed() {
+        let attrs = dynomite::attr_map! {
+            "vec" => "not base64!".to_string(),
+            "Bytes" => "YmFy".to_string()
+        };
+        assert_eq!(
+            Base64Encoded::try_from(attrs),
+            Err(dynomite::AttributeError::Path {
+                path: vec!["vec".into()],
+                source: Box::new(dynomite::AttributeError::InvalidFormat)
+            })
+        );
+    }
+
     #[test]
     fn flatten() {
         let value = FlattenRoot {
@@ -186,6 +1218,285 @@ mod tests {
         assert_eq!(value, FlattenRoot::try_from(attrs).unwrap());
     }
 
+    #[test]
+    fn struct_default_when_fields_absent() {
+        assert_eq!(
+            Shipping::from_attrs(&mut Attributes::new()),
+            Ok(Shipping::default())
+        );
+        let attrs = dynomite::attr_map! { "id" => "foo".to_string() };
+        assert_eq!(
+            Parcel::try_from(attrs),
+            Ok(Parcel {
+                id: "foo".into(),
+                shipping: Shipping::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn struct_default_fills_absent_fields_when_any_present() {
+        let attrs =
+            dynomite::attr_map! { "id" => "foo".to_string(), "carrier" => "ups".to_string() };
+        assert_eq!(
+            Parcel::try_from(attrs),
+            Ok(Parcel {
+                id: "foo".into(),
+                shipping: Shipping {
+                    carrier: "ups".into(),
+                    tracking_number: String::new(),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn phantom_fields_are_not_stored() {
+        let certificate = Certificate {
+            id: "1".into(),
+            state: std::marker::PhantomData,
+        };
+        let attrs: Attributes = certificate.clone().into();
+        assert_eq!(attrs, dynomite::attr_map! { "id" => "1" });
+        assert_eq!(Certificate::try_from(attrs), Ok(certificate));
+    }
+
+    #[test]
+    fn struct_default_reads_projections() {
+        let attrs = dynomite::attr_map! { "id" => "foo", "title" => "Projected" };
+        assert_eq!(
+            Excerpt::try_from(attrs),
+            Ok(Excerpt {
+                id: "foo".into(),
+                title: "Projected".into(),
+                pages: 100,
+                tags: vec![],
+                rating: 0,
+                shelf: Some("unshelved".into()),
+            })
+        );
+        // keys are still required
+        assert_eq!(
+            Excerpt::try_from(dynomite::attr_map! { "title" => "Projected" }),
+            Err(dynomite::AttributeError::MissingKey { name: "id".into() })
+        );
+        assert_eq!(
+            Excerpt::try_from(Attributes::new()),
+            Err(dynomite::AttributeError::MissingKey { name: "id".into() })
+        );
+    }
+
+    #[test]
+    fn struct_default_reads_flattened_fields_when_own_fields_absent() {
+        let attrs = dynomite::attr_map! { "carrier" => "ups".to_string() };
+        assert_eq!(
+            Consignment::try_from(attrs),
+            Ok(Consignment {
+                note: String::new(),
+                shipping: Shipping {
+                    carrier: "ups".into(),
+                    tracking_number: String::new(),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn presence_of_optional_fields() {
+        let mut attrs = dynomite::attr_map! {
+            "id" => "1",
+            "title" => Option::<String>::None,
+            "Summary" => "rust",
+        };
+        let (patch, presence) =
+            Patch::from_attrs_with_presence(&mut attrs).expect("patch to resolve");
+        assert_eq!(
+            patch,
+            Patch {
+                id: "1".into(),
+                title: None,
+                summary: Some("rust".into()),
+                pages: 0,
+            }
+        );
+        assert_eq!(
+            presence,
+            PatchPresence {
+                title: true,
+                summary: true,
+                pages: false,
+            }
+        );
+
+        let mut attrs = dynomite::attr_map! { "id" => "1" };
+        let (_, presence) = Patch::from_attrs_with_presence(&mut attrs).expect("patch to resolve");
+        assert_eq!(presence, PatchPresence::default());
+    }
+
+    #[test]
+    fn presence_of_private_fields() {
+        let mut attrs = dynomite::attr_map! { "id" => "1", "note" => "draft" };
+        let (_, presence) =
+            revision::Revision::from_attrs_with_presence(&mut attrs).expect("revision to resolve");
+        assert!(presence.note);
+    }
+
+    #[test]
+    fn flatten_error_names_path() {
+        let mut attrs: Attributes = FlattenRoot {
+            id: "foo".into(),
+            flat: Flattened {
+                a: true,
+                flat_nested: FlattenedNested { b: 42, c: false },
+            },
+        }
+        .into();
+        attrs.insert("b".into(), "42".to_string().into_attr());
+
+        let err = FlattenRoot::try_from(attrs).unwrap_err();
+        assert_eq!(
+            err,
+            dynomite::AttributeError::Path {
+                path: vec!["flat".into(), "flat_nested".into(), "b".into()],
+                source: Box::new(dynomite::AttributeError::WrongType {
+                    expected: "N",
+                    found: "S"
+                })
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "flat.flat_nested.b: Wrong type, expected N but found S"
+        );
+    }
+
+    #[test]
+    fn flatten_optional_struct() {
+        let customer = Customer {
+            name: "foo".into(),
+            billing: Some(Billing {
+                card: "1234".into(),
+                address: "bar".into(),
+            }),
+        };
+        let attrs: Attributes = customer.clone().into();
+        assert_eq!(
+            attrs,
+            dynomite::attr_map! {
+                "name" => "foo",
+                "card" => "1234",
+                "billingAddress" => "bar",
+            }
+        );
+        assert_eq!(Customer::try_from(attrs), Ok(customer));
+
+        let customer = Customer {
+            name: "foo".into(),
+            billing: None,
+        };
+        let attrs: Attributes = customer.clone().into();
+        assert_eq!(attrs, dynomite::attr_map! { "name" => "foo" });
+        assert_eq!(Customer::try_from(attrs), Ok(customer));
+
+        // partially present structs fail to resolve rather than being read as `None`
+        assert_eq!(
+            Customer::try_from(dynomite::attr_map! { "name" => "foo", "billingAddress" => "bar" }),
+            Err(dynomite::AttributeError::Path {
+                path: vec!["billing".into()],
+                source: Box::new(dynomite::AttributeError::MissingField {
+                    name: "card".into()
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn flatten_prefixed_array() {
+        let value = Booking {
+            id: "foo".into(),
+            slots: [
+                Slot {
+                    start: 9,
+                    label: "morning".into(),
+                },
+                Slot {
+                    start: 14,
+                    label: "afternoon".into(),
+                },
+            ],
+        };
+
+        let attrs: Attributes = value.clone().into();
+        let mut names = attrs.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "id",
+                "item_0_Start",
+                "item_0_label",
+                "item_1_Start",
+                "item_1_label"
+            ]
+        );
+
+        assert_eq!(value, Booking::try_from(attrs).unwrap());
+    }
+
+    #[test]
+    fn finalize_computes_fields() {
+        let mut attrs: Attributes = Invoice {
+            id: "foo".into(),
+            lines: InvoiceLines {
+                price: 3,
+                quantity: 4,
+            },
+            total: 0,
+        }
+        .into();
+        attrs.remove("total");
+
+        let invoice = Invoice::try_from(attrs).unwrap();
+        assert_eq!(invoice.total, 12);
+    }
+
+    #[test]
+    fn prepare_normalizes_fields() {
+        let attrs: Attributes = Account {
+            id: "foo".into(),
+            email: "Someone@Example.COM".into(),
+        }
+        .into();
+
+        assert_eq!(
+            attrs.get("email").and_then(|value| value.s.as_deref()),
+            Some("someone@example.com")
+        );
+    }
+
+    #[test]
+    fn prepare_leaves_keys_unprepared() {
+        let account = Account {
+            id: "Foo".into(),
+            email: "Someone@Example.COM".into(),
+        };
+        assert_eq!(account.key(), dynomite::attr_map! { "id" => "Foo" });
+        assert_eq!(
+            Attributes::from(account).get("id"),
+            Some(&"Foo".to_string().into_attr())
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must not modify an item's key fields")]
+    fn prepare_modifying_keys_panics() {
+        let _: Attributes = Subscriber {
+            email: "Someone@Example.COM".into(),
+        }
+        .into();
+    }
+
     #[test]
     fn additional_props() {
         let original = AdditionalPropsVerbatim {
@@ -209,6 +1520,73 @@ mod tests {
         assert!(collected.remainder.contains_key("e"));
     }
 
+    #[test]
+    fn additional_props_in_btree_map() {
+        let original = AdditionalPropsVerbatim {
+            a: true,
+            b: 42,
+            c: 43,
+            d: "foo".to_owned(),
+            e: 44,
+        };
+        let attrs: Attributes = original.clone().into();
+        let collected = RemainingPropsInBTreeMap::try_from(attrs.clone()).unwrap();
+
+        assert_eq!(collected.original_c_collector.c, original.c);
+        assert_eq!(
+            collected
+                .remainder
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect::<Vec<_>>(),
+            vec![("d", "foo".to_string().into_attr()), ("e", 44.into_attr()),]
+        );
+        assert_eq!(Attributes::from(collected), attrs);
+    }
+
+    #[test]
+    fn lenient_fields_read_either_type() {
+        let mut attrs = Attributes::new();
+        attrs.insert("number".into(), 1234.into_attr());
+        attrs.insert("balance".into(), "56".to_string().into_attr());
+        let account = LenientAccount::try_from(attrs).unwrap();
+        assert_eq!(
+            account,
+            LenientAccount {
+                number: "1234".into(),
+                balance: 56,
+            }
+        );
+
+        let attrs: Attributes = account.into();
+        assert_eq!(attrs["number"].s.as_deref(), Some("1234"));
+        assert_eq!(attrs["balance"].n.as_deref(), Some("56"));
+    }
+
+    #[test]
+    fn deny_unknown_fields_rejects_extra_attributes() {
+        let mut attrs = dynomite::attr_map! { "a" => true, "B" => 1 };
+        assert_eq!(
+            StrictProps::try_from(attrs.clone()),
+            Ok(StrictProps { a: true, b: 1 })
+        );
+        attrs.insert("c".into(), 2.into_attr());
+        attrs.insert("b".into(), 3.into_attr());
+        assert_eq!(
+            StrictProps::try_from(attrs),
+            Err(dynomite::AttributeError::UnknownField { name: "b".into() })
+        );
+    }
+
+    #[test]
+    fn deny_unknown_fields_allows_flattened_remainder() {
+        let attrs = dynomite::attr_map! { "a" => true, "c" => 1, "d" => "foo" };
+        let collected = StrictPropsWithRemainder::try_from(attrs).unwrap();
+        assert!(collected.a);
+        assert_eq!(collected.original_c_collector.c, 1);
+        assert_eq!(collected.remainder, dynomite::attr_map! { "d" => "foo" });
+    }
+
     #[test]
     fn flat_single_item_tuple_enum() {
         let original = MyEnum::Foo(Foo {
@@ -240,4 +1618,33 @@ mod tests {
         assert!(attrs.contains_key("kind"));
         assert!(attrs.contains_key("a"));
     }
+
+    #[test]
+    fn transparent_struct() {
+        let foo = Foo {
+            a: "foo".into(),
+            b: 1,
+        };
+        let wrapped = TransparentFoo { foo: foo.clone() };
+
+        let attrs: Attributes = wrapped.clone().into();
+        assert_eq!(attrs, Attributes::from(foo.clone()));
+        assert_eq!(wrapped.clone().into_attr(), foo.into_attr());
+        assert_eq!(TransparentFoo::try_from(attrs).unwrap(), wrapped);
+    }
+
+    #[test]
+    fn enum_variant_preserves_unknown_attributes() {
+        let attrs = dynomite::attr_map! {
+            "kind" => "Created".to_string(),
+            "id" => "foo".to_string(),
+            "added_later" => 42,
+        };
+
+        let event = Event::try_from(attrs.clone()).unwrap();
+        let Event::Created(created) = &event;
+        assert_eq!(created.unknown, dynomite::attr_map! { "added_later" => 42 });
+
+        assert_eq!(Attributes::from(event), attrs);
+    }
 }
@@ -1,4 +1,5 @@
 use dynomite::{attr_map, Attributes, Item};
+use std::convert::TryFrom;
 
 #[derive(Debug, Item)]
 struct Blackjack {
@@ -50,3 +51,172 @@ fn smoke_test() {
 
     assert_eq!(attrs, expected);
 }
+
+#[derive(Debug, Clone, PartialEq, Attributes)]
+struct Profile {
+    name: String,
+    #[dynomite(skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>,
+}
+
+#[test]
+fn empty_strings_are_not_skipped() {
+    let profile = Profile {
+        name: "".to_owned(),
+        nickname: Some("".to_owned()),
+    };
+
+    let attrs: Attributes = profile.clone().into();
+
+    let expected = attr_map! {
+        "name" => "".to_owned(),
+        "nickname" => "".to_owned()
+    };
+
+    assert_eq!(attrs, expected);
+    assert_eq!(Profile::try_from(attrs), Ok(profile));
+}
+
+#[test]
+fn skipped_none_is_missing_when_read_back() {
+    let profile = Profile {
+        name: "".to_owned(),
+        nickname: None,
+    };
+
+    let attrs: Attributes = profile.into();
+
+    assert_eq!(attrs, attr_map! { "name" => "".to_owned() });
+    assert_eq!(
+        Profile::try_from(attrs),
+        Err(dynomite::AttributeError::MissingField {
+            name: "nickname".to_owned()
+        })
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Attributes)]
+struct Contact {
+    email: String,
+    #[dynomite(optional)]
+    phone: Option<String>,
+    #[dynomite(optional, skip_serializing_if = "Option::is_none")]
+    fax: Option<String>,
+}
+
+#[test]
+fn optional_none_is_written_as_null() {
+    let contact = Contact {
+        email: "foo@bar.com".to_owned(),
+        phone: None,
+        fax: Some("555".to_owned()),
+    };
+
+    let attrs: Attributes = contact.clone().into();
+
+    let expected = attr_map! {
+        "email" => "foo@bar.com".to_owned(),
+        "phone" => None::<String>,
+        "fax" => "555".to_owned()
+    };
+
+    assert_eq!(attrs, expected);
+    assert_eq!(Contact::try_from(attrs), Ok(contact));
+}
+
+#[test]
+fn optional_none_is_skipped() {
+    let contact = Contact {
+        email: "foo@bar.com".to_owned(),
+        phone: Some("555".to_owned()),
+        fax: None,
+    };
+
+    let attrs: Attributes = contact.clone().into();
+
+    let expected = attr_map! {
+        "email" => "foo@bar.com".to_owned(),
+        "phone" => "555".to_owned()
+    };
+
+    assert_eq!(attrs, expected);
+    assert_eq!(Contact::try_from(attrs), Ok(contact));
+}
+
+#[test]
+fn optional_null_is_read_as_none() {
+    let attrs = attr_map! {
+        "email" => "foo@bar.com".to_owned(),
+        "phone" => None::<String>,
+        "fax" => None::<String>
+    };
+
+    assert_eq!(
+        Contact::try_from(attrs),
+        Ok(Contact {
+            email: "foo@bar.com".to_owned(),
+            phone: None,
+            fax: None,
+        })
+    );
+}
+
+#[test]
+fn optional_absent_is_read_as_none() {
+    let attrs = attr_map! {
+        "email" => "foo@bar.com".to_owned()
+    };
+
+    assert_eq!(
+        Contact::try_from(attrs),
+        Ok(Contact {
+            email: "foo@bar.com".to_owned(),
+            phone: None,
+            fax: None,
+        })
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Attributes)]
+struct Playlist {
+    name: String,
+    #[dynomite(skip_serializing_if = "Vec::is_empty")]
+    tracks: Vec<String>,
+    #[dynomite(skip_serializing_if = "Option::is_none")]
+    cover: Option<String>,
+    #[dynomite(skip_serializing_if = "is_zero")]
+    plays: u32,
+}
+
+#[test]
+fn predicates_receive_field_references() {
+    let empty = Playlist {
+        name: "empty".to_owned(),
+        tracks: vec![],
+        cover: None,
+        plays: 0,
+    };
+
+    let attrs: Attributes = empty.into();
+
+    assert_eq!(attrs, attr_map! { "name" => "empty".to_owned() });
+
+    let full = Playlist {
+        name: "full".to_owned(),
+        tracks: vec!["intro".to_owned()],
+        cover: Some("cover.png".to_owned()),
+        plays: 3,
+    };
+
+    let attrs: Attributes = full.clone().into();
+
+    let expected = attr_map! {
+        "name" => "full".to_owned(),
+        "tracks" => vec!["intro".to_owned()],
+        "cover" => "cover.png".to_owned(),
+        "plays" => 3
+    };
+
+    assert_eq!(attrs, expected);
+    assert_eq!(Playlist::try_from(attrs), Ok(full));
+}
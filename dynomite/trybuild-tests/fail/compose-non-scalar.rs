@@ -0,0 +1,10 @@
+use dynomite_derive::Attribute;
+
+#[derive(Attribute)]
+#[dynomite(compose = "ORDER#{year}#{shipped}")]
+struct OrderSortKey {
+    year: u16,
+    shipped: bool,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dynomite_derive::Item;
+
+#[derive(Item)]
+struct Order {
+    #[dynomite(partition_key)]
+    id: String,
+    total: Result<u32, String>,
+}
+
+fn main() {}
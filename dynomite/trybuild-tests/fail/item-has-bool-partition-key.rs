@@ -0,0 +1,9 @@
+use dynomite_derive::Item;
+
+#[derive(Item)]
+struct Foo {
+    #[dynomite(partition_key)]
+    key: bool,
+}
+
+fn main() {}
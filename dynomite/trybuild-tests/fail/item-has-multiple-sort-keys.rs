@@ -0,0 +1,13 @@
+use dynomite_derive::Item;
+
+#[derive(Item)]
+struct Foo {
+    #[dynomite(partition_key)]
+    key: String,
+    #[dynomite(sort_key)]
+    sort1: String,
+    #[dynomite(sort_key)]
+    sort2: String
+}
+
+fn main() {}
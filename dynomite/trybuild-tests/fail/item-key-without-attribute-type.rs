@@ -0,0 +1,14 @@
+use dynomite_derive::{Attributes, Item};
+
+#[derive(Attributes, Debug, Clone, PartialEq)]
+struct Owner {
+    name: String,
+}
+
+#[derive(Item)]
+struct Foo {
+    #[dynomite(partition_key)]
+    owner: Owner,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use dynomite_derive::Attributes;
+
+#[derive(Attributes)]
+struct Foo {
+    maybe: Option<Option<u32>>,
+}
+
+fn main() {}
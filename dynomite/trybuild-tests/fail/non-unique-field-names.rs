@@ -0,0 +1,10 @@
+use dynomite_derive::Attributes;
+
+#[derive(Attributes)]
+struct Foo {
+    name: String,
+    #[dynomite(rename = "name")]
+    title: String,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use dynomite_derive::{Attributes, Item};
+
+#[derive(Attributes)]
+struct Org {
+    name: String,
+}
+
+#[derive(Item)]
+struct Membership {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(prefix = "ORG#")]
+    org: Org,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dynomite_derive::Item;
+
+#[derive(Item)]
+struct Membership {
+    #[dynomite(partition_key)]
+    id: String,
+    #[dynomite(prefix = "ORG#")]
+    org: Option<String>,
+}
+
+fn main() {}